@@ -0,0 +1,345 @@
+use crate::adapters::geocoding::Geocoder;
+use crate::adapters::http::{self, HttpClient};
+use crate::domain::aqi::EpaRevision;
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{Location, Pollutant, Pollutants};
+use crate::domain::services::GeoDistance;
+use crate::domain::weather::Weather;
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::debug;
+
+const STATIONS_URL: &str = "http://air4thai.pcd.go.th/services/getNewAQI_JSON.php";
+
+/// Farthest a station may be from a place and still speak for it.
+const MAX_STATION_DISTANCE_KM: f64 = 50.0;
+
+/// Thailand Pollution Control Department (Air4Thai) ground stations.
+///
+/// Every location is served by the nearest station within
+/// `MAX_STATION_DISTANCE_KM`; places farther from any station are not found, so
+/// a failover moves on to the next provider. The station's Thai AQI is kept as
+/// reported, while the US AQI, which uses different breakpoints, is recomputed
+/// from the station's PM2.5 concentration.
+#[derive(Debug, Clone)]
+pub struct Air4ThaiClient {
+    http: HttpClient,
+    geocoder: Geocoder,
+    aqi_revision: EpaRevision,
+}
+
+#[derive(Debug, Deserialize)]
+struct StationsResponse {
+    stations: Vec<Station>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Station {
+    #[serde(rename = "nameEN")]
+    name_en: String,
+    #[serde(rename = "areaEN")]
+    area_en: String,
+    lat: String,
+    long: String,
+    #[serde(rename = "AQILast")]
+    aqi_last: StationReading,
+}
+
+#[derive(Debug, Deserialize)]
+struct StationReading {
+    #[serde(rename = "PM25")]
+    pm25: Option<Measurement>,
+    #[serde(rename = "PM10")]
+    pm10: Option<Measurement>,
+    #[serde(rename = "O3")]
+    o3: Option<Measurement>,
+    #[serde(rename = "NO2")]
+    no2: Option<Measurement>,
+    #[serde(rename = "SO2")]
+    so2: Option<Measurement>,
+    #[serde(rename = "CO")]
+    co: Option<Measurement>,
+    /// The station's overall Thai AQI.
+    #[serde(rename = "AQI")]
+    aqi: Option<StationIndex>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StationIndex {
+    #[serde(default)]
+    aqi: Option<String>,
+}
+
+/// Air4Thai sends every number as a string, with "-1" or "N/A" when missing.
+#[derive(Debug, Deserialize)]
+struct Measurement {
+    #[serde(default)]
+    value: Option<String>,
+}
+
+fn parse_number(raw: &Option<String>) -> Option<f64> {
+    raw.as_deref()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| *v >= 0.0)
+}
+
+impl Station {
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((
+            self.lat.trim().parse().ok()?,
+            self.long.trim().parse().ok()?,
+        ))
+    }
+
+    fn pm25(&self) -> Option<f64> {
+        parse_number(&self.aqi_last.pm25.as_ref()?.value)
+    }
+
+    fn thai_aqi(&self) -> Option<i32> {
+        parse_number(&self.aqi_last.aqi.as_ref()?.aqi).map(|aqi| aqi.round() as i32)
+    }
+
+    /// Air4Thai reports particulates in µg/m³, O₃/NO₂/SO₂ in ppb and CO in ppm.
+    fn pollutants(&self) -> Pollutants {
+        let value = |m: &Option<Measurement>| parse_number(&m.as_ref()?.value);
+        let reading = &self.aqi_last;
+        Pollutants {
+            pm25: value(&reading.pm25),
+            pm10: value(&reading.pm10),
+            o3: value(&reading.o3).map(|ppb| Pollutant::O3.ppb_to_ugm3(ppb)),
+            no2: value(&reading.no2).map(|ppb| Pollutant::No2.ppb_to_ugm3(ppb)),
+            so2: value(&reading.so2).map(|ppb| Pollutant::So2.ppb_to_ugm3(ppb)),
+            co: value(&reading.co).map(|ppm| Pollutant::Co.ppb_to_ugm3(ppm * 1000.0)),
+        }
+    }
+
+    /// The area ends with the province, e.g. "Ban Suan, Mueang, Chon Buri".
+    fn province(&self) -> String {
+        self.area_en
+            .rsplit(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+impl Air4ThaiClient {
+    pub fn new(http: HttpClient) -> Self {
+        Self {
+            geocoder: Geocoder::new(http.clone()),
+            http,
+            aqi_revision: EpaRevision::default(),
+        }
+    }
+
+    /// Breakpoint tables used to compute the US AQI from the station's PM2.5.
+    pub fn with_aqi_revision(mut self, aqi_revision: EpaRevision) -> Self {
+        self.aqi_revision = aqi_revision;
+        self
+    }
+
+    async fn fetch_stations(&self) -> Result<Vec<Station>, AirQualityError> {
+        let response: StationsResponse = http::get_json(&self.http, STATIONS_URL).await?;
+        Ok(response.stations)
+    }
+}
+
+#[async_trait]
+impl AirQualityRepository for Air4ThaiClient {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        _priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        let place = self.geocoder.resolve(location).await?;
+        let stations = self.fetch_stations().await?;
+
+        let (station, distance, pm25) =
+            nearest_station(&stations, (place.lat, place.lon), &location.name)?;
+
+        debug!(
+            "Air4Thai station '{}' is {:.1} km from {}",
+            station.name_en, distance, location.name
+        );
+
+        Ok(station_reading(station, pm25, self.aqi_revision))
+    }
+}
+
+/// The closest station to `(lat, lon)` with its distance in km and PM2.5. Only
+/// stations currently reporting PM2.5 count, since nothing else converts to US AQI.
+/// `place` is not found when that station is over `MAX_STATION_DISTANCE_KM` away.
+fn nearest_station<'a>(
+    stations: &'a [Station],
+    (lat, lon): (f64, f64),
+    place: &str,
+) -> Result<(&'a Station, f64, f64), AirQualityError> {
+    let nearest = stations
+        .iter()
+        .filter_map(|station| {
+            let pm25 = station.pm25()?;
+            let distance = GeoDistance::haversine_km((lat, lon), station.coordinates()?);
+            Some((station, distance, pm25))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .ok_or_else(|| {
+            AirQualityError::UpstreamUnavailable("no Air4Thai station reports PM2.5".to_string())
+        })?;
+    if nearest.1 > MAX_STATION_DISTANCE_KM {
+        debug!(
+            "Nearest Air4Thai station to {} is {:.0} km away",
+            place, nearest.1
+        );
+        return Err(AirQualityError::CityNotFound(place.to_string()));
+    }
+    Ok(nearest)
+}
+
+fn station_reading(station: &Station, pm25: f64, aqi_revision: EpaRevision) -> RawAirQualityData {
+    RawAirQualityData {
+        city: station.name_en.clone(),
+        state: station.province(),
+        aqi: aqi_revision.aqi_from_concentration(Pollutant::Pm25, pm25),
+        pollutants: station.pollutants(),
+        // The US AQI above is computed from PM2.5 alone.
+        main_pollutant: Some(Pollutant::Pm25),
+        aqi_cn: None,
+        aqi_th: station.thai_aqi(),
+        weather: Weather::default(),
+        source: "Air4Thai".to_string(),
+        spread: None,
+        stale_since: None,
+        cached: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stations() -> Vec<Station> {
+        let response: StationsResponse =
+            serde_json::from_str(include_str!("../../tests/fixtures/air4thai/stations.json"))
+                .unwrap();
+        response.stations
+    }
+
+    fn named<'a>(stations: &'a [Station], name: &str) -> &'a Station {
+        stations.iter().find(|s| s.name_en == name).unwrap()
+    }
+
+    #[test]
+    fn parses_stations() {
+        let stations = stations();
+        assert_eq!(stations.len(), 4);
+
+        let ban_suan = named(&stations, "Ban Suan, Mueang");
+        assert_eq!(ban_suan.coordinates(), Some((13.363611, 100.984167)));
+        assert_eq!(ban_suan.province(), "Chon Buri");
+        assert_eq!(ban_suan.pm25(), Some(36.4));
+
+        let pollutants = ban_suan.pollutants();
+        assert_eq!(pollutants.pm25, Some(36.4));
+        assert_eq!(pollutants.pm10, Some(58.0));
+        assert_eq!(pollutants.o3, Some(Pollutant::O3.ppb_to_ugm3(25.0)));
+        assert_eq!(pollutants.no2, Some(Pollutant::No2.ppb_to_ugm3(12.0)));
+        // CO comes in ppm.
+        assert_eq!(pollutants.co, Some(Pollutant::Co.ppb_to_ugm3(610.0)));
+
+        assert_eq!(named(&stations, "No Coordinates").province(), "Chon Buri");
+    }
+
+    #[test]
+    fn missing_values_are_none() {
+        let stations = stations();
+
+        // "-1" for a pollutant the station measures but has no value for.
+        assert_eq!(named(&stations, "Ban Suan, Mueang").pollutants().so2, None);
+        assert_eq!(named(&stations, "Nong Pla Lai, Mueang").pm25(), None);
+
+        // "N/A", padded numbers, and pollutants left out entirely.
+        let si_racha = named(&stations, "Thung Sukhla, Si Racha").pollutants();
+        assert_eq!(si_racha.pm10, None);
+        assert_eq!(si_racha.o3, Some(Pollutant::O3.ppb_to_ugm3(21.0)));
+        assert_eq!(si_racha.co, None);
+
+        assert_eq!(named(&stations, "No Coordinates").coordinates(), None);
+        assert_eq!(parse_number(&Some("N/A".to_string())), None);
+        assert_eq!(parse_number(&Some("-1".to_string())), None);
+        assert_eq!(parse_number(&None), None);
+        assert_eq!(parse_number(&Some("0".to_string())), Some(0.0));
+    }
+
+    #[test]
+    fn picks_the_nearest_station_reporting_pm25() {
+        let stations = stations();
+
+        // Nong Pla Lai sits right on this point but has no PM2.5.
+        let (station, distance, pm25) =
+            nearest_station(&stations, (13.36, 101.0), "Ban Suan").unwrap();
+        assert_eq!(station.name_en, "Ban Suan, Mueang");
+        assert!((1.5..2.0).contains(&distance), "{} km", distance);
+        assert_eq!(pm25, 36.4);
+
+        let (station, _, _) = nearest_station(&stations, (13.17, 100.93), "Si Racha").unwrap();
+        assert_eq!(station.name_en, "Thung Sukhla, Si Racha");
+    }
+
+    #[test]
+    fn places_far_from_every_station_are_not_found() {
+        let stations = stations();
+
+        // Chiang Mai is over 500 km from any station in the fixture.
+        assert!(matches!(
+            nearest_station(&stations, (18.79, 98.98), "Chiang Mai"),
+            Err(AirQualityError::CityNotFound(place)) if place == "Chiang Mai"
+        ));
+        // Pattaya, about 35 km south of Si Racha, is still within reach.
+        let (station, distance, _) =
+            nearest_station(&stations, (12.93, 100.88), "Pattaya").unwrap();
+        assert_eq!(station.name_en, "Thung Sukhla, Si Racha");
+        assert!(distance < MAX_STATION_DISTANCE_KM, "{} km", distance);
+    }
+
+    #[test]
+    fn no_station_without_pm25() {
+        let stations: Vec<Station> = stations()
+            .into_iter()
+            .filter(|s| s.name_en == "Nong Pla Lai, Mueang")
+            .collect();
+        assert!(matches!(
+            nearest_station(&stations, (13.36, 101.0), "Ban Suan"),
+            Err(AirQualityError::UpstreamUnavailable(_))
+        ));
+        assert!(nearest_station(&[], (13.36, 101.0), "Ban Suan").is_err());
+    }
+
+    #[test]
+    fn keeps_the_reported_thai_aqi() {
+        let stations = stations();
+        assert_eq!(named(&stations, "Ban Suan, Mueang").thai_aqi(), Some(78));
+        // Led by PM10, but still the station's overall index.
+        assert_eq!(
+            named(&stations, "Nong Pla Lai, Mueang").thai_aqi(),
+            Some(30)
+        );
+        assert_eq!(named(&stations, "No Coordinates").thai_aqi(), None);
+    }
+
+    #[test]
+    fn recomputes_the_us_aqi_from_pm25() {
+        let stations = stations();
+        let station = named(&stations, "Ban Suan, Mueang");
+
+        let raw = station_reading(station, 36.4, EpaRevision::Epa2024);
+
+        assert_eq!(raw.aqi, 103);
+        assert_eq!(raw.aqi_th, Some(78));
+        assert_eq!(raw.city, "Ban Suan, Mueang");
+        assert_eq!(raw.state, "Chon Buri");
+        assert_eq!(raw.main_pollutant, Some(Pollutant::Pm25));
+        assert_eq!(raw.source, "Air4Thai");
+    }
+}
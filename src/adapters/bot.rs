@@ -1,76 +1,235 @@
+use crate::adapters::chart::PngChartRenderer;
+use crate::domain::forecast::Forecast;
+use crate::domain::history::HistoryPeriod;
+use crate::domain::i18n::{fill, Catalogue, Locale};
 use crate::domain::models::Location;
-use crate::use_cases::CheckAirQuality;
-use crate::use_cases::check_air_quality::AirQualityRepository;
+use crate::domain::recommendation::Activity;
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::{placeholder_list, MessageTemplate};
+use crate::presentation::{
+    fill_template, render_forecast, render_reading, render_recommendation, MessageFormat,
+};
+use crate::use_cases::chat_settings::{ChatPreferences, ChatSettingsRepository};
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, ReadingRepository};
+use crate::use_cases::forecast_air_quality::ForecastRepository;
+use crate::use_cases::manage_subscriptions::SubscriptionRepository;
+use crate::use_cases::{
+    AirQualityHistory, ChartAirQuality, ChatSettings, CheckAirQuality, ForecastAirQuality,
+    ManageSubscriptions, RecommendOutdoorTime,
+};
+use chrono::{FixedOffset, Utc};
 use std::sync::Arc;
 use teloxide::prelude::*;
+use teloxide::types::{InputFile, ParseMode};
 use teloxide::utils::command::BotCommands;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::domain::models::AirQualityLevel;
 
+/// Descriptions live in each locale's catalogue, see `Catalogue::help`.
 #[derive(BotCommands, Clone)]
-#[command(rename_rule = "lowercase", description = "คำสั่งที่ใช้ได้:")]
+#[command(rename_rule = "lowercase")]
 pub enum Command {
-    #[command(description = "แสดงคำสั่งทั้งหมด")]
     Help,
-    #[command(description = "ดูคุณภาพอากาศทุกพื้นที่")]
     Pm25,
-    #[command(description = "ดูคุณภาพอากาศ เช่น /check Ban Suan")]
     Check(String),
+    Subscribe(String),
+    Unsubscribe(String),
+    Subscriptions,
+    History(String),
+    Chart(String),
+    Standard(String),
+    Lang(String),
+    Template(String),
+    Forecast(String),
+    Bestime(String),
 }
 
-pub struct BotHandler<R: AirQualityRepository + Clone + 'static> {
+pub struct BotHandler<R, S>
+where
+    R: AirQualityRepository + Clone + 'static,
+    S: SubscriptionRepository + ReadingRepository + ChatSettingsRepository + Clone + 'static,
+{
     bot: Bot,
-    checker: Arc<CheckAirQuality<R>>,
-    locations: Arc<Vec<Location>>,
+    context: BotContext<R, S>,
 }
 
-impl<R: AirQualityRepository + Clone + 'static> BotHandler<R> {
+/// Everything the command handlers need, shared by all updates.
+struct BotContext<R, S>
+where
+    R: AirQualityRepository + Clone + 'static,
+    S: SubscriptionRepository + ReadingRepository + ChatSettingsRepository + Clone + 'static,
+{
+    checker: CheckAirQuality<R>,
+    subscriptions: ManageSubscriptions<S>,
+    history: AirQualityHistory<S>,
+    charts: ChartAirQuality<S, PngChartRenderer>,
+    settings: ChatSettings<S>,
+    forecasts: Option<ForecastAirQuality<Arc<dyn ForecastRepository>>>,
+    recommender: Option<RecommendOutdoorTime<Arc<dyn ForecastRepository>, S>>,
+    locations: Vec<Location>,
+    timezone: FixedOffset,
+    format: MessageFormat,
+}
+
+impl<R, S> BotHandler<R, S>
+where
+    R: AirQualityRepository + Clone + 'static,
+    S: SubscriptionRepository + ReadingRepository + ChatSettingsRepository + Clone + 'static,
+{
     pub fn new(
         token: String,
         checker: CheckAirQuality<R>,
+        store: S,
         locations: Vec<Location>,
+        timezone: FixedOffset,
+        default_standard: AqiStandard,
+        default_locale: Locale,
     ) -> Self {
         Self {
             bot: Bot::new(token),
-            checker: Arc::new(checker),
-            locations: Arc::new(locations),
+            context: BotContext {
+                checker,
+                subscriptions: ManageSubscriptions::new(store.clone()),
+                history: AirQualityHistory::new(store.clone()),
+                settings: ChatSettings::new(store.clone(), default_standard, default_locale),
+                charts: ChartAirQuality::new(store, PngChartRenderer::new(timezone)),
+                forecasts: None,
+                recommender: None,
+                locations,
+                timezone,
+                format: MessageFormat::default(),
+            },
         }
     }
 
+    /// Template for chats that haven't written their own.
+    pub fn with_default_template(mut self, template: Option<MessageTemplate>) -> Self {
+        self.context.settings = self.context.settings.with_default_template(template);
+        self
+    }
+
+    /// Enables `/forecast`.
+    pub fn with_forecasts(
+        mut self,
+        forecasts: ForecastAirQuality<Arc<dyn ForecastRepository>>,
+    ) -> Self {
+        self.context.forecasts = Some(forecasts);
+        self
+    }
+
+    /// Enables `/bestime`.
+    pub fn with_recommender(
+        mut self,
+        recommender: RecommendOutdoorTime<Arc<dyn ForecastRepository>, S>,
+    ) -> Self {
+        self.context.recommender = Some(recommender);
+        self
+    }
+
+    /// Markup replies are rendered in; HTML unless set.
+    pub fn with_format(mut self, format: MessageFormat) -> Self {
+        self.context.format = format;
+        self
+    }
+
     pub async fn run(self) {
-        let checker = self.checker;
-        let locations = self.locations;
+        let context = Arc::new(self.context);
 
-        let handler = Update::filter_message().filter_command::<Command>().endpoint(
-            move |bot: Bot, msg: Message, cmd: Command| {
-                let checker = checker.clone();
-                let locations = locations.clone();
+        let handler = Update::filter_message()
+            .filter_command::<Command>()
+            .endpoint(move |bot: Bot, msg: Message, cmd: Command| {
+                let context = context.clone();
 
                 async move {
+                    let BotContext {
+                        checker,
+                        subscriptions,
+                        history,
+                        charts,
+                        settings,
+                        forecasts,
+                        recommender,
+                        locations,
+                        timezone,
+                        format,
+                    } = &*context;
+                    let text = settings.locale(msg.chat.id.0).await.catalogue();
+
                     match cmd {
                         Command::Help => {
-                            bot.send_message(msg.chat.id, Command::descriptions().to_string())
-                                .await?;
+                            bot.send_message(msg.chat.id, text.help).await?;
                         }
                         Command::Pm25 => {
-                            handle_pm25(&bot, &msg, &checker, &locations).await;
+                            let preferences = settings.preferences(msg.chat.id.0).await;
+                            handle_pm25(&bot, &msg, checker, locations, &preferences, *format)
+                                .await;
                         }
                         Command::Check(city) => {
                             let city = city.trim();
                             if city.is_empty() {
-                                bot.send_message(msg.chat.id, "กรุณาระบุชื่อเมือง เช่น /check Ban Suan หรือ /check 13.46,101.09")
-                                    .await?;
+                                bot.send_message(msg.chat.id, text.check_usage).await?;
                             } else {
                                 let location = parse_location_input(city);
-                                handle_check(&bot, &msg, &checker, &location).await;
+                                let preferences = settings.preferences(msg.chat.id.0).await;
+                                handle_check(&bot, &msg, checker, &location, &preferences, *format)
+                                    .await;
                             }
                         }
+                        Command::Subscribe(args) => {
+                            handle_subscribe(&bot, &msg, subscriptions, &args, text).await;
+                        }
+                        Command::Unsubscribe(args) => {
+                            handle_unsubscribe(&bot, &msg, subscriptions, &args, text).await;
+                        }
+                        Command::Subscriptions => {
+                            handle_subscriptions(&bot, &msg, subscriptions, text).await;
+                        }
+                        Command::History(args) => {
+                            handle_history(&bot, &msg, history, *timezone, *format, &args, text)
+                                .await;
+                        }
+                        Command::Chart(args) => {
+                            handle_chart(&bot, &msg, charts, &args, text).await;
+                        }
+                        Command::Standard(args) => {
+                            handle_standard(&bot, &msg, settings, &args, text).await;
+                        }
+                        Command::Lang(args) => {
+                            handle_lang(&bot, &msg, settings, &args, text).await;
+                        }
+                        Command::Template(args) => {
+                            handle_template(&bot, &msg, settings, &args, text).await;
+                        }
+                        Command::Forecast(args) => {
+                            let preferences = settings.preferences(msg.chat.id.0).await;
+                            handle_forecast(
+                                &bot,
+                                &msg,
+                                forecasts.as_ref(),
+                                &args,
+                                &preferences,
+                                *timezone,
+                                *format,
+                            )
+                            .await;
+                        }
+                        Command::Bestime(args) => {
+                            handle_bestime(
+                                &bot,
+                                &msg,
+                                recommender.as_ref(),
+                                &args,
+                                text,
+                                *timezone,
+                                *format,
+                            )
+                            .await;
+                        }
                     }
                     Ok::<(), teloxide::RequestError>(())
                 }
-            },
-        );
+            });
 
         info!("Starting Telegram bot...");
         Dispatcher::builder(self.bot, handler)
@@ -85,7 +244,10 @@ fn parse_location_input(input: &str) -> Location {
     // Check if input looks like coordinates: "13.46,101.09"
     let parts: Vec<&str> = input.split(',').collect();
     if parts.len() == 2 {
-        if let (Ok(lat), Ok(lon)) = (parts[0].trim().parse::<f64>(), parts[1].trim().parse::<f64>()) {
+        if let (Ok(lat), Ok(lon)) = (
+            parts[0].trim().parse::<f64>(),
+            parts[1].trim().parse::<f64>(),
+        ) {
             // Validate reasonable lat/lon ranges
             if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
                 return Location::from_coordinates(format!("{:.2},{:.2}", lat, lon), lat, lon);
@@ -98,6 +260,77 @@ fn parse_location_input(input: &str) -> Location {
     Location::from_city(&city, "Chon Buri", "Thailand")
 }
 
+/// Splits "<location> [level]"; the level defaults to unhealthy-for-sensitive.
+fn parse_subscribe_input(input: &str) -> Option<(Location, AirQualityLevel)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    if let Some((place, last)) = input.rsplit_once(char::is_whitespace) {
+        if let Some(level) = AirQualityLevel::from_slug(last) {
+            return Some((parse_location_input(place.trim()), level));
+        }
+    }
+
+    Some((
+        parse_location_input(input),
+        AirQualityLevel::UnhealthyForSensitive,
+    ))
+}
+
+/// Splits "<location> [24h|7d]"; the period defaults to 24 hours.
+fn parse_history_input(input: &str) -> Option<(Location, HistoryPeriod)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    if let Some((place, last)) = input.rsplit_once(char::is_whitespace) {
+        if let Some(period) = HistoryPeriod::parse(last) {
+            return Some((parse_location_input(place.trim()), period));
+        }
+    }
+
+    Some((parse_location_input(input), HistoryPeriod::Day))
+}
+
+/// Splits "<location> [hours]h", e.g. "Ban Suan 48h"; the span defaults to 24 hours.
+fn parse_forecast_input(input: &str) -> Option<(Location, u32)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    if let Some((place, last)) = input.rsplit_once(char::is_whitespace) {
+        let hours = last
+            .strip_suffix(['h', 'H'])
+            .and_then(|hours| hours.parse::<u32>().ok())
+            .filter(|hours| (1..=Forecast::MAX_HOURS).contains(hours));
+        if let Some(hours) = hours {
+            return Some((parse_location_input(place.trim()), hours));
+        }
+    }
+
+    Some((parse_location_input(input), 24))
+}
+
+/// Splits "<location> [activity]", e.g. "Ban Suan cycling"; running unless named.
+fn parse_bestime_input(input: &str) -> Option<(Location, Activity)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    if let Some((place, last)) = input.rsplit_once(char::is_whitespace) {
+        if let Some(activity) = Activity::parse(last) {
+            return Some((parse_location_input(place.trim()), activity));
+        }
+    }
+
+    Some((parse_location_input(input), Activity::default()))
+}
+
 fn map_thai_to_english(input: &str) -> String {
     let mappings = [
         ("พานทอง", "Phan Thong"),
@@ -126,9 +359,26 @@ async fn handle_pm25<R: AirQualityRepository>(
     msg: &Message,
     checker: &CheckAirQuality<R>,
     locations: &[Location],
+    preferences: &ChatPreferences,
+    format: MessageFormat,
 ) {
     for location in locations {
-        handle_check(bot, msg, checker, location).await;
+        handle_check(bot, msg, checker, location, preferences, format).await;
+    }
+}
+
+/// Sends `message` in the markup it was rendered for.
+async fn send_formatted(
+    bot: &Bot,
+    msg: &Message,
+    message: String,
+    format: MessageFormat,
+) -> Result<Message, teloxide::RequestError> {
+    let request = bot.send_message(msg.chat.id, message);
+    match format {
+        MessageFormat::Html => request.parse_mode(ParseMode::Html).await,
+        MessageFormat::MarkdownV2 => request.parse_mode(ParseMode::MarkdownV2).await,
+        MessageFormat::PlainText => request.await,
     }
 }
 
@@ -137,45 +387,555 @@ async fn handle_check<R: AirQualityRepository>(
     msg: &Message,
     checker: &CheckAirQuality<R>,
     location: &Location,
+    preferences: &ChatPreferences,
+    format: MessageFormat,
 ) {
-    match checker.execute(location.clone()).await {
+    let standard = preferences.standard;
+    let text = preferences.locale.catalogue();
+    match checker
+        .execute(location.clone(), FetchPriority::Interactive)
+        .await
+    {
         Ok(data) => {
-            let level = AirQualityLevel::from_aqi(data.aqi);
-            let (city, state) = data.location.city_state();
-            let location_str = if state.is_empty() {
-                city
-            } else {
-                format!("{}, {}", city, state)
+            let Some(message) = render_reading(
+                format.renderer(),
+                preferences.template.as_ref(),
+                &data,
+                standard,
+                text,
+                Utc::now(),
+            ) else {
+                warn!(
+                    "No {} index for {} (AQI {})",
+                    standard.slug(),
+                    location.name,
+                    data.aqi
+                );
+                let error_msg = fill(text.invalid_reading, &[("place", &location.name)]);
+                if let Err(e) = bot.send_message(msg.chat.id, error_msg).await {
+                    error!("Failed to send error message: {}", e);
+                }
+                return;
             };
-            let message = format!(
-                "{} <b>{}</b>\n\n\
-                📍 {}\n\
-                AQI <b>{}</b> · PM2.5 {} µg/m³\n\
-                🌡️ {}°C · 💧 {}%\n\n\
-                {}",
-                level.emoji(),
-                level.thai_description(),
-                location_str,
-                data.aqi,
-                data.pm25,
-                data.temperature,
-                data.humidity,
-                level.health_warning(),
-            );
 
-            if let Err(e) = bot
-                .send_message(msg.chat.id, message)
-                .parse_mode(teloxide::types::ParseMode::Html)
-                .await
-            {
+            if let Err(e) = send_formatted(bot, msg, message, format).await {
                 error!("Failed to send message: {}", e);
             }
         }
         Err(e) => {
-            let error_msg = format!("❌ ไม่สามารถดึงข้อมูล {} ได้: {}", location.name, e);
+            warn!("Failed to check {}: {}", location.name, e);
+            let error_msg = fill(
+                text.check_failed,
+                &[("place", &location.name), ("reason", &text.error(&e))],
+            );
             if let Err(e) = bot.send_message(msg.chat.id, error_msg).await {
                 error!("Failed to send error message: {}", e);
             }
         }
     }
 }
+
+async fn handle_subscribe<S: SubscriptionRepository>(
+    bot: &Bot,
+    msg: &Message,
+    subscriptions: &ManageSubscriptions<S>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let reply = match parse_subscribe_input(args) {
+        None => {
+            let levels: Vec<&str> = AirQualityLevel::ALL.iter().map(|l| l.slug()).collect();
+            fill(text.subscribe_usage, &[("levels", &levels.join(", "))])
+        }
+        Some((location, level)) => {
+            match subscriptions
+                .subscribe(msg.chat.id.0, location, level)
+                .await
+            {
+                Ok(subscription) => fill(
+                    text.subscribed,
+                    &[
+                        ("place", &subscription.location.name),
+                        ("level", &level_label(level, text)),
+                    ],
+                ),
+                Err(e) => {
+                    error!("Failed to save subscription: {:#}", e);
+                    text.subscribe_failed.to_string()
+                }
+            }
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_unsubscribe<S: SubscriptionRepository>(
+    bot: &Bot,
+    msg: &Message,
+    subscriptions: &ManageSubscriptions<S>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let args = args.trim();
+    let location = (!args.is_empty()).then(|| parse_location_input(args));
+
+    let reply = match subscriptions
+        .unsubscribe(msg.chat.id.0, location.as_ref())
+        .await
+    {
+        Ok(0) => text.unsubscribe_none.to_string(),
+        Ok(removed) => fill(text.unsubscribed, &[("count", &removed)]),
+        Err(e) => {
+            error!("Failed to delete subscriptions: {:#}", e);
+            text.unsubscribe_failed.to_string()
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_subscriptions<S: SubscriptionRepository>(
+    bot: &Bot,
+    msg: &Message,
+    subscriptions: &ManageSubscriptions<S>,
+    text: &Catalogue,
+) {
+    let reply = match subscriptions.list(msg.chat.id.0).await {
+        Ok(list) if list.is_empty() => text.subscriptions_empty.to_string(),
+        Ok(list) => {
+            let lines: Vec<String> = list
+                .iter()
+                .map(|s| {
+                    fill(
+                        text.subscription_item,
+                        &[
+                            ("place", &s.location.name),
+                            ("level", &level_label(s.min_level, text)),
+                        ],
+                    )
+                })
+                .collect();
+            format!("{}\n{}", text.subscriptions_header, lines.join("\n"))
+        }
+        Err(e) => {
+            error!("Failed to load subscriptions: {:#}", e);
+            text.subscriptions_failed.to_string()
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_history<S: ReadingRepository>(
+    bot: &Bot,
+    msg: &Message,
+    history: &AirQualityHistory<S>,
+    timezone: FixedOffset,
+    format: MessageFormat,
+    args: &str,
+    text: &Catalogue,
+) {
+    let Some((location, period)) = parse_history_input(args) else {
+        if let Err(e) = bot.send_message(msg.chat.id, text.history_usage).await {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+
+    let r = format.renderer();
+    let reply = match history.summarize(&location, period, Utc::now()).await {
+        Ok(Some(summary)) => {
+            let (worst_at, worst_aqi) = summary.worst_hour;
+            let worst_at = worst_at.with_timezone(&timezone).format("%d/%m %H:00");
+            fill_template(
+                r,
+                text.history_summary,
+                &[
+                    ("place", &r.bold(&location.name)),
+                    ("period", &r.escape(text.period(period))),
+                    ("aqi_min", &r.escape(&format!("{:.0}", summary.aqi.min))),
+                    ("aqi_max", &r.escape(&format!("{:.0}", summary.aqi.max))),
+                    (
+                        "aqi_average",
+                        &r.escape(&format!("{:.0}", summary.aqi.average)),
+                    ),
+                    ("pm25_min", &r.escape(&format!("{:.1}", summary.pm25.min))),
+                    ("pm25_max", &r.escape(&format!("{:.1}", summary.pm25.max))),
+                    (
+                        "pm25_average",
+                        &r.escape(&format!("{:.1}", summary.pm25.average)),
+                    ),
+                    ("worst_at", &r.escape(&worst_at.to_string())),
+                    ("worst_aqi", &r.escape(&worst_aqi.to_string())),
+                    ("trend", &r.escape(text.trend(summary.trend))),
+                    ("readings", &r.escape(&summary.readings.to_string())),
+                ],
+            )
+        }
+        Ok(None) => fill_template(r, text.no_history, &[("place", &r.escape(&location.name))]),
+        Err(e) => {
+            error!("Failed to load history: {:#}", e);
+            r.escape(text.history_failed)
+        }
+    };
+
+    if let Err(e) = send_formatted(bot, msg, reply, format).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_chart<S: ReadingRepository>(
+    bot: &Bot,
+    msg: &Message,
+    charts: &ChartAirQuality<S, PngChartRenderer>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let Some((location, period)) = parse_history_input(args) else {
+        if let Err(e) = bot.send_message(msg.chat.id, text.chart_usage).await {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+
+    let result = match charts.execute(&location, period, Utc::now()).await {
+        Ok(Some(png)) => {
+            let caption = fill(
+                text.chart_caption,
+                &[("place", &location.name), ("period", &text.period(period))],
+            );
+            bot.send_photo(msg.chat.id, InputFile::memory(png).file_name("aqi.png"))
+                .caption(caption)
+                .await
+                .map(|_| ())
+        }
+        Ok(None) => {
+            let reply = fill(text.no_history, &[("place", &location.name)]);
+            bot.send_message(msg.chat.id, reply).await.map(|_| ())
+        }
+        Err(e) => {
+            error!("Failed to render chart: {:#}", e);
+            bot.send_message(msg.chat.id, text.chart_failed)
+                .await
+                .map(|_| ())
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Failed to send chart: {}", e);
+    }
+}
+
+async fn handle_forecast(
+    bot: &Bot,
+    msg: &Message,
+    forecasts: Option<&ForecastAirQuality<Arc<dyn ForecastRepository>>>,
+    args: &str,
+    preferences: &ChatPreferences,
+    timezone: FixedOffset,
+    format: MessageFormat,
+) {
+    let text = preferences.locale.catalogue();
+    let Some(forecasts) = forecasts else {
+        if let Err(e) = bot
+            .send_message(msg.chat.id, text.forecast_unavailable)
+            .await
+        {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+    let Some((location, hours)) = parse_forecast_input(args) else {
+        if let Err(e) = bot.send_message(msg.chat.id, text.forecast_usage).await {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+
+    let result = match forecasts.execute(location.clone(), hours).await {
+        Ok(forecast) => {
+            let message = render_forecast(
+                format.renderer(),
+                &forecast,
+                preferences.standard,
+                text,
+                timezone,
+            );
+            send_formatted(bot, msg, message, format).await
+        }
+        Err(e) => {
+            warn!("Failed to forecast {}: {}", location.name, e);
+            let reply = fill(
+                text.forecast_failed,
+                &[("place", &location.name), ("reason", &text.error(&e))],
+            );
+            bot.send_message(msg.chat.id, reply).await
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Failed to send forecast: {}", e);
+    }
+}
+
+async fn handle_bestime<S: ReadingRepository>(
+    bot: &Bot,
+    msg: &Message,
+    recommender: Option<&RecommendOutdoorTime<Arc<dyn ForecastRepository>, S>>,
+    args: &str,
+    text: &Catalogue,
+    timezone: FixedOffset,
+    format: MessageFormat,
+) {
+    let Some(recommender) = recommender else {
+        if let Err(e) = bot
+            .send_message(msg.chat.id, text.forecast_unavailable)
+            .await
+        {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+    let Some((location, activity)) = parse_bestime_input(args) else {
+        let activities = Activity::ALL.map(|activity| activity.slug()).join(", ");
+        let reply = fill(text.bestime_usage, &[("activities", &activities)]);
+        if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+            error!("Failed to send message: {}", e);
+        }
+        return;
+    };
+
+    let result = match recommender
+        .execute(location.clone(), activity, Utc::now())
+        .await
+    {
+        Ok(recommendation) => {
+            let message = render_recommendation(format.renderer(), &recommendation, text, timezone);
+            send_formatted(bot, msg, message, format).await
+        }
+        Err(e) => {
+            warn!("Failed to recommend a time in {}: {}", location.name, e);
+            let reply = fill(
+                text.bestime_failed,
+                &[("place", &location.name), ("reason", &text.error(&e))],
+            );
+            bot.send_message(msg.chat.id, reply).await
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Failed to send recommendation: {}", e);
+    }
+}
+
+async fn handle_standard<S: ChatSettingsRepository>(
+    bot: &Bot,
+    msg: &Message,
+    settings: &ChatSettings<S>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let choices: Vec<String> = AqiStandard::ALL
+        .iter()
+        .map(|s| format!("• {} — {}", s.slug(), text.standard_name(*s)))
+        .collect();
+    let choices = choices.join("\n");
+
+    let args = args.trim();
+    let reply = if args.is_empty() {
+        let current = settings.aqi_standard(msg.chat.id.0).await;
+        fill(
+            text.standard_current,
+            &[
+                ("standard", &text.standard_name(current)),
+                ("choices", &choices),
+            ],
+        )
+    } else {
+        match AqiStandard::from_slug(args) {
+            None => fill(
+                text.standard_unknown,
+                &[("input", &args), ("choices", &choices)],
+            ),
+            Some(standard) => match settings.set_aqi_standard(msg.chat.id.0, standard).await {
+                Ok(()) => fill(
+                    text.standard_saved,
+                    &[("standard", &text.standard_name(standard))],
+                ),
+                Err(e) => {
+                    error!("Failed to save AQI standard: {:#}", e);
+                    text.standard_failed.to_string()
+                }
+            },
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_lang<S: ChatSettingsRepository>(
+    bot: &Bot,
+    msg: &Message,
+    settings: &ChatSettings<S>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let choices: Vec<String> = Locale::ALL
+        .iter()
+        .map(|l| format!("• {} — {}", l.slug(), l.catalogue().language_name))
+        .collect();
+    let choices = choices.join("\n");
+
+    let args = args.trim();
+    let reply = if args.is_empty() {
+        fill(
+            text.language_current,
+            &[("language", &text.language_name), ("choices", &choices)],
+        )
+    } else {
+        match Locale::from_slug(args) {
+            None => fill(
+                text.language_unknown,
+                &[("input", &args), ("choices", &choices)],
+            ),
+            Some(locale) => match settings.set_locale(msg.chat.id.0, locale).await {
+                Ok(()) => locale.catalogue().language_saved.to_string(),
+                Err(e) => {
+                    error!("Failed to save language: {:#}", e);
+                    text.language_failed.to_string()
+                }
+            },
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+async fn handle_template<S: ChatSettingsRepository>(
+    bot: &Bot,
+    msg: &Message,
+    settings: &ChatSettings<S>,
+    args: &str,
+    text: &Catalogue,
+) {
+    let placeholders = placeholder_list();
+    let args = args.trim();
+    let reply = if args.is_empty() {
+        match settings.template(msg.chat.id.0).await {
+            Some(template) => fill(
+                text.template_current,
+                &[
+                    ("template", &template.as_str()),
+                    ("placeholders", &placeholders),
+                ],
+            ),
+            None => fill(text.template_builtin, &[("placeholders", &placeholders)]),
+        }
+    } else if args.eq_ignore_ascii_case("reset") {
+        match settings.set_template(msg.chat.id.0, None).await {
+            Ok(()) => text.template_reset.to_string(),
+            Err(e) => {
+                error!("Failed to reset template: {:#}", e);
+                text.template_failed.to_string()
+            }
+        }
+    } else {
+        match MessageTemplate::parse(args) {
+            Err(e) => fill(
+                text.template_invalid,
+                &[
+                    ("reason", &text.template_error(&e)),
+                    ("placeholders", &placeholders),
+                ],
+            ),
+            Ok(template) => match settings.set_template(msg.chat.id.0, Some(&template)).await {
+                Ok(()) => text.template_saved.to_string(),
+                Err(e) => {
+                    error!("Failed to save template: {:#}", e);
+                    text.template_failed.to_string()
+                }
+            },
+        }
+    };
+
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!("Failed to send message: {}", e);
+    }
+}
+
+/// A level as "🟠 Unhealthy for sensitive groups".
+fn level_label(level: AirQualityLevel, text: &Catalogue) -> String {
+    format!("{} {}", level.emoji(), text.level(level).name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscribe(input: &str) -> Option<(String, AirQualityLevel)> {
+        parse_subscribe_input(input).map(|(location, level)| (location.key(), level))
+    }
+
+    fn city(name: &str) -> String {
+        Location::from_city(name, "Chon Buri", "Thailand").key()
+    }
+
+    #[test]
+    fn subscribing_to_a_city_defaults_to_sensitive() {
+        assert_eq!(
+            subscribe("  Ban Suan "),
+            Some((city("Ban Suan"), AirQualityLevel::UnhealthyForSensitive))
+        );
+        assert_eq!(
+            subscribe("ศรีราชา"),
+            Some((city("Si Racha"), AirQualityLevel::UnhealthyForSensitive))
+        );
+        assert_eq!(subscribe("   "), None);
+    }
+
+    #[test]
+    fn subscribing_with_a_level() {
+        assert_eq!(
+            subscribe("Ban Suan unhealthy"),
+            Some((city("Ban Suan"), AirQualityLevel::Unhealthy))
+        );
+        assert_eq!(
+            subscribe("บ้านสวน  Very-Unhealthy"),
+            Some((city("Ban Suan"), AirQualityLevel::VeryUnhealthy))
+        );
+        assert_eq!(
+            subscribe("13.36,100.98 good"),
+            Some((
+                Location::from_coordinates("13.36,100.98".to_string(), 13.36, 100.98).key(),
+                AirQualityLevel::Good
+            ))
+        );
+    }
+
+    #[test]
+    fn an_unknown_level_is_read_as_part_of_the_place() {
+        assert_eq!(
+            subscribe("Ban Suan terrible"),
+            Some((
+                city("Ban Suan terrible"),
+                AirQualityLevel::UnhealthyForSensitive
+            ))
+        );
+        // A level on its own is a place name, not a level for no place.
+        assert_eq!(
+            subscribe("hazardous"),
+            Some((city("hazardous"), AirQualityLevel::UnhealthyForSensitive))
+        );
+    }
+}
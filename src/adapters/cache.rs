@@ -0,0 +1,399 @@
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::Location;
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+/// Read-through cache in front of a provider.
+///
+/// Readings younger than `ttl` are served without an upstream call, and
+/// concurrent lookups for the same place wait for a single upstream request.
+/// When the provider fails, a cached reading up to `max_stale` old is served
+/// instead, marked with the time it was fetched. Places nobody asked about
+/// since their reading expired are forgotten, so arbitrary `/check` input
+/// can't grow the cache without limit.
+pub struct CachingRepository<R: AirQualityRepository> {
+    inner: Arc<R>,
+    ttl: Duration,
+    max_stale: Duration,
+    slots: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<Slot>>>>>,
+}
+
+impl<R: AirQualityRepository> Clone for CachingRepository<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            ttl: self.ttl,
+            max_stale: self.max_stale,
+            slots: self.slots.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    cached: Option<(DateTime<Utc>, RawAirQualityData)>,
+    /// Outcome of the latest upstream request, if it failed.
+    last_error: Option<AirQualityError>,
+}
+
+impl<R: AirQualityRepository> CachingRepository<R> {
+    pub fn new(inner: R, ttl: Duration, max_stale: Duration) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            ttl,
+            max_stale,
+            slots: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn slot(&self, location: &Location) -> Arc<tokio::sync::Mutex<Slot>> {
+        let mut slots = self.slots.lock().unwrap();
+        let key = location.key();
+        if !slots.contains_key(&key) {
+            self.evict_expired(&mut slots, Utc::now());
+        }
+        slots.entry(key).or_default().clone()
+    }
+
+    /// Drops slots no request is using whose reading is too old to serve even
+    /// as stale, or that never got one.
+    fn evict_expired(
+        &self,
+        slots: &mut HashMap<String, Arc<tokio::sync::Mutex<Slot>>>,
+        now: DateTime<Utc>,
+    ) {
+        let keep_for = self.ttl.max(self.max_stale);
+        slots.retain(|_, slot| {
+            if Arc::strong_count(slot) > 1 {
+                return true;
+            }
+            match slot.try_lock() {
+                Ok(slot) => slot
+                    .cached
+                    .as_ref()
+                    .is_some_and(|(fetched_at, _)| now - *fetched_at <= keep_for),
+                Err(_) => true,
+            }
+        });
+    }
+
+    fn serve_stale(
+        &self,
+        slot: &Slot,
+        location: &Location,
+        now: DateTime<Utc>,
+    ) -> Option<RawAirQualityData> {
+        let (fetched_at, data) = slot.cached.as_ref()?;
+        if now - *fetched_at > self.max_stale {
+            return None;
+        }
+        warn!(
+            "Serving {} reading from {} minutes ago",
+            location.name,
+            (now - *fetched_at).num_minutes()
+        );
+        Some(RawAirQualityData {
+            stale_since: Some(*fetched_at),
+            cached: true,
+            ..data.clone()
+        })
+    }
+}
+
+#[async_trait]
+impl<R: AirQualityRepository> AirQualityRepository for CachingRepository<R> {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        let slot = self.slot(location);
+        // Whoever holds the lock is already asking upstream; wait for their answer.
+        let (mut slot, waited) = match slot.try_lock() {
+            Ok(guard) => (guard, false),
+            Err(_) => (slot.lock().await, true),
+        };
+
+        let now = Utc::now();
+        if let Some((fetched_at, data)) = &slot.cached {
+            if now - *fetched_at < self.ttl {
+                debug!("Cache hit for {}", location.name);
+                return Ok(cached(data));
+            }
+        }
+        if waited {
+            // The request we waited for has just finished; share its outcome.
+            if let Some(error) = &slot.last_error {
+                return self
+                    .serve_stale(&slot, location, now)
+                    .ok_or_else(|| error.clone());
+            }
+            if let Some((_, data)) = &slot.cached {
+                return Ok(cached(data));
+            }
+        }
+
+        match self.inner.get_air_quality(location, priority).await {
+            Ok(data) => {
+                slot.cached = Some((Utc::now(), data.clone()));
+                slot.last_error = None;
+                Ok(data)
+            }
+            Err(e) => {
+                slot.last_error = Some(e.clone());
+                self.serve_stale(&slot, location, now).ok_or(e)
+            }
+        }
+    }
+}
+
+/// A copy of a reading another request fetched.
+fn cached(data: &RawAirQualityData) -> RawAirQualityData {
+    RawAirQualityData {
+        cached: true,
+        ..data.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Pollutants;
+    use crate::domain::weather::Weather;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Succeeds for every place except those named "Nowhere…", counting calls.
+    /// Each call takes `delay`, and fails while `failing` is set.
+    #[derive(Default)]
+    struct CountingRepository {
+        calls: AtomicUsize,
+        delay: std::time::Duration,
+        failing: AtomicBool,
+    }
+
+    #[async_trait]
+    impl AirQualityRepository for CountingRepository {
+        async fn get_air_quality(
+            &self,
+            location: &Location,
+            _priority: FetchPriority,
+        ) -> Result<RawAirQualityData, AirQualityError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(AirQualityError::UpstreamUnavailable("down".to_string()));
+            }
+            if location.name.starts_with("Nowhere") {
+                return Err(AirQualityError::CityNotFound(location.name.clone()));
+            }
+            Ok(RawAirQualityData {
+                city: location.name.clone(),
+                state: "Chon Buri".to_string(),
+                aqi: 42,
+                pollutants: Pollutants::default(),
+                main_pollutant: None,
+                aqi_cn: None,
+                aqi_th: None,
+                weather: Weather::default(),
+                source: "Test".to_string(),
+                spread: None,
+                stale_since: None,
+                cached: false,
+            })
+        }
+    }
+
+    fn city(name: &str) -> Location {
+        Location::from_city(name, "Chon Buri", "Thailand")
+    }
+
+    fn cache(ttl: Duration, max_stale: Duration) -> CachingRepository<CountingRepository> {
+        CachingRepository::new(CountingRepository::default(), ttl, max_stale)
+    }
+
+    fn slow_cache(ttl: Duration, max_stale: Duration) -> CachingRepository<CountingRepository> {
+        let inner = CountingRepository {
+            delay: std::time::Duration::from_millis(50),
+            ..CountingRepository::default()
+        };
+        CachingRepository::new(inner, ttl, max_stale)
+    }
+
+    async fn concurrently(
+        cache: &CachingRepository<CountingRepository>,
+        requests: usize,
+    ) -> Vec<Result<RawAirQualityData, AirQualityError>> {
+        let location = city("Ban Suan");
+        futures::future::join_all(
+            (0..requests).map(|_| cache.get_air_quality(&location, FetchPriority::Scheduled)),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn serves_fresh_readings_from_the_cache() {
+        let cache = cache(Duration::minutes(10), Duration::hours(1));
+        for _ in 0..3 {
+            let data = cache
+                .get_air_quality(&city("Ban Suan"), FetchPriority::Interactive)
+                .await
+                .unwrap();
+            assert_eq!(data.aqi, 42);
+        }
+        assert_eq!(cache.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn forgets_places_that_were_never_found() {
+        let cache = cache(Duration::minutes(10), Duration::hours(1));
+        for i in 0..100 {
+            let result = cache
+                .get_air_quality(&city(&format!("Nowhere {}", i)), FetchPriority::Interactive)
+                .await;
+            assert!(result.is_err());
+        }
+        assert_eq!(cache.slots.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_readings_that_can_still_be_served() {
+        let cache = cache(Duration::minutes(10), Duration::hours(1));
+        for name in ["Ban Suan", "Si Racha", "Phan Thong"] {
+            cache
+                .get_air_quality(&city(name), FetchPriority::Interactive)
+                .await
+                .unwrap();
+        }
+        assert_eq!(cache.slots.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn evicts_readings_past_max_stale() {
+        let cache = cache(Duration::milliseconds(1), Duration::milliseconds(1));
+        for i in 0..20 {
+            cache
+                .get_air_quality(&city(&format!("Town {}", i)), FetchPriority::Interactive)
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(3)).await;
+        }
+        assert_eq!(cache.slots.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn marks_readings_it_did_not_fetch() {
+        let cache = cache(Duration::minutes(10), Duration::hours(1));
+        let location = city("Ban Suan");
+        let first = cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .unwrap();
+        let second = cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .unwrap();
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(second.stale_since, None);
+    }
+
+    #[tokio::test]
+    async fn concurrent_lookups_share_one_upstream_call() {
+        let cache = slow_cache(Duration::minutes(10), Duration::hours(1));
+        let results = concurrently(&cache, 10).await;
+
+        assert_eq!(cache.inner.calls.load(Ordering::SeqCst), 1);
+        let fetched: Vec<bool> = results
+            .into_iter()
+            .map(|result| !result.unwrap().cached)
+            .collect();
+        assert_eq!(fetched.iter().filter(|fetched| **fetched).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_lookups_share_one_failure() {
+        let cache = slow_cache(Duration::minutes(10), Duration::hours(1));
+        cache.inner.failing.store(true, Ordering::SeqCst);
+        let results = concurrently(&cache, 5).await;
+
+        assert_eq!(cache.inner.calls.load(Ordering::SeqCst), 1);
+        for result in results {
+            assert!(matches!(
+                result,
+                Err(AirQualityError::UpstreamUnavailable(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_stale_readings_until_max_stale() {
+        let cache = cache(Duration::milliseconds(20), Duration::milliseconds(300));
+        let location = city("Ban Suan");
+        let before = Utc::now();
+        cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .unwrap();
+        let after = Utc::now();
+
+        cache.inner.failing.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+        let stale = cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .unwrap();
+        let fetched_at = stale.stale_since.unwrap();
+        assert!(before <= fetched_at && fetched_at <= after);
+        assert!(stale.cached);
+        assert_eq!(stale.aqi, 42);
+
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        let result = cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AirQualityError::UpstreamUnavailable(_))
+        ));
+        assert_eq!(cache.inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn requests_that_waited_on_a_failure_share_the_stale_reading() {
+        let cache = slow_cache(Duration::milliseconds(20), Duration::hours(1));
+        concurrently(&cache, 1).await.pop().unwrap().unwrap();
+
+        cache.inner.failing.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+        let results = concurrently(&cache, 5).await;
+
+        // One call for the first reading, one for the failed refresh.
+        assert_eq!(cache.inner.calls.load(Ordering::SeqCst), 2);
+        for result in results {
+            assert!(result.unwrap().stale_since.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn recovers_once_the_provider_does() {
+        let cache = cache(Duration::milliseconds(20), Duration::hours(1));
+        let location = city("Ban Suan");
+        cache.inner.failing.store(true, Ordering::SeqCst);
+        assert!(cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .is_err());
+
+        cache.inner.failing.store(false, Ordering::SeqCst);
+        let data = cache
+            .get_air_quality(&location, FetchPriority::Interactive)
+            .await
+            .unwrap();
+        assert_eq!(data.stale_since, None);
+        assert!(!data.cached);
+    }
+}
@@ -0,0 +1,446 @@
+use crate::domain::models::{AirQualityData, AirQualityLevel};
+use crate::use_cases::chart_air_quality::ChartRenderer;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, DurationRound, FixedOffset, Timelike, Utc};
+
+const WIDTH: i32 = 800;
+const HEIGHT: i32 = 400;
+const MARGIN_LEFT: i32 = 48;
+const MARGIN_RIGHT: i32 = 16;
+const MARGIN_TOP: i32 = 16;
+const MARGIN_BOTTOM: i32 = 32;
+
+const WHITE: (u8, u8, u8) = (255, 255, 255);
+const AXIS: (u8, u8, u8) = (60, 60, 60);
+const GRID: (u8, u8, u8) = (200, 200, 200);
+const LINE: (u8, u8, u8) = (33, 33, 33);
+
+/// Upper AQI bound of each level that gets a grid line and label.
+const LEVEL_BOUNDARIES: [i32; 5] = [50, 100, 150, 200, 300];
+
+/// Draws AQI history as a PNG bar chart over level-coloured bands.
+///
+/// Everything is rasterized in-process with a built-in digit font, so no
+/// system fonts or external chart service are needed.
+#[derive(Debug, Clone)]
+pub struct PngChartRenderer {
+    timezone: FixedOffset,
+}
+
+impl PngChartRenderer {
+    pub fn new(timezone: FixedOffset) -> Self {
+        Self { timezone }
+    }
+}
+
+impl ChartRenderer for PngChartRenderer {
+    fn render(
+        &self,
+        readings: &[(DateTime<Utc>, AirQualityData)],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<u8>> {
+        let max_aqi = readings.iter().map(|(_, d)| d.aqi).max().unwrap_or(0);
+        let plot = Plot::new(from, to, y_max_for(max_aqi));
+        let mut canvas = Canvas::new(WIDTH, HEIGHT);
+
+        self.draw_bands(&mut canvas, &plot);
+        self.draw_bars(&mut canvas, &plot, readings);
+        self.draw_line(&mut canvas, &plot, readings);
+        self.draw_axes(&mut canvas, &plot);
+
+        canvas.encode_png()
+    }
+}
+
+impl PngChartRenderer {
+    fn draw_bands(&self, canvas: &mut Canvas, plot: &Plot) {
+        let mut lower = 0;
+        for level in AirQualityLevel::ALL {
+            let upper = level.aqi_range().1.min(plot.y_max);
+            if lower >= plot.y_max {
+                break;
+            }
+            canvas.fill_rect(
+                plot.left,
+                plot.y(upper),
+                plot.right,
+                plot.y(lower),
+                tint(level.rgb(), 0.25),
+            );
+            lower = upper;
+        }
+
+        for boundary in LEVEL_BOUNDARIES.into_iter().filter(|b| *b < plot.y_max) {
+            let y = plot.y(boundary);
+            canvas.line(plot.left, y, plot.right, y, GRID);
+        }
+    }
+
+    /// One bar per bucket (an hour for a day, four hours for a week), coloured by
+    /// the level of the bucket's average AQI.
+    fn draw_bars(
+        &self,
+        canvas: &mut Canvas,
+        plot: &Plot,
+        readings: &[(DateTime<Utc>, AirQualityData)],
+    ) {
+        let bucket = if plot.to - plot.from <= Duration::days(2) {
+            Duration::hours(1)
+        } else {
+            Duration::hours(4)
+        };
+
+        let mut buckets: Vec<(DateTime<Utc>, i32, i32)> = Vec::new();
+        for (at, data) in readings {
+            let start = at.duration_trunc(bucket).unwrap_or(*at);
+            match buckets.last_mut() {
+                Some((last, sum, count)) if *last == start => {
+                    *sum += data.aqi;
+                    *count += 1;
+                }
+                _ => buckets.push((start, data.aqi, 1)),
+            }
+        }
+
+        for (start, sum, count) in buckets {
+            let average = sum / count;
+            let x0 = plot.x(start.max(plot.from));
+            let x1 = (plot.x((start + bucket).min(plot.to)) - 1).max(x0 + 1);
+            canvas.fill_rect(
+                x0,
+                plot.y(average),
+                x1,
+                plot.y(0),
+                AirQualityLevel::from_aqi(average).map_or(GRID, |level| level.rgb()),
+            );
+        }
+    }
+
+    fn draw_line(
+        &self,
+        canvas: &mut Canvas,
+        plot: &Plot,
+        readings: &[(DateTime<Utc>, AirQualityData)],
+    ) {
+        let points: Vec<(i32, i32)> = readings
+            .iter()
+            .map(|(at, data)| (plot.x(*at), plot.y(data.aqi)))
+            .collect();
+
+        for pair in points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            canvas.line(x0, y0, x1, y1, LINE);
+            canvas.line(x0, y0 - 1, x1, y1 - 1, LINE);
+        }
+        // Markers only help while they don't merge into one thick line.
+        if points.len() <= 60 {
+            for &(x, y) in &points {
+                canvas.fill_rect(x - 2, y - 2, x + 3, y + 3, LINE);
+            }
+        }
+    }
+
+    fn draw_axes(&self, canvas: &mut Canvas, plot: &Plot) {
+        canvas.line(plot.left, plot.top, plot.left, plot.bottom, AXIS);
+        canvas.line(plot.left, plot.bottom, plot.right, plot.bottom, AXIS);
+
+        let y_labels = std::iter::once(0)
+            .chain(LEVEL_BOUNDARIES.into_iter().filter(|b| *b < plot.y_max))
+            .chain(std::iter::once(plot.y_max));
+        for value in y_labels {
+            let y = plot.y(value);
+            let label = value.to_string();
+            canvas.line(plot.left - 4, y, plot.left, y, AXIS);
+            canvas.text(
+                plot.left - 8 - text_width(&label),
+                y - GLYPH_HEIGHT * FONT_SCALE / 2,
+                &label,
+                AXIS,
+            );
+        }
+
+        // Hour-of-day ticks for a day, one tick per local midnight for longer spans.
+        let daily = plot.to - plot.from > Duration::days(2);
+        let step = if daily {
+            Duration::days(1)
+        } else {
+            Duration::hours(6)
+        };
+        let offset = Duration::seconds(self.timezone.local_minus_utc() as i64);
+        let mut tick = ((plot.from + offset)
+            .duration_trunc(step)
+            .unwrap_or(plot.from)
+            - offset)
+            + step;
+
+        while tick <= plot.to {
+            let local = tick.with_timezone(&self.timezone);
+            let label = if daily {
+                local.format("%d/%m").to_string()
+            } else {
+                format!("{:02}:00", local.hour())
+            };
+            let x = plot.x(tick);
+            canvas.line(x, plot.bottom, x, plot.bottom + 4, AXIS);
+            let label_x = (x - text_width(&label) / 2).min(WIDTH - text_width(&label) - 2);
+            canvas.text(label_x, plot.bottom + 10, &label, AXIS);
+            tick += step;
+        }
+    }
+}
+
+/// Smallest level boundary above `max_aqi`, so the chart shows the bands that matter.
+fn y_max_for(max_aqi: i32) -> i32 {
+    [100, 150, 200, 300, 500]
+        .into_iter()
+        .find(|bound| max_aqi < *bound)
+        .unwrap_or_else(|| (max_aqi / 100 + 1) * 100)
+}
+
+fn tint((r, g, b): (u8, u8, u8), strength: f64) -> (u8, u8, u8) {
+    let mix = |c: u8| (255.0 - (255.0 - c as f64) * strength).round() as u8;
+    (mix(r), mix(g), mix(b))
+}
+
+/// Maps time and AQI onto pixel coordinates inside the margins.
+struct Plot {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    y_max: i32,
+    left: i32,
+    right: i32,
+    top: i32,
+    bottom: i32,
+}
+
+impl Plot {
+    fn new(from: DateTime<Utc>, to: DateTime<Utc>, y_max: i32) -> Self {
+        Self {
+            from,
+            to,
+            y_max,
+            left: MARGIN_LEFT,
+            right: WIDTH - MARGIN_RIGHT,
+            top: MARGIN_TOP,
+            bottom: HEIGHT - MARGIN_BOTTOM,
+        }
+    }
+
+    fn x(&self, at: DateTime<Utc>) -> i32 {
+        let span = (self.to - self.from).num_seconds().max(1);
+        let offset = (at - self.from).num_seconds().clamp(0, span);
+        self.left + (offset * (self.right - self.left) as i64 / span) as i32
+    }
+
+    fn y(&self, aqi: i32) -> i32 {
+        let aqi = aqi.clamp(0, self.y_max);
+        self.bottom - (aqi as i64 * (self.bottom - self.top) as i64 / self.y_max as i64) as i32
+    }
+}
+
+const FONT_SCALE: i32 = 2;
+const GLYPH_WIDTH: i32 = 3;
+const GLYPH_HEIGHT: i32 = 5;
+
+/// 3x5 bitmap glyphs, one row per entry, most significant bit on the left.
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        _ => return None,
+    })
+}
+
+fn text_width(text: &str) -> i32 {
+    let chars = text.chars().count() as i32;
+    (chars * (GLYPH_WIDTH + 1) - 1).max(0) * FONT_SCALE
+}
+
+struct Canvas {
+    width: i32,
+    height: i32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: i32, height: i32) -> Self {
+        let (r, g, b) = WHITE;
+        Self {
+            width,
+            height,
+            pixels: [r, g, b].repeat((width * height) as usize),
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, (r, g, b): (u8, u8, u8)) {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let i = ((y * self.width + x) * 3) as usize;
+        self.pixels[i..i + 3].copy_from_slice(&[r, g, b]);
+    }
+
+    /// Fills `[x0, x1) x [y0, y1)`.
+    fn fill_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: (u8, u8, u8)) {
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Bresenham line, endpoints included.
+    fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: (u8, u8, u8)) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+
+        loop {
+            self.set(x, y, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str, color: (u8, u8, u8)) {
+        for (i, c) in text.chars().enumerate() {
+            let Some(rows) = glyph(c) else { continue };
+            let left = x + i as i32 * (GLYPH_WIDTH + 1) * FONT_SCALE;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        let px = left + col * FONT_SCALE;
+                        let py = y + row as i32 * FONT_SCALE;
+                        self.fill_rect(px, py, px + FONT_SCALE, py + FONT_SCALE, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder
+            .write_header()
+            .context("Failed to write PNG header")?;
+        writer
+            .write_image_data(&self.pixels)
+            .context("Failed to encode chart")?;
+        writer.finish().context("Failed to finish PNG")?;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Location;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap()
+    }
+
+    /// Readings `hours` apart ending at `now()`, as AQIs oldest first.
+    fn series(aqis: &[i32]) -> Vec<(DateTime<Utc>, AirQualityData)> {
+        let location = Location::from_city("Ban Suan", "Chon Buri", "Thailand");
+        let start = now() - Duration::hours(aqis.len() as i64 - 1);
+        aqis.iter()
+            .enumerate()
+            .map(|(i, &aqi)| {
+                (
+                    start + Duration::hours(i as i64),
+                    AirQualityData::sample(location.clone(), aqi, 20.0),
+                )
+            })
+            .collect()
+    }
+
+    /// Decodes a chart, checking it is an 8-bit RGB PNG of the chart's size.
+    fn decode(png: &[u8]) -> Vec<u8> {
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let mut reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (WIDTH as u32, HEIGHT as u32));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        assert_eq!(info.bit_depth, png::BitDepth::Eight);
+
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        pixels
+    }
+
+    fn render(aqis: &[i32]) -> Vec<u8> {
+        let renderer = PngChartRenderer::new(FixedOffset::east_opt(7 * 3600).unwrap());
+        let png = renderer
+            .render(&series(aqis), now() - Duration::hours(24), now())
+            .unwrap();
+        decode(&png)
+    }
+
+    fn count(pixels: &[u8], color: (u8, u8, u8)) -> usize {
+        pixels
+            .chunks_exact(3)
+            .filter(|p| (p[0], p[1], p[2]) == color)
+            .count()
+    }
+
+    #[test]
+    fn empty_series_draws_just_the_axes() {
+        let pixels = render(&[]);
+
+        assert_eq!(pixels.len(), (WIDTH * HEIGHT * 3) as usize);
+        assert!(count(&pixels, AXIS) > 0);
+        assert_eq!(count(&pixels, LINE), 0);
+    }
+
+    #[test]
+    fn one_point_series() {
+        let pixels = render(&[87]);
+
+        assert_ne!(pixels, render(&[]));
+    }
+
+    #[test]
+    fn normal_series_draws_the_line() {
+        let pixels = render(&[40, 60, 90, 130, 170, 120, 80, 60, 45, 30, 55, 70]);
+
+        assert!(count(&pixels, LINE) > 0);
+        assert_ne!(pixels, render(&[87]));
+    }
+
+    #[test]
+    fn y_axis_stops_at_the_boundary_above_the_worst_reading() {
+        assert_eq!(y_max_for(0), 100);
+        assert_eq!(y_max_for(100), 150);
+        assert_eq!(y_max_for(170), 200);
+        assert_eq!(y_max_for(480), 500);
+        assert_eq!(y_max_for(620), 700);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::use_cases::scheduler::Clock;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        let remaining = (deadline - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+/// Clock for tests that jumps straight to each deadline instead of waiting.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    now: std::sync::Arc<std::sync::Mutex<DateTime<Utc>>>,
+}
+
+#[cfg(test)]
+impl FakeClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: std::sync::Arc::new(std::sync::Mutex::new(now)),
+        }
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        {
+            let mut now = self.now.lock().unwrap();
+            *now = (*now).max(deadline);
+        }
+        // Let whatever is driving the scheduler see each tick.
+        tokio::task::yield_now().await;
+    }
+}
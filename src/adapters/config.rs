@@ -1,13 +1,95 @@
+use crate::domain::aqi::EpaRevision;
+use crate::domain::i18n::Locale;
 use crate::domain::models::Location;
+use crate::domain::schedule::CronSchedule;
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use crate::presentation::MessageFormat;
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveTime, Timelike, Utc};
 use shuttle_runtime::SecretStore;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    IQAir,
+    OpenMeteo,
+    Air4Thai,
+}
+
+impl FromStr for ProviderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "iqair" => Ok(Self::IQAir),
+            "open-meteo" | "openmeteo" => Ok(Self::OpenMeteo),
+            "air4thai" | "pcd" => Ok(Self::Air4Thai),
+            other => anyhow::bail!("Unknown air quality provider '{}'", other),
+        }
+    }
+}
+
+/// How several configured providers are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderStrategy {
+    /// Use the first provider that answers.
+    Failover,
+    /// Ask all providers and take the median.
+    Consensus,
+}
+
+impl FromStr for ProviderStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "failover" => Ok(Self::Failover),
+            "consensus" => Ok(Self::Consensus),
+            other => anyhow::bail!("Unknown provider strategy '{}'", other),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Config {
-    pub iqair_token: String,
+    /// Providers in failover order; the first is preferred.
+    pub providers: Vec<ProviderKind>,
+    pub provider_strategy: ProviderStrategy,
+    pub provider_cool_down_secs: u64,
+    /// AQI points providers may differ by and still agree, for the consensus strategy.
+    pub consensus_margin: i32,
+    pub cache_ttl_secs: i64,
+    /// How old a cached reading may be and still be served when providers fail.
+    pub cache_max_stale_secs: i64,
+    pub iqair_token: Option<String>,
+    pub iqair_calls_per_minute: u32,
+    pub iqair_monthly_quota: u32,
     pub telegram_token: String,
     pub telegram_channel: String,
     pub locations: Vec<Location>,
+    pub cron_schedule: CronSchedule,
+    /// Morning digest, daily at `DIGEST_TIME` in `timezone`; `None` when unset.
+    pub digest_schedule: Option<CronSchedule>,
+    /// Whether the digest suggests the best time to go out for each location.
+    pub digest_plan: bool,
+    pub timezone: FixedOffset,
+    pub alert_hysteresis: i32,
+    /// Breakpoint tables for computing US AQI and estimating concentrations from it.
+    pub aqi_revision: EpaRevision,
+    /// Standard for the channel and for chats that haven't chosen one.
+    pub aqi_standard: AqiStandard,
+    /// Language for the channel and for chats that haven't chosen one.
+    pub locale: Locale,
+    /// Markup for both bot replies and channel messages.
+    pub message_format: MessageFormat,
+    /// Layout for the channel and for chats that haven't written their own;
+    /// `None` uses the built-in one.
+    pub message_template: Option<MessageTemplate>,
+    pub database_path: Option<String>,
+    pub http_timeout_secs: u64,
+    /// Retries after the first attempt for transient HTTP failures.
+    pub http_max_retries: u32,
 }
 
 impl Config {
@@ -27,10 +109,150 @@ impl Config {
             .map(|city| Location::from_city(city.trim(), &state, &country))
             .collect();
 
+        let timezone = secrets
+            .get("TIMEZONE_OFFSET")
+            .unwrap_or_else(|| "+07:00".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("TIMEZONE_OFFSET is invalid: {}", e))?;
+        let cron_schedule: CronSchedule = secrets
+            .get("CRON_SCHEDULE")
+            .unwrap_or_else(|| "0 0 */4 * * *".to_string())
+            .parse()
+            .context("CRON_SCHEDULE is invalid")?;
+        ensure_upcoming(&cron_schedule, Utc::now(), timezone)
+            .context("CRON_SCHEDULE is invalid")?;
+        let digest_schedule = secrets
+            .get("DIGEST_TIME")
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| {
+                let time = NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                    .with_context(|| format!("DIGEST_TIME must be HH:MM, got '{}'", t))?;
+                format!("0 {} {} * * *", time.minute(), time.hour())
+                    .parse::<CronSchedule>()
+                    .context("DIGEST_TIME is invalid")
+            })
+            .transpose()?;
+        let digest_plan = secrets
+            .get("DIGEST_PLAN")
+            .unwrap_or_else(|| "false".to_string())
+            .trim()
+            .parse()
+            .context("DIGEST_PLAN must be true or false")?;
+
+        let alert_hysteresis = secrets
+            .get("ALERT_HYSTERESIS")
+            .unwrap_or_else(|| "5".to_string())
+            .parse()
+            .context("ALERT_HYSTERESIS must be a whole number")?;
+        let aqi_revision = match secrets
+            .get("US_AQI_REVISION")
+            .unwrap_or_else(|| "2024".to_string())
+            .trim()
+        {
+            "2012" => EpaRevision::Epa2012,
+            "2024" => EpaRevision::Epa2024,
+            other => anyhow::bail!("US_AQI_REVISION must be 2012 or 2024, got '{}'", other),
+        };
+        let aqi_standard = secrets
+            .get("AQI_STANDARD")
+            .unwrap_or_else(|| "us".to_string());
+        let aqi_standard = AqiStandard::from_slug(&aqi_standard).with_context(|| {
+            format!(
+                "AQI_STANDARD must be one of us, th, eu, in, got '{}'",
+                aqi_standard
+            )
+        })?;
+        let locale = secrets.get("LANGUAGE").unwrap_or_else(|| "th".to_string());
+        let locale = Locale::from_slug(&locale)
+            .with_context(|| format!("LANGUAGE must be th or en, got '{}'", locale))?;
+        let message_format = secrets
+            .get("MESSAGE_FORMAT")
+            .unwrap_or_else(|| "html".to_string());
+        let message_format = MessageFormat::from_slug(&message_format).with_context(|| {
+            format!(
+                "MESSAGE_FORMAT must be html, markdown or plain, got '{}'",
+                message_format
+            )
+        })?;
+        let message_template = secrets
+            .get("MESSAGE_TEMPLATE")
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| MessageTemplate::parse(&t))
+            .transpose()
+            .context("MESSAGE_TEMPLATE is invalid")?;
+
+        let providers = secrets
+            .get("AIR_QUALITY_PROVIDER")
+            .unwrap_or_else(|| "iqair".to_string())
+            .split(',')
+            .filter(|p| !p.trim().is_empty())
+            .map(ProviderKind::from_str)
+            .collect::<Result<Vec<_>>>()
+            .context("AIR_QUALITY_PROVIDER is invalid")?;
+        if providers.is_empty() {
+            anyhow::bail!("AIR_QUALITY_PROVIDER must name at least one provider");
+        }
+        let provider_strategy = secrets
+            .get("AIR_QUALITY_STRATEGY")
+            .unwrap_or_else(|| "failover".to_string())
+            .parse()
+            .context("AIR_QUALITY_STRATEGY is invalid")?;
+        let consensus_margin = secrets
+            .get("CONSENSUS_MARGIN")
+            .unwrap_or_else(|| "20".to_string())
+            .parse()
+            .context("CONSENSUS_MARGIN must be a whole number")?;
+        let provider_cool_down_secs = secrets
+            .get("PROVIDER_COOL_DOWN_SECS")
+            .unwrap_or_else(|| "300".to_string())
+            .parse()
+            .context("PROVIDER_COOL_DOWN_SECS must be a whole number")?;
+        let cache_ttl_secs = secrets
+            .get("CACHE_TTL_SECS")
+            .unwrap_or_else(|| "600".to_string())
+            .parse()
+            .context("CACHE_TTL_SECS must be a whole number")?;
+        let cache_max_stale_secs = secrets
+            .get("CACHE_MAX_STALE_SECS")
+            .unwrap_or_else(|| "3600".to_string())
+            .parse()
+            .context("CACHE_MAX_STALE_SECS must be a whole number")?;
+        let iqair_token = secrets.get("IQAIR_API_KEY").filter(|k| !k.is_empty());
+        let iqair_calls_per_minute = secrets
+            .get("IQAIR_CALLS_PER_MINUTE")
+            .unwrap_or_else(|| "5".to_string())
+            .parse()
+            .context("IQAIR_CALLS_PER_MINUTE must be a whole number")?;
+        let iqair_monthly_quota = secrets
+            .get("IQAIR_MONTHLY_QUOTA")
+            .unwrap_or_else(|| "10000".to_string())
+            .parse()
+            .context("IQAIR_MONTHLY_QUOTA must be a whole number")?;
+        if providers.contains(&ProviderKind::IQAir) && iqair_token.is_none() {
+            anyhow::bail!("IQAIR_API_KEY not set");
+        }
+
+        let http_timeout_secs = secrets
+            .get("HTTP_TIMEOUT_SECS")
+            .unwrap_or_else(|| "15".to_string())
+            .parse()
+            .context("HTTP_TIMEOUT_SECS must be a whole number")?;
+        let http_max_retries = secrets
+            .get("HTTP_MAX_RETRIES")
+            .unwrap_or_else(|| "3".to_string())
+            .parse()
+            .context("HTTP_MAX_RETRIES must be a whole number")?;
+
         Ok(Self {
-            iqair_token: secrets
-                .get("IQAIR_API_KEY")
-                .context("IQAIR_API_KEY not set")?,
+            providers,
+            provider_strategy,
+            provider_cool_down_secs,
+            consensus_margin,
+            cache_ttl_secs,
+            cache_max_stale_secs,
+            iqair_token,
+            iqair_calls_per_minute,
+            iqair_monthly_quota,
             telegram_token: secrets
                 .get("TELEGRAM_TOKEN")
                 .context("TELEGRAM_TOKEN not set")?,
@@ -38,6 +260,59 @@ impl Config {
                 .get("TELEGRAM_CHANNEL")
                 .context("TELEGRAM_CHANNEL not set")?,
             locations,
+            cron_schedule,
+            digest_schedule,
+            digest_plan,
+            timezone,
+            alert_hysteresis,
+            aqi_revision,
+            aqi_standard,
+            locale,
+            message_format,
+            message_template,
+            database_path: secrets.get("DATABASE_PATH").filter(|p| !p.is_empty()),
+            http_timeout_secs,
+            http_max_retries,
         })
     }
 }
+
+/// Rejects a schedule that never fires from `now` on, e.g. "0 0 0 31 2 *";
+/// the scheduler would stop at once and the service with it.
+fn ensure_upcoming(
+    schedule: &CronSchedule,
+    now: DateTime<Utc>,
+    timezone: FixedOffset,
+) -> Result<()> {
+    if schedule.next_after(&now.with_timezone(&timezone)).is_none() {
+        anyhow::bail!("'{}' never runs", schedule);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn check(expression: &str) -> Result<()> {
+        let now = Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap();
+        let timezone = FixedOffset::east_opt(7 * 3600).unwrap();
+        ensure_upcoming(&expression.parse().unwrap(), now, timezone)
+    }
+
+    #[test]
+    fn accepts_schedules_with_an_upcoming_run() {
+        assert!(check("0 0 */4 * * *").is_ok());
+        // Leap days are rare but do come round.
+        assert!(check("0 0 0 29 2 *").is_ok());
+    }
+
+    #[test]
+    fn rejects_schedules_that_never_run() {
+        for expression in ["0 0 0 31 2 *", "0 0 0 30 2 *", "0 0 0 31 4 *"] {
+            let error = check(expression).unwrap_err();
+            assert_eq!(error.to_string(), format!("'{}' never runs", expression));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{Location, Pollutant, Pollutants};
+use crate::domain::services::{median, Consensus};
+use crate::domain::weather::Weather;
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Asks every provider at once and combines their answers.
+///
+/// The AQI is the median of the sources that agree with each other; the
+/// reading carries the full spread so users can judge how reliable it is.
+#[derive(Clone)]
+pub struct ConsensusRepository {
+    providers: Arc<Vec<(String, Arc<dyn AirQualityRepository>)>>,
+    margin: i32,
+}
+
+impl ConsensusRepository {
+    /// `margin` is how many AQI points sources may differ by and still agree.
+    pub fn new(providers: Vec<(String, Arc<dyn AirQualityRepository>)>, margin: i32) -> Self {
+        Self {
+            providers: Arc::new(providers),
+            margin,
+        }
+    }
+}
+
+#[async_trait]
+impl AirQualityRepository for ConsensusRepository {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        let results = join_all(
+            self.providers
+                .iter()
+                .map(|(_, provider)| provider.get_air_quality(location, priority)),
+        )
+        .await;
+
+        let mut errors = Vec::new();
+        let mut readings = Vec::new();
+        for ((name, _), result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(data) => readings.push(data),
+                Err(e) => {
+                    warn!("{} failed for {}: {}", name, location.name, e);
+                    errors.push((name.clone(), e));
+                }
+            }
+        }
+
+        let aqis: Vec<i32> = readings.iter().map(|r| r.aqi).collect();
+        let Some(consensus) = Consensus::combine(&aqis, self.margin) else {
+            return Err(AirQualityError::from_all_failed(errors));
+        };
+        debug!(
+            "Consensus for {}: AQI {} from {:?}, kept {:?}",
+            location.name, consensus.aqi, aqis, consensus.kept
+        );
+
+        let kept: Vec<&RawAirQualityData> = consensus.kept.iter().map(|&i| &readings[i]).collect();
+        // Place names and weather come from the first provider in configured order
+        // that reports them.
+        let primary = kept[0];
+
+        Ok(RawAirQualityData {
+            city: primary.city.clone(),
+            state: primary.state.clone(),
+            aqi: consensus.aqi,
+            pollutants: median_pollutants(&kept),
+            main_pollutant: primary.main_pollutant,
+            aqi_cn: kept.iter().find_map(|r| r.aqi_cn),
+            aqi_th: kept.iter().find_map(|r| r.aqi_th),
+            weather: kept
+                .iter()
+                .find(|r| r.weather != Weather::default())
+                .map(|r| r.weather.clone())
+                .unwrap_or_default(),
+            source: kept
+                .iter()
+                .map(|r| r.source.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            spread: Some(consensus.spread),
+            stale_since: None,
+            cached: false,
+        })
+    }
+}
+
+/// Median of each pollutant over the sources that measured it.
+fn median_pollutants(readings: &[&RawAirQualityData]) -> Pollutants {
+    let mut pollutants = Pollutants::default();
+    for pollutant in Pollutant::ALL {
+        let values = readings.iter().filter_map(|r| r.pollutants.get(pollutant));
+        pollutants.set(pollutant, median(values));
+    }
+    pollutants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::SourceSpread;
+
+    /// Reports a fixed AQI and PM2.5, or times out when `aqi` is `None`.
+    struct Fixed {
+        aqi: Option<i32>,
+        pm25: f64,
+    }
+
+    #[async_trait]
+    impl AirQualityRepository for Fixed {
+        async fn get_air_quality(
+            &self,
+            location: &Location,
+            _priority: FetchPriority,
+        ) -> Result<RawAirQualityData, AirQualityError> {
+            let aqi = self.aqi.ok_or(AirQualityError::Timeout)?;
+            Ok(RawAirQualityData {
+                city: location.name.clone(),
+                state: "Bangkok".to_string(),
+                aqi,
+                pollutants: Pollutants {
+                    pm25: Some(self.pm25),
+                    ..Pollutants::default()
+                },
+                main_pollutant: Some(Pollutant::Pm25),
+                aqi_cn: None,
+                aqi_th: None,
+                weather: Weather::default(),
+                source: format!("source-{}", aqi),
+                spread: None,
+                stale_since: None,
+                cached: false,
+            })
+        }
+    }
+
+    async fn combine(
+        readings: &[(Option<i32>, f64)],
+        margin: i32,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        let providers = readings
+            .iter()
+            .enumerate()
+            .map(|(i, &(aqi, pm25))| {
+                let provider: Arc<dyn AirQualityRepository> = Arc::new(Fixed { aqi, pm25 });
+                (format!("provider-{}", i), provider)
+            })
+            .collect();
+        let location = Location::from_city("Bangkok", "Bangkok", "Thailand");
+        ConsensusRepository::new(providers, margin)
+            .get_air_quality(&location, FetchPriority::Scheduled)
+            .await
+    }
+
+    #[tokio::test]
+    async fn combines_the_readings_that_agree() {
+        let data = combine(&[(Some(50), 10.0), (Some(150), 80.0), (Some(55), 12.0)], 15)
+            .await
+            .unwrap();
+
+        assert_eq!(data.aqi, 53);
+        assert_eq!(data.source, "source-50, source-55");
+        assert_eq!(data.pollutants.pm25, Some(11.0));
+        assert_eq!(
+            data.spread,
+            Some(SourceSpread {
+                min: 50,
+                max: 150,
+                sources: 3,
+                confident: true,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_providers_are_left_out() {
+        let data = combine(&[(Some(60), 15.0), (None, 0.0), (Some(90), 30.0)], 15)
+            .await
+            .unwrap();
+
+        assert_eq!(data.aqi, 75);
+        assert_eq!(data.source, "source-60, source-90");
+        let spread = data.spread.unwrap();
+        assert_eq!(spread.sources, 2);
+        assert!(!spread.confident);
+    }
+
+    #[tokio::test]
+    async fn a_single_answer_is_served_but_not_confident() {
+        let data = combine(&[(None, 0.0), (Some(70), 20.0)], 15).await.unwrap();
+
+        assert_eq!(data.aqi, 70);
+        assert!(!data.spread.unwrap().confident);
+    }
+
+    #[tokio::test]
+    async fn fails_when_every_provider_does() {
+        let result = combine(&[(None, 0.0), (None, 0.0)], 15).await;
+
+        assert!(matches!(
+            result,
+            Err(AirQualityError::UpstreamUnavailable(message)) if message.starts_with("all providers failed")
+        ));
+    }
+}
@@ -0,0 +1,340 @@
+use crate::adapters::clock::SystemClock;
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::Location;
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
+use crate::use_cases::scheduler::Clock;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Consecutive failures after which a provider is considered down.
+const FAILURE_THRESHOLD: u32 = 2;
+
+/// Tries providers in order and serves the first successful reading.
+///
+/// A provider that keeps failing is skipped for a cool-down period (a simple
+/// circuit breaker) and only tried again as a last resort until it recovers.
+#[derive(Clone)]
+pub struct FailoverRepository {
+    providers: Arc<Vec<Provider>>,
+    cool_down: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+struct Provider {
+    name: String,
+    repository: Arc<dyn AirQualityRepository>,
+    health: Mutex<Health>,
+}
+
+#[derive(Default)]
+struct Health {
+    consecutive_failures: u32,
+    open_until: Option<DateTime<Utc>>,
+}
+
+impl Health {
+    fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.open_until.is_some_and(|until| until > now)
+    }
+}
+
+impl Provider {
+    fn record_success(&self) {
+        let mut health = self.health.lock().unwrap();
+        if health.open_until.is_some() {
+            info!("Air quality provider {} recovered", self.name);
+        }
+        *health = Health::default();
+    }
+
+    fn record_failure(&self, cool_down: Duration, now: DateTime<Utc>) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= FAILURE_THRESHOLD {
+            warn!(
+                "Air quality provider {} failed {} times, skipping it for {}s",
+                self.name,
+                health.consecutive_failures,
+                cool_down.as_secs()
+            );
+            health.open_until = Some(now + cool_down);
+        }
+    }
+}
+
+impl FailoverRepository {
+    /// Providers are tried in the given order.
+    pub fn new(
+        providers: Vec<(String, Arc<dyn AirQualityRepository>)>,
+        cool_down: Duration,
+    ) -> Self {
+        Self::with_clock(providers, cool_down, Arc::new(SystemClock))
+    }
+
+    /// Like `new`, timing cool-downs with `clock`.
+    pub fn with_clock(
+        providers: Vec<(String, Arc<dyn AirQualityRepository>)>,
+        cool_down: Duration,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let providers = providers
+            .into_iter()
+            .map(|(name, repository)| Provider {
+                name,
+                repository,
+                health: Mutex::new(Health::default()),
+            })
+            .collect();
+
+        Self {
+            providers: Arc::new(providers),
+            cool_down,
+            clock,
+        }
+    }
+}
+
+#[async_trait]
+impl AirQualityRepository for FailoverRepository {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        // Healthy providers first, in configured order; providers in cool-down
+        // are kept as a last resort rather than dropped.
+        let now = self.clock.now();
+        let (healthy, cooling): (Vec<&Provider>, Vec<&Provider>) = self
+            .providers
+            .iter()
+            .partition(|p| !p.health.lock().unwrap().is_open(now));
+
+        let mut errors = Vec::new();
+        for provider in healthy.into_iter().chain(cooling) {
+            match provider
+                .repository
+                .get_air_quality(location, priority)
+                .await
+            {
+                Ok(data) => {
+                    provider.record_success();
+                    return Ok(data);
+                }
+                // Not knowing a place says nothing about the provider's health.
+                Err(e @ AirQualityError::CityNotFound(_)) => {
+                    errors.push((provider.name.clone(), e));
+                }
+                Err(e) => {
+                    warn!("{} failed for {}: {}", provider.name, location.name, e);
+                    provider.record_failure(self.cool_down, self.clock.now());
+                    errors.push((provider.name.clone(), e));
+                }
+            }
+        }
+
+        Err(AirQualityError::from_all_failed(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::clock::FakeClock;
+    use crate::domain::models::Pollutants;
+    use crate::domain::weather::Weather;
+    use chrono::TimeZone;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Answers with its own name as the source, or fails while `failing` is set.
+    struct MockProvider {
+        name: &'static str,
+        calls: AtomicUsize,
+        failing: AtomicBool,
+        error: fn(&Location) -> AirQualityError,
+    }
+
+    impl MockProvider {
+        fn new(name: &'static str, failing: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                calls: AtomicUsize::new(0),
+                failing: AtomicBool::new(failing),
+                error: |_| AirQualityError::Timeout,
+            })
+        }
+
+        fn not_finding(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                calls: AtomicUsize::new(0),
+                failing: AtomicBool::new(true),
+                error: |location| AirQualityError::CityNotFound(location.name.clone()),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl AirQualityRepository for MockProvider {
+        async fn get_air_quality(
+            &self,
+            location: &Location,
+            _priority: FetchPriority,
+        ) -> Result<RawAirQualityData, AirQualityError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                return Err((self.error)(location));
+            }
+            Ok(RawAirQualityData {
+                city: location.name.clone(),
+                state: "Chon Buri".to_string(),
+                aqi: 42,
+                pollutants: Pollutants::default(),
+                main_pollutant: None,
+                aqi_cn: None,
+                aqi_th: None,
+                weather: Weather::default(),
+                source: self.name.to_string(),
+                spread: None,
+                stale_since: None,
+                cached: false,
+            })
+        }
+    }
+
+    const COOL_DOWN: Duration = Duration::from_secs(300);
+
+    fn failover(providers: &[&Arc<MockProvider>]) -> (FailoverRepository, FakeClock) {
+        let clock = FakeClock::new(Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap());
+        let providers = providers
+            .iter()
+            .map(|p| {
+                let repository: Arc<dyn AirQualityRepository> = (*p).clone();
+                (p.name.to_string(), repository)
+            })
+            .collect();
+        let repository =
+            FailoverRepository::with_clock(providers, COOL_DOWN, Arc::new(clock.clone()));
+        (repository, clock)
+    }
+
+    async fn source(failover: &FailoverRepository) -> Result<String, AirQualityError> {
+        let location = Location::from_city("Ban Suan", "Chon Buri", "Thailand");
+        failover
+            .get_air_quality(&location, FetchPriority::Scheduled)
+            .await
+            .map(|data| data.source)
+    }
+
+    #[tokio::test]
+    async fn uses_the_first_provider_that_answers() {
+        let primary = MockProvider::new("IQAir", false);
+        let backup = MockProvider::new("OpenMeteo", false);
+        let (failover, _) = failover(&[&primary, &backup]);
+
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+        assert_eq!(backup.calls(), 0);
+
+        primary.failing.store(true, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "OpenMeteo");
+        assert_eq!(primary.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn skips_a_failing_provider_until_the_cool_down_ends() {
+        let primary = MockProvider::new("IQAir", true);
+        let backup = MockProvider::new("OpenMeteo", false);
+        let (failover, clock) = failover(&[&primary, &backup]);
+
+        for _ in 0..FAILURE_THRESHOLD {
+            assert_eq!(source(&failover).await.unwrap(), "OpenMeteo");
+        }
+        assert_eq!(primary.calls(), FAILURE_THRESHOLD as usize);
+
+        // Open: the backup goes first and the primary isn't asked at all.
+        primary.failing.store(false, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "OpenMeteo");
+        clock.advance(chrono::Duration::seconds(299));
+        assert_eq!(source(&failover).await.unwrap(), "OpenMeteo");
+        assert_eq!(primary.calls(), FAILURE_THRESHOLD as usize);
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+        assert_eq!(backup.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn one_failure_does_not_open_the_breaker() {
+        let primary = MockProvider::new("IQAir", true);
+        let backup = MockProvider::new("OpenMeteo", false);
+        let (failover, _) = failover(&[&primary, &backup]);
+
+        source(&failover).await.unwrap();
+        primary.failing.store(false, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+
+        // A success resets the count.
+        primary.failing.store(true, Ordering::SeqCst);
+        source(&failover).await.unwrap();
+        primary.failing.store(false, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+    }
+
+    #[tokio::test]
+    async fn cooling_providers_are_a_last_resort() {
+        let primary = MockProvider::new("IQAir", true);
+        let backup = MockProvider::new("OpenMeteo", false);
+        let (failover, _) = failover(&[&primary, &backup]);
+        for _ in 0..FAILURE_THRESHOLD {
+            source(&failover).await.unwrap();
+        }
+
+        primary.failing.store(false, Ordering::SeqCst);
+        backup.failing.store(true, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+    }
+
+    #[tokio::test]
+    async fn all_providers_failing_reports_every_error() {
+        let primary = MockProvider::new("IQAir", true);
+        let backup = MockProvider::not_finding("OpenMeteo");
+        let (failover, _) = failover(&[&primary, &backup]);
+
+        match source(&failover).await {
+            Err(AirQualityError::UpstreamUnavailable(message)) => {
+                assert_eq!(
+                    message,
+                    "all providers failed (IQAir: request timed out; \
+                     OpenMeteo: city 'Ban Suan' not found)"
+                );
+            }
+            other => panic!("expected UpstreamUnavailable, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_places_do_not_trip_the_breaker() {
+        let primary = MockProvider::not_finding("IQAir");
+        let backup = MockProvider::not_finding("OpenMeteo");
+        let (failover, _) = failover(&[&primary, &backup]);
+
+        for _ in 0..3 {
+            assert!(matches!(
+                source(&failover).await,
+                Err(AirQualityError::CityNotFound(place)) if place == "Ban Suan"
+            ));
+        }
+        assert_eq!(primary.calls(), 3);
+
+        // Still first in line once both answer again.
+        primary.failing.store(false, Ordering::SeqCst);
+        backup.failing.store(false, Ordering::SeqCst);
+        assert_eq!(source(&failover).await.unwrap(), "IQAir");
+    }
+}
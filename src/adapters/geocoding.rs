@@ -0,0 +1,207 @@
+use crate::adapters::http::{self, HttpClient};
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{Location, LocationQuery};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A place resolved to coordinates, with the names the geocoder knows it by.
+#[derive(Debug, Clone)]
+pub struct Place {
+    pub name: String,
+    pub state: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Resolves city names to coordinates with the free Open-Meteo geocoding API.
+/// Results are cached for the lifetime of the process since places don't move,
+/// up to `CACHE_CAPACITY` places so arbitrary `/check` input can't grow it
+/// without limit.
+#[derive(Debug, Clone)]
+pub struct Geocoder {
+    http: HttpClient,
+    cache: Arc<Mutex<PlaceCache>>,
+}
+
+/// Places kept by a `Geocoder`; the least recently used goes first.
+const CACHE_CAPACITY: usize = 1000;
+
+#[derive(Debug)]
+struct PlaceCache {
+    capacity: usize,
+    /// Each place with the tick it was last used at.
+    places: HashMap<String, (Place, u64)>,
+    tick: u64,
+}
+
+impl PlaceCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            places: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Place> {
+        self.tick += 1;
+        let (place, used) = self.places.get_mut(key)?;
+        *used = self.tick;
+        Some(place.clone())
+    }
+
+    fn insert(&mut self, key: String, place: Place) {
+        self.tick += 1;
+        if self.places.len() >= self.capacity && !self.places.contains_key(&key) {
+            let oldest = self
+                .places
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.places.remove(&oldest);
+            }
+        }
+        self.places.insert(key, (place, self.tick));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    country: String,
+    #[serde(default)]
+    admin1: String,
+}
+
+impl Geocoder {
+    pub fn new(http: HttpClient) -> Self {
+        Self {
+            http,
+            cache: Arc::new(Mutex::new(PlaceCache::new(CACHE_CAPACITY))),
+        }
+    }
+
+    pub async fn resolve(&self, location: &Location) -> Result<Place, AirQualityError> {
+        let (city, state, country) = match &location.query {
+            LocationQuery::Coordinates { lat, lon } => {
+                return Ok(Place {
+                    name: location.name.clone(),
+                    state: String::new(),
+                    lat: *lat,
+                    lon: *lon,
+                })
+            }
+            LocationQuery::City {
+                city,
+                state,
+                country,
+            } => (city, state, country),
+        };
+
+        let key = location.key();
+        if let Some(place) = self.cache.lock().unwrap().get(&key) {
+            return Ok(place);
+        }
+
+        let url = format!(
+            "https://geocoding-api.open-meteo.com/v1/search?name={}&count=10&language=en&format=json",
+            urlencoding::encode(city)
+        );
+        let response: SearchResponse = http::get_json(&self.http, &url).await?;
+
+        // Prefer a match in the configured state, then the country, then anything.
+        let matches = |a: &str, b: &str| !b.is_empty() && a.eq_ignore_ascii_case(b);
+        let best = response
+            .results
+            .iter()
+            .find(|r| matches(&r.admin1, state) && matches(&r.country, country))
+            .or_else(|| {
+                response
+                    .results
+                    .iter()
+                    .find(|r| matches(&r.country, country))
+            })
+            .or_else(|| response.results.first())
+            .ok_or_else(|| AirQualityError::CityNotFound(city.clone()))?;
+
+        let place = Place {
+            name: best.name.clone(),
+            state: best.admin1.clone(),
+            lat: best.latitude,
+            lon: best.longitude,
+        };
+        self.cache.lock().unwrap().insert(key, place.clone());
+
+        Ok(place)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(name: &str) -> Place {
+        Place {
+            name: name.to_string(),
+            state: "Chon Buri".to_string(),
+            lat: 13.4,
+            lon: 101.0,
+        }
+    }
+
+    fn cached(cache: &mut PlaceCache, key: &str) -> Option<String> {
+        cache.get(key).map(|place| place.name)
+    }
+
+    #[test]
+    fn keeps_at_most_its_capacity() {
+        let mut cache = PlaceCache::new(2);
+        for name in ["Ban Suan", "Si Racha", "Pattaya"] {
+            cache.insert(name.to_string(), place(name));
+        }
+
+        assert_eq!(cache.places.len(), 2);
+        assert_eq!(cached(&mut cache, "Ban Suan"), None);
+        assert_eq!(cached(&mut cache, "Pattaya").as_deref(), Some("Pattaya"));
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = PlaceCache::new(2);
+        cache.insert("Ban Suan".to_string(), place("Ban Suan"));
+        cache.insert("Si Racha".to_string(), place("Si Racha"));
+        // Looking Ban Suan up makes Si Racha the oldest.
+        cached(&mut cache, "Ban Suan");
+        cache.insert("Pattaya".to_string(), place("Pattaya"));
+
+        assert_eq!(cached(&mut cache, "Si Racha"), None);
+        assert!(cached(&mut cache, "Ban Suan").is_some());
+        assert!(cached(&mut cache, "Pattaya").is_some());
+    }
+
+    #[test]
+    fn replacing_a_place_evicts_nothing() {
+        let mut cache = PlaceCache::new(2);
+        cache.insert("Ban Suan".to_string(), place("Ban Suan"));
+        cache.insert("Si Racha".to_string(), place("Si Racha"));
+        cache.insert("Ban Suan".to_string(), place("Ban Suan (Chon Buri)"));
+
+        assert_eq!(cache.places.len(), 2);
+        assert_eq!(
+            cached(&mut cache, "Ban Suan").as_deref(),
+            Some("Ban Suan (Chon Buri)")
+        );
+        assert!(cached(&mut cache, "Si Racha").is_some());
+    }
+}
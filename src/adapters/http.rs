@@ -0,0 +1,478 @@
+use crate::domain::errors::AirQualityError;
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tracing::warn;
+
+/// Timeouts and retry rules shared by every outbound HTTP call.
+#[derive(Debug, Clone)]
+pub struct HttpPolicy {
+    pub connect_timeout: Duration,
+    /// Limit for a whole request, including reading the body.
+    pub timeout: Duration,
+    /// Extra attempts after the first for transient failures.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Longest wait between attempts; a longer `Retry-After` isn't waited for.
+    pub max_delay: Duration,
+    /// Whether a 429 is retried like a 5xx.
+    pub retry_rate_limited: bool,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(15),
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_rate_limited: true,
+        }
+    }
+}
+
+impl HttpPolicy {
+    /// Full jitter: a random wait up to the exponential backoff for `attempt`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        ceiling.mul_f64(fastrand::f64())
+    }
+}
+
+/// HTTP client that applies an `HttpPolicy` to every request.
+///
+/// Network errors, timeouts, 5xx and 429 responses are retried with jittered
+/// exponential backoff; a `Retry-After` header replaces the backoff when present.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    policy: HttpPolicy,
+}
+
+impl HttpClient {
+    pub fn new(policy: HttpPolicy) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(policy.connect_timeout)
+            .timeout(policy.timeout)
+            .build()
+            .expect("HTTP client configuration is valid");
+        Self { client, policy }
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Whether 429 responses are retried. Callers with a metered quota turn
+    /// this off, since every retry spends more of the quota the provider just
+    /// said was used up.
+    pub fn with_rate_limit_retries(mut self, retry: bool) -> Self {
+        self.policy.retry_rate_limited = retry;
+        self
+    }
+
+    /// Sends `request`, retrying transient failures. The last response is
+    /// returned as is, so callers still see a final 429 or 5xx.
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            // Bodies that can't be cloned (streams) get a single attempt.
+            let Some(retry) = request
+                .try_clone()
+                .filter(|_| attempt < self.policy.max_retries)
+            else {
+                return request.send().await;
+            };
+
+            let delay = match retry.send().await {
+                Ok(response) if self.is_transient(response.status()) => {
+                    match retry_after_secs(&response).map(Duration::from_secs) {
+                        Some(wait) if wait > self.policy.max_delay => return Ok(response),
+                        Some(wait) => wait,
+                        None => self.policy.backoff(attempt),
+                    }
+                }
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    self.policy.backoff(attempt)
+                }
+                result => return result,
+            };
+
+            attempt += 1;
+            warn!(
+                "Retrying request to {} in {:?} (attempt {} of {})",
+                host(&request),
+                delay,
+                attempt,
+                self.policy.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn is_transient(&self, status: StatusCode) -> bool {
+        status.is_server_error()
+            || (status == StatusCode::TOO_MANY_REQUESTS && self.policy.retry_rate_limited)
+    }
+}
+
+/// Only the host is logged; paths and queries can carry API keys and tokens.
+fn host(request: &RequestBuilder) -> String {
+    request
+        .try_clone()
+        .and_then(|r| r.build().ok())
+        .and_then(|r| r.url().host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Maps a transport failure onto the domain error. The URL is dropped from the
+/// message since its query can carry an API key.
+pub fn request_error(e: reqwest::Error) -> AirQualityError {
+    let e = e.without_url();
+    if e.is_timeout() {
+        AirQualityError::Timeout
+    } else if e.is_decode() {
+        AirQualityError::Parse(e.to_string())
+    } else {
+        AirQualityError::UpstreamUnavailable(e.to_string())
+    }
+}
+
+/// Fails with the matching domain error when the response isn't a success.
+pub fn error_for_status(response: Response) -> Result<Response, AirQualityError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    Err(status_error(status, retry_after_secs(&response)))
+}
+
+/// Domain error for an unsuccessful HTTP status.
+pub fn status_error(status: StatusCode, retry_after_secs: Option<u64>) -> AirQualityError {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => AirQualityError::RateLimited { retry_after_secs },
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AirQualityError::InvalidApiKey,
+        _ => AirQualityError::UpstreamUnavailable(format!("HTTP {}", status)),
+    }
+}
+
+/// Seconds to wait from a `Retry-After` header.
+pub fn retry_after_secs(response: &Response) -> Option<u64> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+/// A `Retry-After` value is either a number of seconds or an HTTP date; a
+/// date already past means no wait.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(secs);
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.with_timezone(&Utc) - now;
+    // Round up so a date a fraction of a second away still waits for it.
+    Some((wait.num_milliseconds().max(0) as u64).div_ceil(1000))
+}
+
+/// Sends a GET request and decodes a successful JSON body.
+pub async fn get_json<T: DeserializeOwned>(
+    http: &HttpClient,
+    url: &str,
+) -> Result<T, AirQualityError> {
+    let response = http.send(http.get(url)).await.map_err(request_error)?;
+    error_for_status(response)?
+        .json()
+        .await
+        .map_err(request_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers each request on 127.0.0.1 with the next scripted response,
+    /// repeating the last one. `None` accepts the request and never answers.
+    struct Server {
+        url: String,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl Server {
+        async fn start(script: Vec<Option<String>>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/feed?key=secret", listener.local_addr().unwrap());
+            let requests = Arc::new(AtomicUsize::new(0));
+            let script = Arc::new(Mutex::new(VecDeque::from(script)));
+
+            let counter = requests.clone();
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let reply = {
+                        let mut script = script.lock().unwrap();
+                        if script.len() > 1 {
+                            script.pop_front().unwrap()
+                        } else {
+                            script.front().cloned().unwrap()
+                        }
+                    };
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buffer = [0; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match socket.read(&mut buffer).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => request.extend_from_slice(&buffer[..n]),
+                            }
+                        }
+                        match reply {
+                            Some(reply) => {
+                                let _ = socket.write_all(reply.as_bytes()).await;
+                            }
+                            None => std::future::pending::<()>().await,
+                        }
+                    });
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    fn reply(status: &str, headers: &[(&str, &str)], body: &str) -> Option<String> {
+        let mut reply = format!(
+            "HTTP/1.1 {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            status,
+            body.len()
+        );
+        for (name, value) in headers {
+            reply.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        reply.push_str("\r\n");
+        reply.push_str(body);
+        Some(reply)
+    }
+
+    fn ok() -> Option<String> {
+        reply(
+            "200 OK",
+            &[("Content-Type", "application/json")],
+            r#"{"aqi":42}"#,
+        )
+    }
+
+    fn client(max_retries: u32) -> HttpClient {
+        HttpClient::new(HttpPolicy {
+            connect_timeout: Duration::from_secs(1),
+            timeout: Duration::from_secs(2),
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(5),
+            ..HttpPolicy::default()
+        })
+    }
+
+    async fn status(http: &HttpClient, url: &str) -> StatusCode {
+        http.send(http.get(url)).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        let server = Server::start(vec![
+            reply("503 Service Unavailable", &[], ""),
+            reply("502 Bad Gateway", &[], ""),
+            ok(),
+        ])
+        .await;
+
+        let value: serde_json::Value = get_json(&client(3), &server.url).await.unwrap();
+        assert_eq!(value["aqi"], 42);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_last_retry() {
+        let server = Server::start(vec![reply("500 Internal Server Error", &[], "")]).await;
+
+        let status = status(&client(2), &server.url).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let server = Server::start(vec![reply("404 Not Found", &[], "")]).await;
+        assert_eq!(status(&client(3), &server.url).await, StatusCode::NOT_FOUND);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limits_after_retry_after() {
+        let server = Server::start(vec![
+            reply("429 Too Many Requests", &[("Retry-After", "1")], ""),
+            ok(),
+        ])
+        .await;
+
+        let started = Instant::now();
+        assert_eq!(status(&client(3), &server.url).await, StatusCode::OK);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn returns_rate_limits_it_wont_wait_for() {
+        let server = Server::start(vec![reply(
+            "429 Too Many Requests",
+            &[("Retry-After", "30")],
+            "",
+        )])
+        .await;
+        let result: Result<serde_json::Value, _> = get_json(&client(3), &server.url).await;
+        assert!(matches!(
+            result,
+            Err(AirQualityError::RateLimited {
+                retry_after_secs: Some(30)
+            })
+        ));
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn callers_can_opt_out_of_rate_limit_retries() {
+        let server = Server::start(vec![
+            reply("429 Too Many Requests", &[("Retry-After", "1")], ""),
+            ok(),
+        ])
+        .await;
+        let http = client(3).with_rate_limit_retries(false);
+        assert_eq!(
+            status(&http, &server.url).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after_seconds() {
+        let server = Server::start(vec![
+            reply("503 Service Unavailable", &[("Retry-After", "1")], ""),
+            ok(),
+        ])
+        .await;
+
+        let started = Instant::now();
+        assert_eq!(status(&client(1), &server.url).await, StatusCode::OK);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after_date() {
+        let at = (Utc::now() + chrono::Duration::seconds(2))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let server = Server::start(vec![
+            reply("503 Service Unavailable", &[("Retry-After", &at)], ""),
+            ok(),
+        ])
+        .await;
+
+        let started = Instant::now();
+        assert_eq!(status(&client(1), &server.url).await, StatusCode::OK);
+        // The date is truncated to the second, so the wait is over one second.
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn returns_a_retry_after_longer_than_max_delay() {
+        let server = Server::start(vec![reply(
+            "503 Service Unavailable",
+            &[("Retry-After", "60")],
+            "",
+        )])
+        .await;
+
+        let started = Instant::now();
+        let status = status(&client(3), &server.url).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_timeouts_then_reports_them() {
+        let server = Server::start(vec![None]).await;
+        let http = HttpClient::new(HttpPolicy {
+            timeout: Duration::from_millis(200),
+            max_retries: 1,
+            base_delay: Duration::from_millis(1),
+            ..HttpPolicy::default()
+        });
+
+        let started = Instant::now();
+        let result: Result<serde_json::Value, _> = get_json(&http, &server.url).await;
+        assert!(matches!(result, Err(AirQualityError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn connection_errors_leave_the_key_out() {
+        // Nothing listens on a port that was just released.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/feed?key=secret", listener.local_addr().unwrap());
+        drop(listener);
+
+        let result: Result<serde_json::Value, _> = get_json(&client(0), &url).await;
+        match result {
+            Err(AirQualityError::UpstreamUnavailable(message)) => {
+                assert!(!message.contains("secret"), "{}", message)
+            }
+            other => panic!("expected UpstreamUnavailable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_both_retry_after_forms() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap();
+        assert_eq!(parse_retry_after(" 120 ", now), Some(120));
+        assert_eq!(
+            parse_retry_after("Fri, 10 Jan 2025 08:01:30 GMT", now),
+            Some(90)
+        );
+        assert_eq!(
+            parse_retry_after("Fri, 10 Jan 2025 07:59:00 GMT", now),
+            Some(0)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
@@ -1,21 +1,28 @@
-use crate::domain::models::Location;
-use crate::use_cases::check_air_quality::{AirQualityRepository, RawAirQualityData};
-use anyhow::{Context, Result};
+use crate::adapters::http::{self, HttpClient};
+use crate::adapters::quota::CallBudget;
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{Location, Pollutant, Pollutants};
+use crate::domain::weather::{Weather, WeatherCondition};
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
 use async_trait::async_trait;
+use reqwest::StatusCode;
 use serde::Deserialize;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct IQAirClient {
     api_key: String,
-    client: reqwest::Client,
+    http: HttpClient,
     city_coordinates: HashMap<String, (f64, f64)>,
+    budget: Option<CallBudget>,
 }
 
+/// IQAir wraps every answer in `{"status": ..., "data": ...}`; on failure the
+/// data is `{"message": "city_not_found"}` or similar.
 #[derive(Debug, Deserialize)]
 struct ApiResponse {
     status: String,
-    data: ApiData,
+    data: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,23 +35,78 @@ struct ApiData {
 #[derive(Debug, Deserialize)]
 struct Current {
     pollution: Pollution,
-    weather: Weather,
+    weather: CurrentWeather,
 }
 
 #[derive(Debug, Deserialize)]
 struct Pollution {
     #[serde(rename = "aqius")]
     aqi_us: i32,
+    #[serde(rename = "mainus", default)]
+    main_us: Option<String>,
+    #[serde(rename = "aqicn", default)]
+    aqi_cn: Option<i32>,
+    // Concentrations are only included on paid plans.
+    p2: Option<Concentration>,
+    p1: Option<Concentration>,
+    o3: Option<Concentration>,
+    n2: Option<Concentration>,
+    s2: Option<Concentration>,
+    co: Option<Concentration>,
 }
 
 #[derive(Debug, Deserialize)]
-struct Weather {
-    tp: i32,
-    hu: i32,
+struct Concentration {
+    conc: f64,
+}
+
+impl Pollution {
+    /// IQAir reports particulates in µg/m³, O₃/NO₂/SO₂ in ppb and CO in ppm.
+    fn pollutants(&self) -> Pollutants {
+        let conc = |c: &Option<Concentration>| c.as_ref().map(|c| c.conc);
+        Pollutants {
+            pm25: conc(&self.p2),
+            pm10: conc(&self.p1),
+            o3: conc(&self.o3).map(|ppb| Pollutant::O3.ppb_to_ugm3(ppb)),
+            no2: conc(&self.n2).map(|ppb| Pollutant::No2.ppb_to_ugm3(ppb)),
+            so2: conc(&self.s2).map(|ppb| Pollutant::So2.ppb_to_ugm3(ppb)),
+            co: conc(&self.co).map(|ppm| Pollutant::Co.ppb_to_ugm3(ppm * 1000.0)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentWeather {
+    tp: Option<i32>,
+    hu: Option<i32>,
+    /// Pressure in hPa.
+    pr: Option<f64>,
+    /// Wind speed in m/s.
+    ws: Option<f64>,
+    wd: Option<f64>,
+    ic: Option<String>,
+}
+
+impl CurrentWeather {
+    fn to_weather(&self) -> Weather {
+        Weather {
+            temperature: self.tp,
+            humidity: self.hu,
+            wind_speed_kmh: self.ws.map(|ms| ms * 3.6),
+            wind_direction_deg: self.wd,
+            pressure_hpa: self.pr,
+            condition: self
+                .ic
+                .as_deref()
+                .and_then(WeatherCondition::from_icon_code),
+        }
+    }
 }
 
 impl IQAirClient {
-    pub fn new(api_key: String) -> Self {
+    /// 429s aren't retried: they would spend more of the plan's calls, and the
+    /// budget is what paces them.
+    pub fn new(api_key: String, http: HttpClient) -> Self {
         let mut city_coordinates = HashMap::new();
         // Cities not directly supported by IQAir API but available via coordinates
         city_coordinates.insert("phan thong".to_string(), (13.4617, 101.0817));
@@ -52,11 +114,18 @@ impl IQAirClient {
 
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            http: http.with_rate_limit_retries(false),
             city_coordinates,
+            budget: None,
         }
     }
 
+    /// Paces calls to stay within the plan's per-minute and monthly limits.
+    pub fn with_budget(mut self, budget: CallBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     fn build_city_url(&self, city: &str, state: &str, country: &str) -> String {
         format!(
             "https://api.airvisual.com/v2/city?city={}&state={}&country={}&key={}",
@@ -74,55 +143,277 @@ impl IQAirClient {
         )
     }
 
-    async fn fetch_api(&self, url: &str) -> Result<ApiResponse> {
-        let response: ApiResponse = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .context("Failed to fetch air quality data")?
-            .error_for_status()
-            .context("API returned error")?
-            .json()
+    async fn fetch_api(
+        &self,
+        url: &str,
+        place: &str,
+        priority: FetchPriority,
+    ) -> Result<ApiData, AirQualityError> {
+        if let Some(budget) = &self.budget {
+            budget.acquire(priority).await?;
+        }
+
+        let response = self
+            .http
+            .send(self.http.get(url))
             .await
-            .context("Failed to parse response")?;
+            .map_err(http::request_error)?;
+        let status = response.status();
+        let retry_after_secs = http::retry_after_secs(&response);
+        let body = response.text().await.map_err(http::request_error)?;
 
-        if response.status != "success" {
-            anyhow::bail!("API error: {}", response.status);
-        }
+        parse_response(status, retry_after_secs, &body, place)
+    }
+}
+
+/// Unwraps IQAir's envelope, mapping its error messages onto our errors.
+/// Bodies that aren't an envelope fall back to the HTTP status.
+fn parse_response(
+    status: StatusCode,
+    retry_after_secs: Option<u64>,
+    body: &str,
+    place: &str,
+) -> Result<ApiData, AirQualityError> {
+    let envelope: ApiResponse = match serde_json::from_str(body) {
+        Ok(envelope) => envelope,
+        Err(e) if status.is_success() => return Err(AirQualityError::Parse(e.to_string())),
+        Err(_) => return Err(http::status_error(status, retry_after_secs)),
+    };
+
+    if envelope.status != "success" {
+        let message = envelope.data["message"]
+            .as_str()
+            .unwrap_or(&envelope.status);
+        return Err(match message {
+            "city_not_found" | "no_nearest_station" | "node_not_found" => {
+                AirQualityError::CityNotFound(place.to_string())
+            }
+            "call_limit_reached" | "too_many_requests" => {
+                AirQualityError::RateLimited { retry_after_secs }
+            }
+            "incorrect_api_key"
+            | "api_key_expired"
+            | "permission_denied"
+            | "feature_not_available" => AirQualityError::InvalidApiKey,
+            other => AirQualityError::UpstreamUnavailable(format!("IQAir: {}", other)),
+        });
+    }
+
+    serde_json::from_value(envelope.data).map_err(|e| AirQualityError::Parse(e.to_string()))
+}
 
-        Ok(response)
+impl ApiData {
+    fn into_reading(self) -> RawAirQualityData {
+        let pollution = self.current.pollution;
+        RawAirQualityData {
+            city: self.city,
+            state: self.state,
+            aqi: pollution.aqi_us,
+            pollutants: pollution.pollutants(),
+            main_pollutant: pollution
+                .main_us
+                .as_deref()
+                .and_then(Pollutant::from_iqair_code),
+            aqi_cn: pollution.aqi_cn,
+            aqi_th: None,
+            weather: self.current.weather.to_weather(),
+            source: "IQAir".to_string(),
+            spread: None,
+            stale_since: None,
+            cached: false,
+        }
     }
 }
 
 #[async_trait]
 impl AirQualityRepository for IQAirClient {
-    async fn get_air_quality(&self, location: &Location) -> Result<RawAirQualityData> {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
         let (city, state, country) = location.city_state_country();
 
         // Try city name first
         let city_url = self.build_city_url(&city, &state, &country);
-        let response = match self.fetch_api(&city_url).await {
-            Ok(resp) => resp,
-            Err(_) => {
+        let data = match self.fetch_api(&city_url, &city, priority).await {
+            Err(AirQualityError::CityNotFound(_)) => {
                 // Fallback: check if we have coordinates for this city
-                let city_lower = city.to_lowercase();
-                if let Some(&(lat, lon)) = self.city_coordinates.get(&city_lower) {
-                    let coords_url = self.build_coords_url(lat, lon);
-                    self.fetch_api(&coords_url).await?
-                } else {
-                    // No fallback available, return original error
-                    anyhow::bail!("City '{}' not found in IQAir API", city);
+                match self.city_coordinates.get(&city.to_lowercase()) {
+                    Some(&(lat, lon)) => {
+                        let coords_url = self.build_coords_url(lat, lon);
+                        self.fetch_api(&coords_url, &city, priority).await?
+                    }
+                    None => return Err(AirQualityError::CityNotFound(city)),
                 }
             }
+            result => result?,
         };
 
-        Ok(RawAirQualityData {
-            city: response.data.city,
-            state: response.data.state,
-            aqi: response.data.current.pollution.aqi_us,
-            temperature: response.data.current.weather.tp,
-            humidity: response.data.current.weather.hu,
-        })
+        Ok(data.into_reading())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITY: &str = include_str!("../../tests/fixtures/iqair/city.json");
+
+    fn fail(message: &str) -> String {
+        format!(r#"{{"status":"fail","data":{{"message":"{}"}}}}"#, message)
+    }
+
+    fn parse(status: u16, body: &str) -> Result<ApiData, AirQualityError> {
+        let status = StatusCode::from_u16(status).unwrap();
+        parse_response(status, Some(60), body, "Ban Suan")
+    }
+
+    #[test]
+    fn parses_a_successful_answer() {
+        let raw = parse(200, CITY).unwrap().into_reading();
+
+        assert_eq!(
+            (raw.city.as_str(), raw.state.as_str()),
+            ("Chon Buri", "Chon Buri")
+        );
+        assert_eq!(raw.aqi, 87);
+        assert_eq!(raw.aqi_cn, Some(62));
+        assert_eq!(raw.main_pollutant, Some(Pollutant::Pm25));
+        assert_eq!(raw.source, "IQAir");
+    }
+
+    /// The fixture's reading with `edit` applied to its `current` block.
+    fn edited(edit: impl FnOnce(&mut serde_json::Value)) -> RawAirQualityData {
+        let mut json: serde_json::Value = serde_json::from_str(CITY).unwrap();
+        edit(&mut json["data"]["current"]);
+        parse(200, &json.to_string()).unwrap().into_reading()
+    }
+
+    #[test]
+    fn converts_every_pollutant_to_micrograms() {
+        let pollutants = parse(200, CITY).unwrap().into_reading().pollutants;
+
+        // (pollutant, as reported, µg/m³ to one decimal)
+        let cases = [
+            (Pollutant::Pm25, "29.3 µg/m³", "29.3"),
+            (Pollutant::Pm10, "41 µg/m³", "41.0"),
+            (Pollutant::O3, "24 ppb", "47.1"),
+            (Pollutant::No2, "10.5 ppb", "19.8"),
+            (Pollutant::So2, "1.2 ppb", "3.1"),
+            (Pollutant::Co, "0.4 ppm", "458.2"),
+        ];
+        for (pollutant, reported, ugm3) in cases {
+            let value = pollutants.get(pollutant).map(|v| format!("{:.1}", v));
+            assert_eq!(value.as_deref(), Some(ugm3), "{:?} {}", pollutant, reported);
+        }
+    }
+
+    #[test]
+    fn free_plans_report_no_concentrations() {
+        let raw = edited(|current| {
+            let pollution = current["pollution"].as_object_mut().unwrap();
+            for code in ["p2", "p1", "o3", "n2", "s2", "co"] {
+                pollution.remove(code);
+            }
+        });
+
+        assert_eq!(raw.pollutants, Pollutants::default());
+        assert_eq!(raw.aqi, 87);
+    }
+
+    #[test]
+    fn parses_the_weather() {
+        let weather = parse(200, CITY).unwrap().into_reading().weather;
+
+        assert_eq!(weather.temperature, Some(31));
+        assert_eq!(weather.humidity, Some(58));
+        assert_eq!(weather.pressure_hpa, Some(1012.0));
+        // 4.12 m/s
+        assert_eq!(
+            weather
+                .wind_speed_kmh
+                .map(|kmh| format!("{:.1}", kmh))
+                .as_deref(),
+            Some("14.8")
+        );
+        assert_eq!(weather.wind_compass(), Some("NE"));
+        assert_eq!(weather.condition, Some(WeatherCondition::PartlyCloudy));
+    }
+
+    #[test]
+    fn missing_weather_fields_stay_unknown() {
+        let raw = edited(|current| {
+            current["weather"] =
+                serde_json::json!({ "ts": "2025-01-10T08:00:00.000Z", "ic": "99x" });
+        });
+
+        assert_eq!(raw.weather, Weather::default());
+    }
+
+    #[test]
+    fn main_pollutant_codes() {
+        let cases = [
+            ("p2", Some(Pollutant::Pm25)),
+            ("p1", Some(Pollutant::Pm10)),
+            ("o3", Some(Pollutant::O3)),
+            ("n2", Some(Pollutant::No2)),
+            ("s2", Some(Pollutant::So2)),
+            ("co", Some(Pollutant::Co)),
+            ("pm25", None),
+        ];
+        for (code, pollutant) in cases {
+            let raw = edited(|current| current["pollution"]["mainus"] = code.into());
+            assert_eq!(raw.main_pollutant, pollutant, "{}", code);
+        }
+    }
+
+    #[test]
+    fn unknown_city() {
+        assert!(matches!(
+            parse(400, &fail("city_not_found")),
+            Err(AirQualityError::CityNotFound(place)) if place == "Ban Suan"
+        ));
+    }
+
+    #[test]
+    fn call_limit_reached() {
+        assert!(matches!(
+            parse(429, &fail("call_limit_reached")),
+            Err(AirQualityError::RateLimited {
+                retry_after_secs: Some(60)
+            })
+        ));
+    }
+
+    #[test]
+    fn incorrect_api_key() {
+        assert!(matches!(
+            parse(401, &fail("incorrect_api_key")),
+            Err(AirQualityError::InvalidApiKey)
+        ));
+    }
+
+    #[test]
+    fn server_errors() {
+        // A proxy's HTML page falls back to the status.
+        assert!(matches!(
+            parse(502, "<html><body>Bad Gateway</body></html>"),
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "HTTP 502 Bad Gateway"
+        ));
+        // An envelope keeps IQAir's own message.
+        assert!(matches!(
+            parse(503, &fail("server_busy")),
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "IQAir: server_busy"
+        ));
+    }
+
+    #[test]
+    fn malformed_success_is_a_parse_error() {
+        assert!(matches!(parse(200, "{}"), Err(AirQualityError::Parse(_))));
+        assert!(matches!(
+            parse(200, r#"{"status":"success","data":{"city":"Chon Buri"}}"#),
+            Err(AirQualityError::Parse(_))
+        ));
     }
 }
@@ -0,0 +1,237 @@
+use crate::domain::i18n::Locale;
+use crate::domain::models::{AirQualityData, AirQualityLevel, Location, Subscription};
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use crate::use_cases::alert_air_quality::AlertStateRepository;
+use crate::use_cases::chat_settings::ChatSettingsRepository;
+use crate::use_cases::check_air_quality::{ReadingRepository, READING_RETENTION_DAYS};
+use crate::use_cases::manage_subscriptions::SubscriptionRepository;
+use crate::use_cases::storage::QuotaUsageRepository;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Process-local store; everything is lost on restart.
+///
+/// Readings older than a week are dropped since nothing looks further back.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    subscriptions: Vec<Subscription>,
+    readings: HashMap<String, Vec<(DateTime<Utc>, AirQualityData)>>,
+    alert_levels: HashMap<(String, String), AirQualityLevel>,
+    aqi_standards: HashMap<i64, AqiStandard>,
+    locales: HashMap<i64, Locale>,
+    templates: HashMap<i64, MessageTemplate>,
+    quota_usage: HashMap<String, ((i32, u32), u32)>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SubscriptionRepository for InMemoryStore {
+    async fn save_subscription(&self, subscription: Subscription) -> Result<()> {
+        let subscriptions = &mut self.inner.lock().unwrap().subscriptions;
+        subscriptions.retain(|s| {
+            s.chat_id != subscription.chat_id || s.location.key() != subscription.location.key()
+        });
+        subscriptions.push(subscription);
+        Ok(())
+    }
+
+    async fn delete_subscriptions(
+        &self,
+        chat_id: i64,
+        location: Option<&Location>,
+    ) -> Result<usize> {
+        let subscriptions = &mut self.inner.lock().unwrap().subscriptions;
+        let before = subscriptions.len();
+        subscriptions.retain(|s| {
+            s.chat_id != chat_id || location.is_some_and(|l| l.key() != s.location.key())
+        });
+        Ok(before - subscriptions.len())
+    }
+
+    async fn subscriptions_for_chat(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        let inner = self.inner.lock().unwrap();
+        Ok(inner
+            .subscriptions
+            .iter()
+            .filter(|s| s.chat_id == chat_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn all_subscriptions(&self) -> Result<Vec<Subscription>> {
+        Ok(self.inner.lock().unwrap().subscriptions.clone())
+    }
+}
+
+#[async_trait]
+impl ReadingRepository for InMemoryStore {
+    async fn save_reading(
+        &self,
+        location: &Location,
+        recorded_at: DateTime<Utc>,
+        data: &AirQualityData,
+    ) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let readings = inner.readings.entry(location.key()).or_default();
+        let cutoff = recorded_at - Duration::days(READING_RETENTION_DAYS);
+        readings.retain(|(at, _)| *at >= cutoff);
+        // Kept oldest first, like the SQLite store's ORDER BY.
+        let index = readings.partition_point(|(at, _)| *at <= recorded_at);
+        readings.insert(index, (recorded_at, data.clone()));
+        Ok(())
+    }
+
+    async fn readings_since(
+        &self,
+        location: &Location,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>> {
+        let inner = self.inner.lock().unwrap();
+        Ok(inner
+            .readings
+            .get(&location.key())
+            .map(|readings| {
+                readings
+                    .iter()
+                    .filter(|(at, _)| *at >= since)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl AlertStateRepository for InMemoryStore {
+    async fn last_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+    ) -> Result<Option<AirQualityLevel>> {
+        let key = (chat_id.to_string(), location.key());
+        Ok(self.inner.lock().unwrap().alert_levels.get(&key).copied())
+    }
+
+    async fn save_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+        level: AirQualityLevel,
+    ) -> Result<()> {
+        let key = (chat_id.to_string(), location.key());
+        self.inner.lock().unwrap().alert_levels.insert(key, level);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChatSettingsRepository for InMemoryStore {
+    async fn aqi_standard(&self, chat_id: i64) -> Result<Option<AqiStandard>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .aqi_standards
+            .get(&chat_id)
+            .copied())
+    }
+
+    async fn save_aqi_standard(&self, chat_id: i64, standard: AqiStandard) -> Result<()> {
+        self.inner
+            .lock()
+            .unwrap()
+            .aqi_standards
+            .insert(chat_id, standard);
+        Ok(())
+    }
+
+    async fn locale(&self, chat_id: i64) -> Result<Option<Locale>> {
+        Ok(self.inner.lock().unwrap().locales.get(&chat_id).copied())
+    }
+
+    async fn save_locale(&self, chat_id: i64, locale: Locale) -> Result<()> {
+        self.inner.lock().unwrap().locales.insert(chat_id, locale);
+        Ok(())
+    }
+
+    async fn template(&self, chat_id: i64) -> Result<Option<MessageTemplate>> {
+        Ok(self.inner.lock().unwrap().templates.get(&chat_id).cloned())
+    }
+
+    async fn save_template(&self, chat_id: i64, template: Option<&MessageTemplate>) -> Result<()> {
+        let templates = &mut self.inner.lock().unwrap().templates;
+        match template {
+            Some(template) => templates.insert(chat_id, template.clone()),
+            None => templates.remove(&chat_id),
+        };
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QuotaUsageRepository for InMemoryStore {
+    async fn quota_used(&self, api: &str, month: (i32, u32)) -> Result<u32> {
+        let inner = self.inner.lock().unwrap();
+        Ok(inner
+            .quota_usage
+            .get(api)
+            .filter(|(recorded, _)| *recorded == month)
+            .map_or(0, |(_, used)| *used))
+    }
+
+    async fn save_quota_used(&self, api: &str, month: (i32, u32), used: u32) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.quota_usage.insert(api.to_string(), (month, used));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::storage_contract;
+
+    #[tokio::test]
+    async fn subscriptions() {
+        storage_contract::subscriptions(InMemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn readings() {
+        storage_contract::readings(InMemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn reading_retention() {
+        storage_contract::reading_retention(InMemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn alert_state() {
+        storage_contract::alert_state(InMemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn chat_settings() {
+        storage_contract::chat_settings(InMemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn quota_usage() {
+        storage_contract::quota_usage(InMemoryStore::new()).await;
+    }
+}
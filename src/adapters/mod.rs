@@ -1,4 +1,18 @@
+pub mod air4thai;
 pub mod bot;
+pub mod cache;
+pub mod chart;
+pub mod clock;
 pub mod config;
+pub mod consensus;
+pub mod failover;
+pub mod geocoding;
+pub mod http;
 pub mod iqair;
+pub mod memory;
+pub mod open_meteo;
+pub mod quota;
+pub mod sqlite;
+#[cfg(test)]
+mod storage_contract;
 pub mod telegram;
@@ -0,0 +1,450 @@
+use crate::adapters::geocoding::{Geocoder, Place};
+use crate::adapters::http::{self, HttpClient};
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{AirQualityData, Location, Pollutant, Pollutants};
+use crate::domain::weather::{Weather, WeatherCondition};
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, RawAirQualityData};
+use crate::use_cases::forecast_air_quality::ForecastRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const AIR_QUALITY_FIELDS: &str = "us_aqi,pm2_5,pm10,ozone,nitrogen_dioxide,sulphur_dioxide,\
+    carbon_monoxide,us_aqi_pm2_5,us_aqi_pm10,us_aqi_ozone,us_aqi_nitrogen_dioxide,\
+    us_aqi_sulphur_dioxide,us_aqi_carbon_monoxide";
+const WEATHER_FIELDS: &str = "temperature_2m,relative_humidity_2m,wind_speed_10m,\
+    wind_direction_10m,surface_pressure,weather_code";
+
+/// Free, keyless air quality from the Open-Meteo CAMS model, looked up by coordinates.
+#[derive(Debug, Clone)]
+pub struct OpenMeteoClient {
+    http: HttpClient,
+    geocoder: Geocoder,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityResponse {
+    current: AirQualityCurrent,
+}
+
+/// Concentrations are all in µg/m³; the `us_aqi_*` fields are per-pollutant sub-indices.
+/// Also built from each hour of a forecast.
+#[derive(Debug, Deserialize)]
+struct AirQualityCurrent {
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    nitrogen_dioxide: Option<f64>,
+    sulphur_dioxide: Option<f64>,
+    carbon_monoxide: Option<f64>,
+    us_aqi_pm2_5: Option<f64>,
+    us_aqi_pm10: Option<f64>,
+    us_aqi_ozone: Option<f64>,
+    us_aqi_nitrogen_dioxide: Option<f64>,
+    us_aqi_sulphur_dioxide: Option<f64>,
+    us_aqi_carbon_monoxide: Option<f64>,
+}
+
+impl AirQualityCurrent {
+    fn pollutants(&self) -> Pollutants {
+        Pollutants {
+            pm25: self.pm2_5,
+            pm10: self.pm10,
+            o3: self.ozone,
+            no2: self.nitrogen_dioxide,
+            so2: self.sulphur_dioxide,
+            co: self.carbon_monoxide,
+        }
+    }
+
+    /// The pollutant with the highest sub-index sets the overall US AQI.
+    fn main_pollutant(&self) -> Option<Pollutant> {
+        [
+            (Pollutant::Pm25, self.us_aqi_pm2_5),
+            (Pollutant::Pm10, self.us_aqi_pm10),
+            (Pollutant::O3, self.us_aqi_ozone),
+            (Pollutant::No2, self.us_aqi_nitrogen_dioxide),
+            (Pollutant::So2, self.us_aqi_sulphur_dioxide),
+            (Pollutant::Co, self.us_aqi_carbon_monoxide),
+        ]
+        .into_iter()
+        .filter_map(|(pollutant, aqi)| Some((pollutant, aqi?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(pollutant, _)| pollutant)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherResponse {
+    current: WeatherCurrent,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherCurrent {
+    temperature_2m: f64,
+    relative_humidity_2m: f64,
+    wind_speed_10m: Option<f64>,
+    wind_direction_10m: Option<f64>,
+    surface_pressure: Option<f64>,
+    weather_code: Option<i32>,
+}
+
+impl WeatherCurrent {
+    fn to_weather(&self) -> Weather {
+        Weather {
+            temperature: Some(self.temperature_2m.round() as i32),
+            humidity: Some(self.relative_humidity_2m.round() as i32),
+            wind_speed_kmh: self.wind_speed_10m,
+            wind_direction_deg: self.wind_direction_10m,
+            pressure_hpa: self.surface_pressure,
+            condition: self.weather_code.and_then(WeatherCondition::from_wmo_code),
+        }
+    }
+}
+
+/// Hourly series keyed by field name, with times as Unix timestamps.
+#[derive(Debug, Deserialize)]
+struct HourlyResponse {
+    hourly: Hourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hourly {
+    time: Vec<i64>,
+    #[serde(flatten)]
+    series: HashMap<String, Vec<Option<f64>>>,
+}
+
+impl Hourly {
+    fn value(&self, field: &str, index: usize) -> Option<f64> {
+        self.series.get(field)?.get(index).copied().flatten()
+    }
+
+    fn air_quality(&self, index: usize) -> AirQualityCurrent {
+        AirQualityCurrent {
+            us_aqi: self.value("us_aqi", index),
+            pm2_5: self.value("pm2_5", index),
+            pm10: self.value("pm10", index),
+            ozone: self.value("ozone", index),
+            nitrogen_dioxide: self.value("nitrogen_dioxide", index),
+            sulphur_dioxide: self.value("sulphur_dioxide", index),
+            carbon_monoxide: self.value("carbon_monoxide", index),
+            us_aqi_pm2_5: self.value("us_aqi_pm2_5", index),
+            us_aqi_pm10: self.value("us_aqi_pm10", index),
+            us_aqi_ozone: self.value("us_aqi_ozone", index),
+            us_aqi_nitrogen_dioxide: self.value("us_aqi_nitrogen_dioxide", index),
+            us_aqi_sulphur_dioxide: self.value("us_aqi_sulphur_dioxide", index),
+            us_aqi_carbon_monoxide: self.value("us_aqi_carbon_monoxide", index),
+        }
+    }
+
+    fn weather(&self, index: usize) -> Weather {
+        Weather {
+            temperature: self
+                .value("temperature_2m", index)
+                .map(|t| t.round() as i32),
+            humidity: self
+                .value("relative_humidity_2m", index)
+                .map(|h| h.round() as i32),
+            wind_speed_kmh: self.value("wind_speed_10m", index),
+            wind_direction_deg: self.value("wind_direction_10m", index),
+            pressure_hpa: self.value("surface_pressure", index),
+            condition: self
+                .value("weather_code", index)
+                .and_then(|code| WeatherCondition::from_wmo_code(code as i32)),
+        }
+    }
+}
+
+impl OpenMeteoClient {
+    pub fn new(http: HttpClient) -> Self {
+        Self {
+            geocoder: Geocoder::new(http.clone()),
+            http,
+        }
+    }
+
+    fn build_air_quality_url(&self, lat: f64, lon: f64) -> String {
+        format!(
+            "https://air-quality-api.open-meteo.com/v1/air-quality?latitude={}&longitude={}&current={}",
+            lat, lon, AIR_QUALITY_FIELDS
+        )
+    }
+
+    fn build_weather_url(&self, lat: f64, lon: f64) -> String {
+        format!(
+            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current={}",
+            lat, lon, WEATHER_FIELDS
+        )
+    }
+
+    fn build_air_quality_forecast_url(&self, lat: f64, lon: f64, hours: u32) -> String {
+        format!(
+            "https://air-quality-api.open-meteo.com/v1/air-quality?latitude={}&longitude={}&hourly={}&forecast_hours={}&timeformat=unixtime",
+            lat, lon, AIR_QUALITY_FIELDS, hours
+        )
+    }
+
+    fn build_weather_forecast_url(&self, lat: f64, lon: f64, hours: u32) -> String {
+        format!(
+            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly={}&forecast_hours={}&timeformat=unixtime",
+            lat, lon, WEATHER_FIELDS, hours
+        )
+    }
+
+    async fn fetch_api<T: DeserializeOwned>(&self, url: &str) -> Result<T, AirQualityError> {
+        http::get_json(&self.http, url).await
+    }
+}
+
+#[async_trait]
+impl AirQualityRepository for OpenMeteoClient {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        _priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        let place = self.geocoder.resolve(location).await?;
+
+        let air_url = self.build_air_quality_url(place.lat, place.lon);
+        let weather_url = self.build_weather_url(place.lat, place.lon);
+        let (air, weather) = tokio::try_join!(
+            self.fetch_api::<AirQualityResponse>(&air_url),
+            self.fetch_api::<WeatherResponse>(&weather_url),
+        )?;
+
+        current_reading(location, place, &air, &weather)
+    }
+}
+
+/// Combines the current air quality and weather for `place`.
+fn current_reading(
+    location: &Location,
+    place: Place,
+    air: &AirQualityResponse,
+    weather: &WeatherResponse,
+) -> Result<RawAirQualityData, AirQualityError> {
+    let aqi = air.current.us_aqi.ok_or_else(|| {
+        AirQualityError::UpstreamUnavailable(format!("Open-Meteo has no AQI for {}", location.name))
+    })?;
+
+    Ok(RawAirQualityData {
+        city: place.name,
+        state: place.state,
+        aqi: aqi.round() as i32,
+        pollutants: air.current.pollutants(),
+        main_pollutant: air.current.main_pollutant(),
+        aqi_cn: None,
+        aqi_th: None,
+        weather: weather.current.to_weather(),
+        source: "Open-Meteo".to_string(),
+        spread: None,
+        stale_since: None,
+        cached: false,
+    })
+}
+
+#[async_trait]
+impl ForecastRepository for OpenMeteoClient {
+    async fn get_forecast(
+        &self,
+        location: &Location,
+        hours: u32,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>, AirQualityError> {
+        let place = self.geocoder.resolve(location).await?;
+
+        let air_url = self.build_air_quality_forecast_url(place.lat, place.lon, hours);
+        let weather_url = self.build_weather_forecast_url(place.lat, place.lon, hours);
+        let (air, weather) = tokio::try_join!(
+            self.fetch_api::<HourlyResponse>(&air_url),
+            self.fetch_api::<HourlyResponse>(&weather_url),
+        )?;
+
+        Ok(forecast_hours(location, &air, &weather))
+    }
+}
+
+/// Pairs each air quality hour with the weather for the same timestamp. Hours
+/// the model has no AQI or PM2.5 for are left out.
+fn forecast_hours(
+    location: &Location,
+    air: &HourlyResponse,
+    weather: &HourlyResponse,
+) -> Vec<(DateTime<Utc>, AirQualityData)> {
+    let weather_hours: HashMap<i64, usize> = weather
+        .hourly
+        .time
+        .iter()
+        .enumerate()
+        .map(|(index, &time)| (time, index))
+        .collect();
+
+    air.hourly
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &time)| {
+            let at = DateTime::from_timestamp(time, 0)?;
+            let current = air.hourly.air_quality(index);
+            let data = AirQualityData {
+                location: location.clone(),
+                aqi: current.us_aqi?.round() as i32,
+                pm25: current.pm2_5?,
+                pm25_estimated: false,
+                pollutants: current.pollutants().without_invalid(),
+                main_pollutant: current.main_pollutant(),
+                aqi_cn: None,
+                aqi_th: None,
+                weather: weather_hours
+                    .get(&time)
+                    .map(|&index| weather.hourly.weather(index))
+                    .unwrap_or_default(),
+                source: "Open-Meteo".to_string(),
+                spread: None,
+                stale_since: None,
+            };
+            Some((at, data))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn fixture<T: DeserializeOwned>(json: &str) -> T {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn ban_suan() -> Location {
+        Location::from_city("Ban Suan", "Chon Buri", "Thailand")
+    }
+
+    #[test]
+    fn parses_current_readings() {
+        let air: AirQualityResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/current_air_quality.json"
+        ));
+        let weather: WeatherResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/current_weather.json"
+        ));
+        let place = Place {
+            name: "Ban Suan".to_string(),
+            state: "Chon Buri".to_string(),
+            lat: 13.4,
+            lon: 101.1,
+        };
+
+        let raw = current_reading(&ban_suan(), place, &air, &weather).unwrap();
+
+        assert_eq!(
+            (raw.city.as_str(), raw.state.as_str()),
+            ("Ban Suan", "Chon Buri")
+        );
+        assert_eq!(raw.aqi, 112);
+        assert_eq!(raw.pollutants.pm25, Some(39.8));
+        assert_eq!(raw.pollutants.co, Some(410.0));
+        assert_eq!(raw.main_pollutant, Some(Pollutant::Pm25));
+        assert_eq!(raw.weather.temperature, Some(30));
+        assert_eq!(raw.weather.humidity, Some(64));
+        assert_eq!(raw.weather.wind_compass(), Some("NE"));
+        assert_eq!(raw.weather.condition, Some(WeatherCondition::PartlyCloudy));
+        assert_eq!(raw.source, "Open-Meteo");
+    }
+
+    #[test]
+    fn missing_current_aqi_is_unavailable() {
+        let mut air: AirQualityResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/current_air_quality.json"
+        ));
+        air.current.us_aqi = None;
+        let weather: WeatherResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/current_weather.json"
+        ));
+        let place = Place {
+            name: "Ban Suan".to_string(),
+            state: String::new(),
+            lat: 13.4,
+            lon: 101.1,
+        };
+
+        assert!(matches!(
+            current_reading(&ban_suan(), place, &air, &weather),
+            Err(AirQualityError::UpstreamUnavailable(_))
+        ));
+    }
+
+    #[test]
+    fn parses_hourly_forecasts_with_nulls() {
+        let air: HourlyResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/hourly_air_quality.json"
+        ));
+        let weather: HourlyResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/hourly_weather.json"
+        ));
+
+        let hours = forecast_hours(&ban_suan(), &air, &weather);
+
+        // 02:00Z has no AQI and 04:00Z no PM2.5, so both are left out.
+        let times: Vec<String> = hours.iter().map(|(at, _)| at.to_rfc3339()).collect();
+        assert_eq!(
+            times,
+            ["2025-01-10T01:00:00+00:00", "2025-01-10T03:00:00+00:00"]
+        );
+
+        let (_, first) = &hours[0];
+        assert_eq!((first.aqi, first.pm25), (96, 33.1));
+        assert_eq!(first.main_pollutant, Some(Pollutant::Pm25));
+        // The weather series starts an hour later, so this hour has none.
+        assert_eq!(first.weather, Weather::default());
+
+        let (_, second) = &hours[1];
+        assert_eq!((second.aqi, second.pm25), (72, 22.4));
+        assert_eq!(second.pollutants.pm10, None);
+        assert_eq!(second.pollutants.no2, None);
+        // Negative concentrations mean "no data".
+        assert_eq!(second.pollutants.so2, None);
+        assert_eq!(second.main_pollutant, Some(Pollutant::O3));
+        assert_eq!(second.weather.temperature, Some(30));
+        assert_eq!(second.weather.humidity, Some(70));
+        assert_eq!(second.weather.condition, Some(WeatherCondition::Rain));
+        assert_eq!(second.location.name, "Ban Suan");
+    }
+
+    #[test]
+    fn hourly_times_are_unix_timestamps_whatever_the_response_offset() {
+        // Both fixtures report utc_offset_seconds = 25200 (+07:00); with
+        // timeformat=unixtime the times are absolute, so 01:00Z is 08:00 local.
+        let air: HourlyResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/hourly_air_quality.json"
+        ));
+        let weather: HourlyResponse = fixture(include_str!(
+            "../../tests/fixtures/open_meteo/hourly_weather.json"
+        ));
+        let bangkok = FixedOffset::east_opt(7 * 3600).unwrap();
+
+        let hours = forecast_hours(&ban_suan(), &air, &weather);
+
+        assert_eq!(
+            hours[0].0.with_timezone(&bangkok).to_rfc3339(),
+            "2025-01-10T08:00:00+07:00"
+        );
+    }
+
+    #[test]
+    fn a_missing_hourly_series_reads_as_null() {
+        let air: HourlyResponse =
+            fixture(r#"{"hourly": {"time": [1736470800], "us_aqi": [50.0], "pm2_5": [12.0]}}"#);
+        let weather: HourlyResponse = fixture(r#"{"hourly": {"time": []}}"#);
+
+        let hours = forecast_hours(&ban_suan(), &air, &weather);
+
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].1.pollutants.pm10, None);
+        assert_eq!(hours[0].1.main_pollutant, None);
+    }
+}
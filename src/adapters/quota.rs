@@ -0,0 +1,391 @@
+use crate::domain::errors::AirQualityError;
+use crate::use_cases::check_air_quality::FetchPriority;
+use crate::use_cases::storage::QuotaUsageRepository;
+use chrono::{DateTime, Datelike, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Share of the monthly quota kept back for scheduled broadcasts.
+const SCHEDULED_RESERVE_PERCENT: u32 = 10;
+
+/// Per-minute token bucket plus a monthly call counter for a metered API.
+///
+/// Interactive calls leave one token in the bucket (unless it only holds one)
+/// and stop once the monthly reserve is reached, so scheduled broadcasts keep
+/// working when the budget is low. The monthly count restarts from zero on
+/// deploy unless it is saved with `with_usage`.
+#[derive(Debug, Clone)]
+pub struct CallBudget {
+    per_minute: u32,
+    monthly: u32,
+    state: Arc<Mutex<State>>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    refilled_at: Instant,
+    month: (i32, u32),
+    used_this_month: u32,
+    /// Whether the saved count has been read back since startup.
+    restored: bool,
+}
+
+/// Where the monthly count is saved, under the API's name.
+#[derive(Clone)]
+struct Usage {
+    api: String,
+    store: Arc<dyn QuotaUsageRepository>,
+    /// Held while saving so a later count never lands before an earlier one.
+    saving: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl fmt::Debug for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Usage")
+            .field("api", &self.api)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CallBudget {
+    pub fn new(per_minute: u32, monthly: u32) -> Self {
+        let per_minute = per_minute.max(1);
+        let now = Utc::now();
+        Self {
+            per_minute,
+            monthly,
+            state: Arc::new(Mutex::new(State {
+                tokens: per_minute as f64,
+                refilled_at: Instant::now(),
+                month: (now.year(), now.month()),
+                used_this_month: 0,
+                restored: false,
+            })),
+            usage: None,
+        }
+    }
+
+    /// Keeps the monthly count in `store` under `api`, so a restart carries on
+    /// from it instead of from zero.
+    pub fn with_usage(mut self, api: &str, store: Arc<dyn QuotaUsageRepository>) -> Self {
+        self.usage = Some(Usage {
+            api: api.to_string(),
+            store,
+            saving: Arc::new(tokio::sync::Mutex::new(())),
+        });
+        self
+    }
+
+    /// Spends one call. Scheduled calls wait out the rate limit; interactive
+    /// calls are refused instead.
+    pub async fn acquire(&self, priority: FetchPriority) -> Result<(), AirQualityError> {
+        self.restore_usage(Utc::now()).await;
+        loop {
+            match self.try_acquire(priority, Utc::now(), Instant::now()) {
+                Err(AirQualityError::RateLimited {
+                    retry_after_secs: Some(secs),
+                }) if priority == FetchPriority::Scheduled => {
+                    info!("Rate limited, waiting {}s", secs);
+                    tokio::time::sleep(Duration::from_secs(secs)).await;
+                }
+                Ok(()) => {
+                    self.save_usage().await;
+                    return Ok(());
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Reads the saved count for this month once; a failed read is retried
+    /// on the next call.
+    async fn restore_usage(&self, now: DateTime<Utc>) {
+        let Some(usage) = &self.usage else {
+            return;
+        };
+        if self.state.lock().unwrap().restored {
+            return;
+        }
+
+        let month = (now.year(), now.month());
+        match usage.store.quota_used(&usage.api, month).await {
+            Ok(saved) => {
+                let mut state = self.state.lock().unwrap();
+                if !state.restored && state.month == month {
+                    state.used_this_month = state.used_this_month.max(saved);
+                    info!(
+                        "{} has used {} of {} monthly calls",
+                        usage.api, state.used_this_month, self.monthly
+                    );
+                }
+                state.restored = true;
+            }
+            Err(e) => warn!("Failed to read {} quota usage: {:#}", usage.api, e),
+        }
+    }
+
+    async fn save_usage(&self) {
+        let Some(usage) = &self.usage else {
+            return;
+        };
+        let _saving = usage.saving.lock().await;
+        let (month, used) = {
+            let state = self.state.lock().unwrap();
+            (state.month, state.used_this_month)
+        };
+        if let Err(e) = usage.store.save_quota_used(&usage.api, month, used).await {
+            warn!("Failed to save {} quota usage: {:#}", usage.api, e);
+        }
+    }
+
+    fn try_acquire(
+        &self,
+        priority: FetchPriority,
+        now: DateTime<Utc>,
+        instant: Instant,
+    ) -> Result<(), AirQualityError> {
+        let mut state = self.state.lock().unwrap();
+
+        let capacity = self.per_minute as f64;
+        let elapsed = instant
+            .saturating_duration_since(state.refilled_at)
+            .as_secs_f64();
+        state.tokens = (state.tokens + elapsed * capacity / 60.0).min(capacity);
+        state.refilled_at = state.refilled_at.max(instant);
+
+        if state.month != (now.year(), now.month()) {
+            state.month = (now.year(), now.month());
+            state.used_this_month = 0;
+        }
+
+        let (token_reserve, monthly_limit) = match priority {
+            FetchPriority::Scheduled => (0.0, self.monthly),
+            // A one-call bucket can't keep a token back without refusing
+            // every interactive call.
+            FetchPriority::Interactive => (
+                (capacity - 1.0).min(1.0),
+                self.monthly
+                    - (u64::from(self.monthly) * u64::from(SCHEDULED_RESERVE_PERCENT) / 100) as u32,
+            ),
+        };
+
+        if state.used_this_month >= monthly_limit {
+            warn!(
+                "{:?} call refused, {} of {} monthly calls used",
+                priority, state.used_this_month, self.monthly
+            );
+            return Err(AirQualityError::QuotaExhausted);
+        }
+        let needed = 1.0 + token_reserve;
+        if state.tokens < needed {
+            let wait = (needed - state.tokens) * 60.0 / capacity;
+            return Err(AirQualityError::RateLimited {
+                retry_after_secs: Some(wait.ceil() as u64),
+            });
+        }
+
+        state.tokens -= 1.0;
+        state.used_this_month += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::memory::InMemoryStore;
+    use chrono::TimeZone;
+
+    fn january() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap()
+    }
+
+    /// A budget whose clock starts at `start` in January.
+    fn budget(per_minute: u32, monthly: u32) -> (CallBudget, Instant) {
+        let budget = CallBudget::new(per_minute, monthly);
+        {
+            let mut state = budget.state.lock().unwrap();
+            state.month = (2025, 1);
+        }
+        let start = budget.state.lock().unwrap().refilled_at;
+        (budget, start)
+    }
+
+    fn retry_after(result: Result<(), AirQualityError>) -> Option<u64> {
+        match result {
+            Err(AirQualityError::RateLimited { retry_after_secs }) => retry_after_secs,
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn interactive_calls_leave_a_token_for_scheduled_ones() {
+        let (budget, start) = budget(3, 1000);
+        let now = january();
+        for _ in 0..2 {
+            budget
+                .try_acquire(FetchPriority::Interactive, now, start)
+                .unwrap();
+        }
+        assert_eq!(
+            retry_after(budget.try_acquire(FetchPriority::Interactive, now, start)),
+            Some(20)
+        );
+        budget
+            .try_acquire(FetchPriority::Scheduled, now, start)
+            .unwrap();
+        assert_eq!(
+            retry_after(budget.try_acquire(FetchPriority::Scheduled, now, start)),
+            Some(20)
+        );
+    }
+
+    #[test]
+    fn bucket_refills_over_the_minute() {
+        let (budget, start) = budget(6, 1000);
+        let now = january();
+        for _ in 0..6 {
+            budget
+                .try_acquire(FetchPriority::Scheduled, now, start)
+                .unwrap();
+        }
+        assert_eq!(
+            retry_after(budget.try_acquire(FetchPriority::Scheduled, now, start)),
+            Some(10)
+        );
+
+        let later = start + Duration::from_secs(10);
+        budget
+            .try_acquire(FetchPriority::Scheduled, now, later)
+            .unwrap();
+        assert!(budget
+            .try_acquire(FetchPriority::Scheduled, now, later)
+            .is_err());
+
+        // Refilling stops at the bucket size however long it sat idle.
+        let much_later = later + Duration::from_secs(3600);
+        for _ in 0..6 {
+            budget
+                .try_acquire(FetchPriority::Scheduled, now, much_later)
+                .unwrap();
+        }
+        assert!(budget
+            .try_acquire(FetchPriority::Scheduled, now, much_later)
+            .is_err());
+    }
+
+    #[test]
+    fn one_call_per_minute_still_serves_interactive_calls() {
+        let (budget, start) = budget(1, 1000);
+        let now = january();
+        budget
+            .try_acquire(FetchPriority::Interactive, now, start)
+            .unwrap();
+        assert_eq!(
+            retry_after(budget.try_acquire(FetchPriority::Interactive, now, start)),
+            Some(60)
+        );
+        budget
+            .try_acquire(
+                FetchPriority::Interactive,
+                now,
+                start + Duration::from_secs(60),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn monthly_reserve_is_kept_for_scheduled_calls() {
+        let (budget, start) = budget(1000, 20);
+        let now = january();
+        for _ in 0..18 {
+            budget
+                .try_acquire(FetchPriority::Interactive, now, start)
+                .unwrap();
+        }
+        assert!(matches!(
+            budget.try_acquire(FetchPriority::Interactive, now, start),
+            Err(AirQualityError::QuotaExhausted)
+        ));
+        for _ in 0..2 {
+            budget
+                .try_acquire(FetchPriority::Scheduled, now, start)
+                .unwrap();
+        }
+        assert!(matches!(
+            budget.try_acquire(FetchPriority::Scheduled, now, start),
+            Err(AirQualityError::QuotaExhausted)
+        ));
+
+        // A new month starts from zero.
+        let february = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+        budget
+            .try_acquire(FetchPriority::Interactive, february, start)
+            .unwrap();
+    }
+
+    fn this_month() -> (i32, u32) {
+        let now = Utc::now();
+        (now.year(), now.month())
+    }
+
+    #[tokio::test]
+    async fn monthly_count_is_saved_after_each_call() {
+        let store = Arc::new(InMemoryStore::new());
+        let budget = CallBudget::new(1000, 100).with_usage("iqair", store.clone());
+        for _ in 0..3 {
+            budget.acquire(FetchPriority::Scheduled).await.unwrap();
+        }
+
+        assert_eq!(store.quota_used("iqair", this_month()).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn monthly_count_carries_on_after_a_restart() {
+        let store = Arc::new(InMemoryStore::new());
+        store
+            .save_quota_used("iqair", this_month(), 17)
+            .await
+            .unwrap();
+        let budget = CallBudget::new(1000, 20).with_usage("iqair", store.clone());
+
+        budget.acquire(FetchPriority::Interactive).await.unwrap();
+        assert_eq!(store.quota_used("iqair", this_month()).await.unwrap(), 18);
+        // 18 of 20 is where the scheduled reserve starts.
+        assert!(matches!(
+            budget.acquire(FetchPriority::Interactive).await,
+            Err(AirQualityError::QuotaExhausted)
+        ));
+    }
+
+    #[tokio::test]
+    async fn last_month_is_not_carried_over() {
+        let store = Arc::new(InMemoryStore::new());
+        let (year, month) = this_month();
+        let last_month = if month == 1 {
+            (year - 1, 12)
+        } else {
+            (year, month - 1)
+        };
+        store
+            .save_quota_used("iqair", last_month, 20)
+            .await
+            .unwrap();
+        let budget = CallBudget::new(1000, 20).with_usage("iqair", store.clone());
+
+        budget.acquire(FetchPriority::Interactive).await.unwrap();
+        assert_eq!(store.quota_used("iqair", this_month()).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn huge_monthly_quota_does_not_overflow() {
+        let (budget, start) = budget(10, u32::MAX);
+        budget
+            .try_acquire(FetchPriority::Interactive, january(), start)
+            .unwrap();
+    }
+}
@@ -0,0 +1,506 @@
+use crate::domain::i18n::Locale;
+use crate::domain::models::{AirQualityData, AirQualityLevel, Location, Subscription};
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use crate::use_cases::alert_air_quality::AlertStateRepository;
+use crate::use_cases::chat_settings::ChatSettingsRepository;
+use crate::use_cases::check_air_quality::{ReadingRepository, READING_RETENTION_DAYS};
+use crate::use_cases::manage_subscriptions::SubscriptionRepository;
+use crate::use_cases::storage::QuotaUsageRepository;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Schema changes, applied in order. `PRAGMA user_version` records how many have run,
+/// so new migrations must only ever be appended.
+const MIGRATIONS: &[&str] = &[
+    // 1: subscriptions, readings and alert state
+    "CREATE TABLE subscriptions (
+        chat_id INTEGER NOT NULL,
+        location_key TEXT NOT NULL,
+        location TEXT NOT NULL,
+        min_level TEXT NOT NULL,
+        PRIMARY KEY (chat_id, location_key)
+    );
+    CREATE TABLE readings (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        location_key TEXT NOT NULL,
+        recorded_at INTEGER NOT NULL,
+        aqi INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX readings_by_location_time ON readings (location_key, recorded_at);
+    CREATE TABLE alert_state (
+        chat_id TEXT NOT NULL,
+        location_key TEXT NOT NULL,
+        level TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (chat_id, location_key)
+    );",
+    // 2: per-chat preferences
+    "CREATE TABLE chat_settings (
+        chat_id INTEGER PRIMARY KEY,
+        aqi_standard TEXT
+    );",
+    // 3: per-chat language
+    "ALTER TABLE chat_settings ADD COLUMN locale TEXT;",
+    // 4: per-chat message template
+    "ALTER TABLE chat_settings ADD COLUMN template TEXT;",
+    // 5: metered API usage for the current month
+    "CREATE TABLE quota_usage (
+        api TEXT PRIMARY KEY,
+        month TEXT NOT NULL,
+        used INTEGER NOT NULL
+    );",
+];
+
+/// Embedded SQLite store, so subscriptions and history survive redeploys.
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let mut conn =
+            Connection::open(path).with_context(|| format!("Failed to open database {}", path))?;
+        Self::migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn migrate(conn: &mut Connection) -> Result<()> {
+        let applied: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        for (version, sql) in (1..).zip(MIGRATIONS).skip(applied as usize) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(sql)
+                .with_context(|| format!("Migration {} failed", version))?;
+            tx.pragma_update(None, "user_version", version)?;
+            tx.commit()?;
+            info!("Applied database migration {}", version);
+        }
+
+        Ok(())
+    }
+
+    /// Runs `f` on the blocking thread pool, so SQLite I/O doesn't stall the
+    /// async workers.
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || f(&conn.lock().unwrap()))
+            .await
+            .context("Database task panicked")?
+    }
+}
+
+fn parse_level(slug: &str) -> Result<AirQualityLevel> {
+    AirQualityLevel::from_slug(slug)
+        .with_context(|| format!("Unknown level '{}' in database", slug))
+}
+
+#[async_trait]
+impl SubscriptionRepository for SqliteStore {
+    async fn save_subscription(&self, subscription: Subscription) -> Result<()> {
+        let location_key = subscription.location.key();
+        let location = serde_json::to_string(&subscription.location)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO subscriptions (chat_id, location_key, location, min_level)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (chat_id, location_key)
+                 DO UPDATE SET location = excluded.location, min_level = excluded.min_level",
+                params![
+                    subscription.chat_id,
+                    location_key,
+                    location,
+                    subscription.min_level.slug(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_subscriptions(
+        &self,
+        chat_id: i64,
+        location: Option<&Location>,
+    ) -> Result<usize> {
+        let location_key = location.map(Location::key);
+        self.with_conn(move |conn| {
+            let removed = match location_key {
+                Some(location_key) => conn.execute(
+                    "DELETE FROM subscriptions WHERE chat_id = ?1 AND location_key = ?2",
+                    params![chat_id, location_key],
+                )?,
+                None => conn.execute(
+                    "DELETE FROM subscriptions WHERE chat_id = ?1",
+                    params![chat_id],
+                )?,
+            };
+            Ok(removed)
+        })
+        .await
+    }
+
+    async fn subscriptions_for_chat(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT chat_id, location, min_level FROM subscriptions
+                 WHERE chat_id = ?1 ORDER BY rowid",
+            )?;
+            let rows = stmt.query_map(params![chat_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+            rows.map(|row| to_subscription(row?)).collect()
+        })
+        .await
+    }
+
+    async fn all_subscriptions(&self) -> Result<Vec<Subscription>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT chat_id, location, min_level FROM subscriptions ORDER BY rowid")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.map(|row| to_subscription(row?)).collect()
+        })
+        .await
+    }
+}
+
+fn to_subscription((chat_id, location, min_level): (i64, String, String)) -> Result<Subscription> {
+    Ok(Subscription {
+        chat_id,
+        location: serde_json::from_str(&location)?,
+        min_level: parse_level(&min_level)?,
+    })
+}
+
+#[async_trait]
+impl ReadingRepository for SqliteStore {
+    async fn save_reading(
+        &self,
+        location: &Location,
+        recorded_at: DateTime<Utc>,
+        data: &AirQualityData,
+    ) -> Result<()> {
+        let location_key = location.key();
+        let aqi = data.aqi;
+        let data = serde_json::to_string(data)?;
+        let cutoff = recorded_at - Duration::days(READING_RETENTION_DAYS);
+        self.with_conn(move |conn| {
+            conn.execute(
+                "DELETE FROM readings WHERE location_key = ?1 AND recorded_at < ?2",
+                params![location_key, cutoff.timestamp()],
+            )?;
+            conn.execute(
+                "INSERT INTO readings (location_key, recorded_at, aqi, data) VALUES (?1, ?2, ?3, ?4)",
+                params![location_key, recorded_at.timestamp(), aqi, data],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn readings_since(
+        &self,
+        location: &Location,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>> {
+        let location_key = location.key();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT recorded_at, data FROM readings
+                 WHERE location_key = ?1 AND recorded_at >= ?2
+                 ORDER BY recorded_at",
+            )?;
+            let rows = stmt.query_map(params![location_key, since.timestamp()], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            rows.map(|row| {
+                let (recorded_at, data) = row?;
+                let recorded_at = Utc
+                    .timestamp_opt(recorded_at, 0)
+                    .single()
+                    .with_context(|| format!("Invalid timestamp {} in database", recorded_at))?;
+                Ok((recorded_at, serde_json::from_str(&data)?))
+            })
+            .collect()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl AlertStateRepository for SqliteStore {
+    async fn last_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+    ) -> Result<Option<AirQualityLevel>> {
+        let chat_id = chat_id.to_string();
+        let location_key = location.key();
+        self.with_conn(move |conn| {
+            let level: Option<String> = conn
+                .query_row(
+                    "SELECT level FROM alert_state WHERE chat_id = ?1 AND location_key = ?2",
+                    params![chat_id, location_key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            level.as_deref().map(parse_level).transpose()
+        })
+        .await
+    }
+
+    async fn save_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+        level: AirQualityLevel,
+    ) -> Result<()> {
+        let chat_id = chat_id.to_string();
+        let location_key = location.key();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO alert_state (chat_id, location_key, level, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (chat_id, location_key)
+                 DO UPDATE SET level = excluded.level, updated_at = excluded.updated_at",
+                params![chat_id, location_key, level.slug(), Utc::now().timestamp()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// One nullable column of `chat_settings` for `chat_id`.
+fn chat_setting(conn: &Connection, column: &str, chat_id: i64) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {} FROM chat_settings WHERE chat_id = ?1", column),
+            params![chat_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+#[async_trait]
+impl ChatSettingsRepository for SqliteStore {
+    async fn aqi_standard(&self, chat_id: i64) -> Result<Option<AqiStandard>> {
+        let slug = self
+            .with_conn(move |conn| chat_setting(conn, "aqi_standard", chat_id))
+            .await?;
+        slug.as_deref()
+            .map(|slug| {
+                AqiStandard::from_slug(slug)
+                    .with_context(|| format!("Unknown AQI standard '{}' in database", slug))
+            })
+            .transpose()
+    }
+
+    async fn save_aqi_standard(&self, chat_id: i64, standard: AqiStandard) -> Result<()> {
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO chat_settings (chat_id, aqi_standard) VALUES (?1, ?2)
+                 ON CONFLICT (chat_id) DO UPDATE SET aqi_standard = excluded.aqi_standard",
+                params![chat_id, standard.slug()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn locale(&self, chat_id: i64) -> Result<Option<Locale>> {
+        let slug = self
+            .with_conn(move |conn| chat_setting(conn, "locale", chat_id))
+            .await?;
+        slug.as_deref()
+            .map(|slug| {
+                Locale::from_slug(slug)
+                    .with_context(|| format!("Unknown language '{}' in database", slug))
+            })
+            .transpose()
+    }
+
+    async fn save_locale(&self, chat_id: i64, locale: Locale) -> Result<()> {
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO chat_settings (chat_id, locale) VALUES (?1, ?2)
+                 ON CONFLICT (chat_id) DO UPDATE SET locale = excluded.locale",
+                params![chat_id, locale.slug()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn template(&self, chat_id: i64) -> Result<Option<MessageTemplate>> {
+        let source = self
+            .with_conn(move |conn| chat_setting(conn, "template", chat_id))
+            .await?;
+        source
+            .as_deref()
+            .map(|source| {
+                MessageTemplate::parse(source)
+                    .with_context(|| format!("Invalid template '{}' in database", source))
+            })
+            .transpose()
+    }
+
+    async fn save_template(&self, chat_id: i64, template: Option<&MessageTemplate>) -> Result<()> {
+        let source = template.map(|template| template.as_str().to_string());
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO chat_settings (chat_id, template) VALUES (?1, ?2)
+                 ON CONFLICT (chat_id) DO UPDATE SET template = excluded.template",
+                params![chat_id, source],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Months are stored as "2025-01".
+fn month_key((year, month): (i32, u32)) -> String {
+    format!("{:04}-{:02}", year, month)
+}
+
+#[async_trait]
+impl QuotaUsageRepository for SqliteStore {
+    async fn quota_used(&self, api: &str, month: (i32, u32)) -> Result<u32> {
+        let api = api.to_string();
+        let month = month_key(month);
+        self.with_conn(move |conn| {
+            let used: Option<u32> = conn
+                .query_row(
+                    "SELECT used FROM quota_usage WHERE api = ?1 AND month = ?2",
+                    params![api, month],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(used.unwrap_or(0))
+        })
+        .await
+    }
+
+    async fn save_quota_used(&self, api: &str, month: (i32, u32), used: u32) -> Result<()> {
+        let api = api.to_string();
+        let month = month_key(month);
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO quota_usage (api, month, used) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (api) DO UPDATE SET month = excluded.month, used = excluded.used",
+                params![api, month, used],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::storage_contract;
+
+    fn in_memory() -> SqliteStore {
+        SqliteStore::open(":memory:").unwrap()
+    }
+
+    fn user_version(store: &SqliteStore) -> i64 {
+        let conn = store.conn.lock().unwrap();
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn applies_every_migration() {
+        assert_eq!(user_version(&in_memory()), MIGRATIONS.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn upgrades_an_older_database_without_losing_data() {
+        let path =
+            std::env::temp_dir().join(format!("air-quality-migrations-{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            conn.pragma_update(None, "user_version", 1).unwrap();
+            conn.execute(
+                "INSERT INTO subscriptions (chat_id, location_key, location, min_level)
+                 VALUES (1, ?1, ?2, 'moderate')",
+                params![
+                    Location::from_city("Ban Suan", "Chon Buri", "Thailand").key(),
+                    serde_json::to_string(&Location::from_city(
+                        "Ban Suan",
+                        "Chon Buri",
+                        "Thailand"
+                    ))
+                    .unwrap(),
+                ],
+            )
+            .unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(user_version(&store), MIGRATIONS.len() as i64);
+        assert_eq!(store.all_subscriptions().await.unwrap().len(), 1);
+        store.save_locale(1, Locale::English).await.unwrap();
+        drop(store);
+
+        // Opening again runs nothing twice.
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(user_version(&store), MIGRATIONS.len() as i64);
+        assert_eq!(store.locale(1).await.unwrap(), Some(Locale::English));
+        drop(store);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn subscriptions() {
+        storage_contract::subscriptions(in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn readings() {
+        storage_contract::readings(in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn reading_retention() {
+        storage_contract::reading_retention(in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn alert_state() {
+        storage_contract::alert_state(in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn chat_settings() {
+        storage_contract::chat_settings(in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn quota_usage() {
+        storage_contract::quota_usage(in_memory()).await;
+    }
+}
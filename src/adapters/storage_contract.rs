@@ -0,0 +1,269 @@
+//! Behaviour every storage backend must share, run against each of them from
+//! their own test modules.
+
+use crate::domain::i18n::Locale;
+use crate::domain::models::{AirQualityData, AirQualityLevel, Location, Subscription};
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use crate::domain::weather::Weather;
+use crate::use_cases::check_air_quality::READING_RETENTION_DAYS;
+use crate::use_cases::storage::Storage;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn ban_suan() -> Location {
+    Location::from_city("Ban Suan", "Chon Buri", "Thailand")
+}
+
+fn si_racha() -> Location {
+    Location::from_city("Si Racha", "Chon Buri", "Thailand")
+}
+
+fn hour(offset: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap() + Duration::hours(offset)
+}
+
+fn subscription(chat_id: i64, location: Location, min_level: AirQualityLevel) -> Subscription {
+    Subscription {
+        chat_id,
+        location,
+        min_level,
+    }
+}
+
+fn summary(subscriptions: &[Subscription]) -> Vec<(i64, String, AirQualityLevel)> {
+    subscriptions
+        .iter()
+        .map(|s| (s.chat_id, s.location.name.clone(), s.min_level))
+        .collect()
+}
+
+pub async fn subscriptions<S: Storage>(store: S) {
+    use AirQualityLevel::*;
+
+    assert!(store.all_subscriptions().await.unwrap().is_empty());
+
+    store
+        .save_subscription(subscription(1, ban_suan(), Moderate))
+        .await
+        .unwrap();
+    store
+        .save_subscription(subscription(1, si_racha(), Unhealthy))
+        .await
+        .unwrap();
+    store
+        .save_subscription(subscription(2, ban_suan(), UnhealthyForSensitive))
+        .await
+        .unwrap();
+    // Subscribing again replaces the level instead of adding a second row.
+    store
+        .save_subscription(subscription(1, ban_suan(), VeryUnhealthy))
+        .await
+        .unwrap();
+
+    let chat = store.subscriptions_for_chat(1).await.unwrap();
+    let mut chat = summary(&chat);
+    chat.sort();
+    assert_eq!(
+        chat,
+        [
+            (1, "Ban Suan".to_string(), VeryUnhealthy),
+            (1, "Si Racha".to_string(), Unhealthy),
+        ]
+    );
+    assert_eq!(store.all_subscriptions().await.unwrap().len(), 3);
+
+    // Locations match by key, whatever the spelling.
+    let shouted = Location::from_city("BAN SUAN ", "chon buri", "THAILAND");
+    assert_eq!(
+        store.delete_subscriptions(1, Some(&shouted)).await.unwrap(),
+        1
+    );
+    assert_eq!(
+        store
+            .delete_subscriptions(1, Some(&ban_suan()))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(store.delete_subscriptions(1, None).await.unwrap(), 1);
+    assert_eq!(store.delete_subscriptions(1, None).await.unwrap(), 0);
+
+    let rest = store.all_subscriptions().await.unwrap();
+    assert_eq!(
+        summary(&rest),
+        [(2, "Ban Suan".to_string(), UnhealthyForSensitive)]
+    );
+}
+
+pub async fn readings<S: Storage>(store: S) {
+    let location = ban_suan();
+    let mut reading = AirQualityData::sample(location.clone(), 87, 28.5);
+    reading.weather = Weather {
+        temperature: Some(31),
+        humidity: Some(74),
+        wind_speed_kmh: Some(9.5),
+        ..Weather::default()
+    };
+
+    for (offset, aqi) in [(0, 40), (2, 60), (1, 50)] {
+        let data = AirQualityData {
+            aqi,
+            ..reading.clone()
+        };
+        store
+            .save_reading(&location, hour(offset), &data)
+            .await
+            .unwrap();
+    }
+    store
+        .save_reading(&si_racha(), hour(1), &reading)
+        .await
+        .unwrap();
+
+    let all = store.readings_since(&location, hour(0)).await.unwrap();
+    let aqis: Vec<(DateTime<Utc>, i32)> = all.iter().map(|(at, d)| (*at, d.aqi)).collect();
+    assert_eq!(aqis, [(hour(0), 40), (hour(1), 50), (hour(2), 60)]);
+
+    let (_, stored) = &all[0];
+    assert_eq!(stored.pm25, 28.5);
+    assert_eq!(stored.pollutants, reading.pollutants);
+    assert_eq!(stored.weather, reading.weather);
+    assert_eq!(stored.source, "Test");
+
+    let since = store.readings_since(&location, hour(1)).await.unwrap();
+    assert_eq!(since.len(), 2);
+    assert!(store
+        .readings_since(&location, hour(3))
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(store
+        .readings_since(
+            &Location::from_city("Phan Thong", "Chon Buri", "Thailand"),
+            hour(0)
+        )
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub async fn reading_retention<S: Storage>(store: S) {
+    let location = ban_suan();
+    let reading = AirQualityData::sample(location.clone(), 50, 12.0);
+    let later = hour(24 * READING_RETENTION_DAYS + 1);
+
+    store
+        .save_reading(&location, hour(0), &reading)
+        .await
+        .unwrap();
+    store
+        .save_reading(&location, hour(2), &reading)
+        .await
+        .unwrap();
+    store
+        .save_reading(&location, later, &reading)
+        .await
+        .unwrap();
+
+    let kept: Vec<DateTime<Utc>> = store
+        .readings_since(&location, hour(-1))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(at, _)| at)
+        .collect();
+    assert_eq!(kept, [hour(2), later]);
+}
+
+pub async fn alert_state<S: Storage>(store: S) {
+    let location = ban_suan();
+    assert_eq!(
+        store.last_alert_level("@channel", &location).await.unwrap(),
+        None
+    );
+
+    store
+        .save_alert_level("@channel", &location, AirQualityLevel::Moderate)
+        .await
+        .unwrap();
+    store
+        .save_alert_level("@channel", &location, AirQualityLevel::Unhealthy)
+        .await
+        .unwrap();
+    store
+        .save_alert_level("42", &location, AirQualityLevel::Good)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store.last_alert_level("@channel", &location).await.unwrap(),
+        Some(AirQualityLevel::Unhealthy)
+    );
+    assert_eq!(
+        store.last_alert_level("42", &location).await.unwrap(),
+        Some(AirQualityLevel::Good)
+    );
+    assert_eq!(
+        store
+            .last_alert_level("@channel", &si_racha())
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+pub async fn chat_settings<S: Storage>(store: S) {
+    assert_eq!(store.aqi_standard(7).await.unwrap(), None);
+    assert_eq!(store.locale(7).await.unwrap(), None);
+    assert!(store.template(7).await.unwrap().is_none());
+
+    store
+        .save_aqi_standard(7, AqiStandard::ThaiPcd)
+        .await
+        .unwrap();
+    store.save_locale(7, Locale::English).await.unwrap();
+    store
+        .save_aqi_standard(7, AqiStandard::IndiaNaqi)
+        .await
+        .unwrap();
+    // Each setting is saved on its own without clearing the others.
+    assert_eq!(
+        store.aqi_standard(7).await.unwrap(),
+        Some(AqiStandard::IndiaNaqi)
+    );
+    assert_eq!(store.locale(7).await.unwrap(), Some(Locale::English));
+    assert_eq!(store.aqi_standard(8).await.unwrap(), None);
+
+    let template = MessageTemplate::parse("{level_emoji} {place} AQI {aqi}").unwrap();
+    store.save_template(7, Some(&template)).await.unwrap();
+    assert_eq!(
+        store
+            .template(7)
+            .await
+            .unwrap()
+            .as_ref()
+            .map(MessageTemplate::as_str),
+        Some("{level_emoji} {place} AQI {aqi}")
+    );
+    store.save_template(7, None).await.unwrap();
+    assert!(store.template(7).await.unwrap().is_none());
+    assert_eq!(store.locale(7).await.unwrap(), Some(Locale::English));
+}
+
+pub async fn quota_usage<S: Storage>(store: S) {
+    let january = (2025, 1);
+    assert_eq!(store.quota_used("iqair", january).await.unwrap(), 0);
+
+    store.save_quota_used("iqair", january, 41).await.unwrap();
+    store.save_quota_used("iqair", january, 42).await.unwrap();
+    store.save_quota_used("other", january, 7).await.unwrap();
+    assert_eq!(store.quota_used("iqair", january).await.unwrap(), 42);
+    assert_eq!(store.quota_used("other", january).await.unwrap(), 7);
+
+    // A new month starts from zero and replaces the old one.
+    let february = (2025, 2);
+    assert_eq!(store.quota_used("iqair", february).await.unwrap(), 0);
+    store.save_quota_used("iqair", february, 1).await.unwrap();
+    assert_eq!(store.quota_used("iqair", february).await.unwrap(), 1);
+    assert_eq!(store.quota_used("iqair", january).await.unwrap(), 0);
+}
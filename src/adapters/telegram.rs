@@ -1,3 +1,5 @@
+use crate::adapters::http::HttpClient;
+use crate::presentation::MessageFormat;
 use crate::use_cases::notify_air_quality::NotificationGateway;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -6,25 +8,37 @@ use serde::Serialize;
 #[derive(Debug, Clone)]
 pub struct TelegramClient {
     token: String,
-    client: reqwest::Client,
+    http: HttpClient,
+    format: MessageFormat,
 }
 
 #[derive(Debug, Serialize)]
 struct SendMessageRequest {
     chat_id: String,
     text: String,
-    parse_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<&'static str>,
 }
 
 impl TelegramClient {
-    pub fn new(token: String) -> Self {
+    pub fn new(token: String, http: HttpClient, format: MessageFormat) -> Self {
         Self {
             token,
-            client: reqwest::Client::new(),
+            http,
+            format,
         }
     }
 }
 
+/// Bot API `parse_mode` for a format; plain text is sent without one.
+fn parse_mode(format: MessageFormat) -> Option<&'static str> {
+    match format {
+        MessageFormat::Html => Some("HTML"),
+        MessageFormat::MarkdownV2 => Some("MarkdownV2"),
+        MessageFormat::PlainText => None,
+    }
+}
+
 #[async_trait]
 impl NotificationGateway for TelegramClient {
     async fn send(&self, channel_id: &str, message: &str) -> Result<()> {
@@ -33,13 +47,11 @@ impl NotificationGateway for TelegramClient {
         let request = SendMessageRequest {
             chat_id: channel_id.to_string(),
             text: message.to_string(),
-            parse_mode: "Markdown".to_string(),
+            parse_mode: parse_mode(self.format),
         };
 
-        self.client
-            .post(&url)
-            .json(&request)
-            .send()
+        self.http
+            .send(self.http.post(&url).json(&request))
             .await
             .context("Failed to send message to Telegram")?
             .error_for_status()
@@ -47,4 +59,8 @@ impl NotificationGateway for TelegramClient {
 
         Ok(())
     }
+
+    fn format(&self) -> MessageFormat {
+        self.format
+    }
 }
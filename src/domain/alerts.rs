@@ -0,0 +1,231 @@
+use crate::domain::models::AirQualityLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelTransition {
+    Initial(AirQualityLevel),
+    Unchanged(AirQualityLevel),
+    Worsened {
+        from: AirQualityLevel,
+        to: AirQualityLevel,
+    },
+    Improved {
+        from: AirQualityLevel,
+        to: AirQualityLevel,
+    },
+}
+
+impl LevelTransition {
+    /// Level to remember after this transition.
+    pub fn level(&self) -> AirQualityLevel {
+        match *self {
+            Self::Initial(level) | Self::Unchanged(level) => level,
+            Self::Worsened { to, .. } | Self::Improved { to, .. } => to,
+        }
+    }
+
+    /// Whether someone who only cares about `min_level` and above should hear about it.
+    /// Worsening counts once the new level reaches the threshold, improving counts when
+    /// leaving a level at or above it.
+    pub fn concerns(&self, min_level: AirQualityLevel) -> bool {
+        match *self {
+            Self::Unchanged(_) => false,
+            Self::Initial(level) => level >= min_level,
+            Self::Worsened { to, .. } => to >= min_level,
+            Self::Improved { from, .. } => from >= min_level,
+        }
+    }
+}
+
+/// Decides when a reading has really left its previous level.
+///
+/// A reading only counts as a new level once it is more than `hysteresis` AQI
+/// points outside the previous level's range, so values hovering around a
+/// boundary (e.g. 100/101) don't flip the level back and forth.
+#[derive(Debug, Clone, Copy)]
+pub struct AlertPolicy {
+    hysteresis: i32,
+}
+
+impl AlertPolicy {
+    pub fn new(hysteresis: i32) -> Self {
+        Self {
+            hysteresis: hysteresis.max(0),
+        }
+    }
+
+    /// `None` when `aqi` is outside every level, i.e. negative.
+    pub fn evaluate(&self, previous: Option<AirQualityLevel>, aqi: i32) -> Option<LevelTransition> {
+        let current = AirQualityLevel::from_aqi(aqi)?;
+        let Some(previous) = previous else {
+            return Some(LevelTransition::Initial(current));
+        };
+
+        let (low, high) = previous.aqi_range();
+        let escaped = aqi > high.saturating_add(self.hysteresis) || aqi < low - self.hysteresis;
+
+        let transition = if current == previous || !escaped {
+            LevelTransition::Unchanged(previous)
+        } else if current > previous {
+            LevelTransition::Worsened {
+                from: previous,
+                to: current,
+            }
+        } else {
+            LevelTransition::Improved {
+                from: previous,
+                to: current,
+            }
+        };
+        Some(transition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use AirQualityLevel::*;
+
+    fn evaluate(previous: Option<AirQualityLevel>, aqi: i32) -> LevelTransition {
+        AlertPolicy::new(5).evaluate(previous, aqi).unwrap()
+    }
+
+    #[test]
+    fn first_reading_sets_the_level() {
+        assert_eq!(evaluate(None, 42), LevelTransition::Initial(Good));
+        assert_eq!(
+            evaluate(None, 101),
+            LevelTransition::Initial(UnhealthyForSensitive)
+        );
+        assert_eq!(AlertPolicy::new(5).evaluate(None, -1), None);
+    }
+
+    #[test]
+    fn worsening_past_the_band() {
+        assert_eq!(
+            evaluate(Some(Moderate), 106),
+            LevelTransition::Worsened {
+                from: Moderate,
+                to: UnhealthyForSensitive
+            }
+        );
+        // Jumps skip the levels in between.
+        assert_eq!(
+            evaluate(Some(Good), 180),
+            LevelTransition::Worsened {
+                from: Good,
+                to: Unhealthy
+            }
+        );
+    }
+
+    #[test]
+    fn improving_past_the_band() {
+        assert_eq!(
+            evaluate(Some(UnhealthyForSensitive), 95),
+            LevelTransition::Improved {
+                from: UnhealthyForSensitive,
+                to: Moderate
+            }
+        );
+        assert_eq!(
+            evaluate(Some(Hazardous), 20),
+            LevelTransition::Improved {
+                from: Hazardous,
+                to: Good
+            }
+        );
+    }
+
+    #[test]
+    fn staying_within_the_band_keeps_the_level() {
+        for aqi in [51, 75, 100, 101, 105, 46, 50] {
+            assert_eq!(
+                evaluate(Some(Moderate), aqi),
+                LevelTransition::Unchanged(Moderate),
+                "AQI {}",
+                aqi
+            );
+        }
+        assert_eq!(
+            evaluate(Some(Hazardous), 900),
+            LevelTransition::Unchanged(Hazardous)
+        );
+    }
+
+    #[test]
+    fn readings_flapping_at_a_boundary_alert_once_each_way() {
+        let policy = AlertPolicy::new(5);
+        let mut level = None;
+        let mut alerts = Vec::new();
+        for aqi in [99, 101, 100, 103, 98, 105, 106, 104, 100, 97, 101, 96, 95] {
+            let transition = policy.evaluate(level, aqi).unwrap();
+            if !matches!(
+                transition,
+                LevelTransition::Unchanged(_) | LevelTransition::Initial(_)
+            ) {
+                alerts.push((aqi, transition));
+            }
+            level = Some(transition.level());
+        }
+        assert_eq!(
+            alerts,
+            [
+                (
+                    106,
+                    LevelTransition::Worsened {
+                        from: Moderate,
+                        to: UnhealthyForSensitive
+                    }
+                ),
+                (
+                    95,
+                    LevelTransition::Improved {
+                        from: UnhealthyForSensitive,
+                        to: Moderate
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_hysteresis_follows_every_boundary() {
+        let policy = AlertPolicy::new(0);
+        assert_eq!(
+            policy.evaluate(Some(Moderate), 101),
+            Some(LevelTransition::Worsened {
+                from: Moderate,
+                to: UnhealthyForSensitive
+            })
+        );
+        // Negative settings count as none.
+        assert_eq!(
+            AlertPolicy::new(-10).evaluate(Some(Good), 51),
+            Some(LevelTransition::Worsened {
+                from: Good,
+                to: Moderate
+            })
+        );
+    }
+
+    #[test]
+    fn transitions_concern_chats_by_their_minimum_level() {
+        let worse = LevelTransition::Worsened {
+            from: Moderate,
+            to: UnhealthyForSensitive,
+        };
+        assert!(worse.concerns(UnhealthyForSensitive));
+        assert!(!worse.concerns(Unhealthy));
+
+        let better = LevelTransition::Improved {
+            from: UnhealthyForSensitive,
+            to: Moderate,
+        };
+        assert!(better.concerns(UnhealthyForSensitive));
+        assert!(!better.concerns(Unhealthy));
+
+        assert!(LevelTransition::Initial(Unhealthy).concerns(UnhealthyForSensitive));
+        assert!(!LevelTransition::Initial(Good).concerns(Moderate));
+        assert!(!LevelTransition::Unchanged(Hazardous).concerns(Good));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::domain::models::Pollutant;
+
+/// Revision of the US EPA AQI breakpoint tables.
+///
+/// The 2024 revision lowered the PM2.5 "Good" bound from 12.0 to 9.0 µg/m³,
+/// tightened the upper PM2.5 breakpoints and merged 301–500 into a single
+/// Hazardous band for every pollutant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpaRevision {
+    Epa2012,
+    #[default]
+    Epa2024,
+}
+
+/// One row of a breakpoint table: concentrations `c_low..=c_high` map linearly
+/// onto index values `i_low..=i_high`.
+#[derive(Debug, Clone, Copy)]
+pub struct Breakpoint {
+    c_low: f64,
+    c_high: f64,
+    i_low: i32,
+    i_high: i32,
+}
+
+pub const fn bp(c_low: f64, c_high: f64, i_low: i32, i_high: i32) -> Breakpoint {
+    Breakpoint {
+        c_low,
+        c_high,
+        i_low,
+        i_high,
+    }
+}
+
+// Units follow the EPA tables: µg/m³ for particulates, ppb for O₃ (8-hour up to
+// 300, 1-hour above), NO₂ and SO₂, ppm for CO.
+const PM25_2012: &[Breakpoint] = &[
+    bp(0.0, 12.0, 0, 50),
+    bp(12.1, 35.4, 51, 100),
+    bp(35.5, 55.4, 101, 150),
+    bp(55.5, 150.4, 151, 200),
+    bp(150.5, 250.4, 201, 300),
+    bp(250.5, 350.4, 301, 400),
+    bp(350.5, 500.4, 401, 500),
+];
+const PM25_2024: &[Breakpoint] = &[
+    bp(0.0, 9.0, 0, 50),
+    bp(9.1, 35.4, 51, 100),
+    bp(35.5, 55.4, 101, 150),
+    bp(55.5, 125.4, 151, 200),
+    bp(125.5, 225.4, 201, 300),
+    bp(225.5, 325.4, 301, 500),
+];
+const PM10_2012: &[Breakpoint] = &[
+    bp(0.0, 54.0, 0, 50),
+    bp(55.0, 154.0, 51, 100),
+    bp(155.0, 254.0, 101, 150),
+    bp(255.0, 354.0, 151, 200),
+    bp(355.0, 424.0, 201, 300),
+    bp(425.0, 504.0, 301, 400),
+    bp(505.0, 604.0, 401, 500),
+];
+const PM10_2024: &[Breakpoint] = &[
+    bp(0.0, 54.0, 0, 50),
+    bp(55.0, 154.0, 51, 100),
+    bp(155.0, 254.0, 101, 150),
+    bp(255.0, 354.0, 151, 200),
+    bp(355.0, 424.0, 201, 300),
+    bp(425.0, 604.0, 301, 500),
+];
+const O3_2012: &[Breakpoint] = &[
+    bp(0.0, 54.0, 0, 50),
+    bp(55.0, 70.0, 51, 100),
+    bp(71.0, 85.0, 101, 150),
+    bp(86.0, 105.0, 151, 200),
+    bp(106.0, 200.0, 201, 300),
+    bp(405.0, 504.0, 301, 400),
+    bp(505.0, 604.0, 401, 500),
+];
+const O3_2024: &[Breakpoint] = &[
+    bp(0.0, 54.0, 0, 50),
+    bp(55.0, 70.0, 51, 100),
+    bp(71.0, 85.0, 101, 150),
+    bp(86.0, 105.0, 151, 200),
+    bp(106.0, 200.0, 201, 300),
+    bp(405.0, 604.0, 301, 500),
+];
+const NO2_2012: &[Breakpoint] = &[
+    bp(0.0, 53.0, 0, 50),
+    bp(54.0, 100.0, 51, 100),
+    bp(101.0, 360.0, 101, 150),
+    bp(361.0, 649.0, 151, 200),
+    bp(650.0, 1249.0, 201, 300),
+    bp(1250.0, 1649.0, 301, 400),
+    bp(1650.0, 2049.0, 401, 500),
+];
+const NO2_2024: &[Breakpoint] = &[
+    bp(0.0, 53.0, 0, 50),
+    bp(54.0, 100.0, 51, 100),
+    bp(101.0, 360.0, 101, 150),
+    bp(361.0, 649.0, 151, 200),
+    bp(650.0, 1249.0, 201, 300),
+    bp(1250.0, 2049.0, 301, 500),
+];
+const SO2_2012: &[Breakpoint] = &[
+    bp(0.0, 35.0, 0, 50),
+    bp(36.0, 75.0, 51, 100),
+    bp(76.0, 185.0, 101, 150),
+    bp(186.0, 304.0, 151, 200),
+    bp(305.0, 604.0, 201, 300),
+    bp(605.0, 804.0, 301, 400),
+    bp(805.0, 1004.0, 401, 500),
+];
+const SO2_2024: &[Breakpoint] = &[
+    bp(0.0, 35.0, 0, 50),
+    bp(36.0, 75.0, 51, 100),
+    bp(76.0, 185.0, 101, 150),
+    bp(186.0, 304.0, 151, 200),
+    bp(305.0, 604.0, 201, 300),
+    bp(605.0, 1004.0, 301, 500),
+];
+const CO_2012: &[Breakpoint] = &[
+    bp(0.0, 4.4, 0, 50),
+    bp(4.5, 9.4, 51, 100),
+    bp(9.5, 12.4, 101, 150),
+    bp(12.5, 15.4, 151, 200),
+    bp(15.5, 30.4, 201, 300),
+    bp(30.5, 40.4, 301, 400),
+    bp(40.5, 50.4, 401, 500),
+];
+const CO_2024: &[Breakpoint] = &[
+    bp(0.0, 4.4, 0, 50),
+    bp(4.5, 9.4, 51, 100),
+    bp(9.5, 12.4, 101, 150),
+    bp(12.5, 15.4, 151, 200),
+    bp(15.5, 30.4, 201, 300),
+    bp(30.5, 50.4, 301, 500),
+];
+
+impl EpaRevision {
+    fn breakpoints(&self, pollutant: Pollutant) -> &'static [Breakpoint] {
+        match (self, pollutant) {
+            (Self::Epa2012, Pollutant::Pm25) => PM25_2012,
+            (Self::Epa2024, Pollutant::Pm25) => PM25_2024,
+            (Self::Epa2012, Pollutant::Pm10) => PM10_2012,
+            (Self::Epa2024, Pollutant::Pm10) => PM10_2024,
+            (Self::Epa2012, Pollutant::O3) => O3_2012,
+            (Self::Epa2024, Pollutant::O3) => O3_2024,
+            (Self::Epa2012, Pollutant::No2) => NO2_2012,
+            (Self::Epa2024, Pollutant::No2) => NO2_2024,
+            (Self::Epa2012, Pollutant::So2) => SO2_2012,
+            (Self::Epa2024, Pollutant::So2) => SO2_2024,
+            (Self::Epa2012, Pollutant::Co) => CO_2012,
+            (Self::Epa2024, Pollutant::Co) => CO_2024,
+        }
+    }
+
+    /// US AQI for a concentration in µg/m³ (the unit `Pollutants` uses).
+    ///
+    /// The concentration is truncated to the table's precision first, as the
+    /// EPA specifies; anything past the top of the table is 500.
+    pub fn aqi_from_concentration(&self, pollutant: Pollutant, ugm3: f64) -> i32 {
+        interpolate(self.breakpoints(pollutant), reported_value(pollutant, ugm3))
+    }
+
+    /// Concentration in µg/m³ at the given AQI, the inverse of
+    /// `aqi_from_concentration`.
+    ///
+    /// The result is rounded to the table's precision, so converting it back
+    /// gives the same AQI wherever the table is fine enough to tell them apart.
+    pub fn concentration_from_aqi(&self, pollutant: Pollutant, aqi: i32) -> f64 {
+        let table = self.breakpoints(pollutant);
+        let aqi = aqi.clamp(0, 500);
+        let row = table
+            .iter()
+            .find(|row| aqi <= row.i_high)
+            .unwrap_or(&table[table.len() - 1]);
+
+        let c = (aqi - row.i_low) as f64 * (row.c_high - row.c_low)
+            / (row.i_high - row.i_low) as f64
+            + row.c_low;
+        let scale = precision(pollutant);
+        from_table_units(pollutant, (c * scale).round() / scale)
+    }
+}
+
+/// Index for a concentration in the table's own units.
+///
+/// A value between two rows (O₃ has none between 200 and 405 ppb) counts as the
+/// start of the next row. Past the last row its slope continues, up to 500.
+#[cfg(test)]
+impl Breakpoint {
+    /// Both ends of the row as `(concentration, index)`.
+    pub fn edges(&self) -> [(f64, i32); 2] {
+        [(self.c_low, self.i_low), (self.c_high, self.i_high)]
+    }
+}
+
+pub fn interpolate(table: &[Breakpoint], c: f64) -> i32 {
+    let row = table
+        .iter()
+        .find(|row| c <= row.c_high)
+        .unwrap_or(&table[table.len() - 1]);
+    let c = c.max(row.c_low);
+    let index = (row.i_high - row.i_low) as f64 / (row.c_high - row.c_low) * (c - row.c_low)
+        + row.i_low as f64;
+    (index.round() as i32).min(500)
+}
+
+/// A concentration in µg/m³ as the EPA tables state it: ppb for O₃, NO₂ and SO₂,
+/// ppm for CO, truncated to the table's precision. Thailand's PCD tables use the
+/// same units.
+pub fn reported_value(pollutant: Pollutant, ugm3: f64) -> f64 {
+    truncate(pollutant, to_table_units(pollutant, ugm3.max(0.0)))
+}
+
+fn to_table_units(pollutant: Pollutant, ugm3: f64) -> f64 {
+    match pollutant {
+        Pollutant::Pm25 | Pollutant::Pm10 => ugm3,
+        Pollutant::O3 | Pollutant::No2 | Pollutant::So2 => pollutant.ugm3_to_ppb(ugm3),
+        Pollutant::Co => pollutant.ugm3_to_ppb(ugm3) / 1000.0,
+    }
+}
+
+fn from_table_units(pollutant: Pollutant, value: f64) -> f64 {
+    match pollutant {
+        Pollutant::Pm25 | Pollutant::Pm10 => value,
+        Pollutant::O3 | Pollutant::No2 | Pollutant::So2 => pollutant.ppb_to_ugm3(value),
+        Pollutant::Co => pollutant.ppb_to_ugm3(value * 1000.0),
+    }
+}
+
+/// Steps per table unit: concentrations are written to 0.1 for PM2.5 and CO
+/// and to whole numbers for the rest.
+fn precision(pollutant: Pollutant) -> f64 {
+    match pollutant {
+        Pollutant::Pm25 | Pollutant::Co => 10.0,
+        Pollutant::Pm10 | Pollutant::O3 | Pollutant::No2 | Pollutant::So2 => 1.0,
+    }
+}
+
+/// Truncates to the table's precision, as the EPA specifies for reported values.
+fn truncate(pollutant: Pollutant, value: f64) -> f64 {
+    let scale = precision(pollutant);
+    // The small epsilon keeps values like 12.1 from becoming 12.0 after unit
+    // conversion rounding.
+    ((value * scale) + 1e-9).floor() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVISIONS: [EpaRevision; 2] = [EpaRevision::Epa2012, EpaRevision::Epa2024];
+
+    /// Every concentration the table can tell apart, in µg/m³, from zero to a
+    /// little past its last row.
+    fn reported_steps(revision: EpaRevision, pollutant: Pollutant) -> impl Iterator<Item = f64> {
+        let table = revision.breakpoints(pollutant);
+        let scale = precision(pollutant);
+        let last = ((table[table.len() - 1].c_high * 1.1) * scale) as i64;
+        (0..=last).map(move |step| from_table_units(pollutant, step as f64 / scale))
+    }
+
+    #[test]
+    fn concentration_round_trips_through_aqi() {
+        for revision in REVISIONS {
+            for pollutant in Pollutant::ALL {
+                let mut rng = fastrand::Rng::with_seed(0xA91);
+                let steps = reported_steps(revision, pollutant);
+                // Reported values and random ones in between them.
+                let samples = steps.flat_map(|c| [c, c + rng.f64() * c.max(1.0) * 0.01]);
+                for ugm3 in samples {
+                    let aqi = revision.aqi_from_concentration(pollutant, ugm3);
+                    let back = revision.concentration_from_aqi(pollutant, aqi);
+                    let again = revision.aqi_from_concentration(pollutant, back);
+                    assert!(
+                        (aqi - again).abs() <= 1,
+                        "{:?} {:?}: {} µg/m³ is AQI {}, back to {} µg/m³ is AQI {}",
+                        revision,
+                        pollutant,
+                        ugm3,
+                        aqi,
+                        back,
+                        again
+                    );
+                }
+            }
+        }
+    }
+
+    /// Where one table step is worth more than one AQI point (O₃ from 55 to
+    /// 70 ppb covers 50 points in 16 steps) not every AQI has a concentration,
+    /// so the inverse only has to land on the closest one the table can give.
+    #[test]
+    fn aqi_round_trips_to_the_closest_reported_concentration() {
+        for revision in REVISIONS {
+            for pollutant in Pollutant::ALL {
+                let step = 1.0 / precision(pollutant);
+                for aqi in 0..=500 {
+                    let ugm3 = revision.concentration_from_aqi(pollutant, aqi);
+                    let miss = (revision.aqi_from_concentration(pollutant, ugm3) - aqi).abs();
+                    let c = reported_value(pollutant, ugm3);
+                    for neighbour in [c - step, c + step] {
+                        let neighbour = from_table_units(pollutant, neighbour.max(0.0));
+                        let other = revision.aqi_from_concentration(pollutant, neighbour);
+                        assert!(
+                            miss <= 1 || miss <= (other - aqi).abs(),
+                            "{:?} {:?}: AQI {} gave {} µg/m³, {} µg/m³ is closer",
+                            revision,
+                            pollutant,
+                            aqi,
+                            ugm3,
+                            neighbour
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn aqi_never_falls_as_concentration_rises() {
+        for revision in REVISIONS {
+            for pollutant in Pollutant::ALL {
+                let mut previous = 0;
+                for ugm3 in reported_steps(revision, pollutant) {
+                    let aqi = revision.aqi_from_concentration(pollutant, ugm3);
+                    assert!(
+                        aqi >= previous,
+                        "{:?} {:?} at {} µg/m³",
+                        revision,
+                        pollutant,
+                        ugm3
+                    );
+                    previous = aqi;
+                }
+                assert_eq!(previous, 500);
+            }
+        }
+    }
+
+    #[test]
+    fn every_breakpoint_edge_lands_on_its_row() {
+        for revision in REVISIONS {
+            for pollutant in Pollutant::ALL {
+                for row in revision.breakpoints(pollutant) {
+                    for (c, index) in [(row.c_low, row.i_low), (row.c_high, row.i_high)] {
+                        let ugm3 = from_table_units(pollutant, c);
+                        assert_eq!(
+                            revision.aqi_from_concentration(pollutant, ugm3),
+                            index,
+                            "{:?} {:?} at {}",
+                            revision,
+                            pollutant,
+                            c
+                        );
+                        assert_eq!(
+                            reported_value(pollutant, ugm3),
+                            c,
+                            "{:?} {:?}",
+                            revision,
+                            pollutant
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pm25_boundaries_follow_each_revision() {
+        use EpaRevision::*;
+        let cases = [
+            (Epa2024, 9.0, 50),
+            (Epa2024, 9.1, 51),
+            (Epa2024, 9.09, 50),
+            (Epa2012, 9.1, 38),
+            (Epa2012, 12.0, 50),
+            (Epa2012, 12.1, 51),
+            (Epa2024, 35.4, 100),
+            (Epa2024, 35.5, 101),
+            (Epa2012, 35.4, 100),
+            (Epa2012, 35.5, 101),
+            (Epa2024, 125.4, 200),
+            (Epa2024, 125.5, 201),
+            (Epa2012, 125.5, 187),
+            (Epa2024, 325.4, 500),
+            (Epa2024, 1000.0, 500),
+        ];
+        for (revision, ugm3, aqi) in cases {
+            assert_eq!(
+                revision.aqi_from_concentration(Pollutant::Pm25, ugm3),
+                aqi,
+                "{:?} at {}",
+                revision,
+                ugm3
+            );
+        }
+        assert_eq!(Epa2024.concentration_from_aqi(Pollutant::Pm25, 50), 9.0);
+        assert_eq!(Epa2024.concentration_from_aqi(Pollutant::Pm25, 51), 9.1);
+        assert_eq!(Epa2012.concentration_from_aqi(Pollutant::Pm25, 51), 12.1);
+        assert_eq!(Epa2024.concentration_from_aqi(Pollutant::Pm25, 101), 35.5);
+    }
+
+    #[test]
+    fn ozone_between_its_tables_starts_the_next_row() {
+        for revision in REVISIONS {
+            let ugm3 = from_table_units(Pollutant::O3, 300.0);
+            assert_eq!(revision.aqi_from_concentration(Pollutant::O3, ugm3), 301);
+        }
+    }
+}
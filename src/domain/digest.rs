@@ -0,0 +1,127 @@
+use crate::domain::forecast::OutdoorWindow;
+use crate::domain::models::{AirQualityData, Location};
+use crate::domain::standards::AqiStandard;
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Utc};
+use std::cmp::Reverse;
+
+/// One location in the morning digest.
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    /// The location as configured; providers may name it differently.
+    pub location: Location,
+    pub data: AirQualityData,
+    /// Highest AQI recorded during the previous local day, if any was stored.
+    pub yesterday_peak: Option<i32>,
+    /// Best time to go out for the rest of the day, when the digest plans it.
+    pub best_window: Option<OutdoorWindow>,
+}
+
+/// Every configured location in one message, worst first.
+#[derive(Debug, Clone)]
+pub struct Digest {
+    /// Local date the digest is for.
+    pub date: NaiveDate,
+    /// Standard the entries are ranked and shown in.
+    pub standard: AqiStandard,
+    pub entries: Vec<DigestEntry>,
+    /// Locations without a current reading.
+    pub unavailable: Vec<Location>,
+}
+
+impl Digest {
+    /// Ranks `entries` from the worst category under `standard` to the best,
+    /// then by index. Entries the standard can't assess go last.
+    pub fn new(
+        date: NaiveDate,
+        standard: AqiStandard,
+        mut entries: Vec<DigestEntry>,
+        unavailable: Vec<Location>,
+    ) -> Self {
+        entries.sort_by_key(|entry| {
+            Reverse(
+                standard
+                    .assess(&entry.data)
+                    .map(|assessment| (assessment.category.rank, assessment.index)),
+            )
+        });
+        Self {
+            date,
+            standard,
+            entries,
+            unavailable,
+        }
+    }
+
+    pub fn worst(&self) -> Option<&DigestEntry> {
+        self.entries.first()
+    }
+
+    /// Start and end of the local day before `now`'s, for yesterday's peak.
+    pub fn yesterday(now: DateTime<Utc>, timezone: FixedOffset) -> (DateTime<Utc>, DateTime<Utc>) {
+        let today = now.with_timezone(&timezone).date_naive();
+        let midnight = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|time| time.and_local_timezone(timezone).single())
+                .map_or(now, |time| time.with_timezone(&Utc))
+        };
+        let yesterday = today.checked_sub_days(Days::new(1)).unwrap_or(today);
+        (midnight(yesterday), midnight(today))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, aqi: i32, pm25: f64) -> DigestEntry {
+        let location = Location::from_city(name, "Chon Buri", "Thailand");
+        DigestEntry {
+            data: AirQualityData::sample(location.clone(), aqi, pm25),
+            location,
+            yesterday_peak: None,
+            best_window: None,
+        }
+    }
+
+    fn ranking(standard: AqiStandard, entries: Vec<DigestEntry>) -> Vec<String> {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+        Digest::new(date, standard, entries, Vec::new())
+            .entries
+            .into_iter()
+            .map(|entry| entry.location.name)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_by_the_standard_shown() {
+        // A high US AQI from ozone, against a PM2.5 level only Thailand's
+        // standard counts as worse.
+        let entries = || {
+            vec![
+                entry("Ban Suan", 150, 10.0),
+                entry("Si Racha", 60, 80.0),
+                entry("Phan Thong", 40, 5.0),
+            ]
+        };
+        assert_eq!(
+            ranking(AqiStandard::UsEpa, entries()),
+            ["Ban Suan", "Si Racha", "Phan Thong"]
+        );
+        assert_eq!(
+            ranking(AqiStandard::ThaiPcd, entries()),
+            ["Si Racha", "Ban Suan", "Phan Thong"]
+        );
+    }
+
+    #[test]
+    fn unassessed_entries_go_last() {
+        let mut missing = entry("Ban Suan", 150, 10.0);
+        missing.data.pollutants = Default::default();
+        missing.data.pm25 = f64::NAN;
+        let entries = vec![missing, entry("Si Racha", 20, 4.0)];
+        assert_eq!(
+            ranking(AqiStandard::ThaiPcd, entries),
+            ["Si Racha", "Ban Suan"]
+        );
+    }
+}
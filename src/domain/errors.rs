@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// Why an air quality reading could not be fetched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AirQualityError {
+    /// The provider doesn't know the place.
+    CityNotFound(String),
+    /// Too many calls; `retry_after_secs` when the wait is known.
+    RateLimited {
+        retry_after_secs: Option<u64>,
+    },
+    /// The call budget for the month is spent (or reserved for scheduled checks).
+    QuotaExhausted,
+    UpstreamUnavailable(String),
+    InvalidApiKey,
+    /// The provider answered with something we couldn't read.
+    Parse(String),
+    Timeout,
+}
+
+impl AirQualityError {
+    /// Combines the failures of several providers asked for the same place.
+    ///
+    /// The place only counts as unknown when every provider said so; any other
+    /// mix means the data is unavailable right now.
+    pub fn from_all_failed(errors: Vec<(String, AirQualityError)>) -> Self {
+        if let Some((_, AirQualityError::CityNotFound(city))) = errors.first() {
+            if errors
+                .iter()
+                .all(|(_, e)| matches!(e, AirQualityError::CityNotFound(_)))
+            {
+                return AirQualityError::CityNotFound(city.clone());
+            }
+        }
+
+        let summary = errors
+            .iter()
+            .map(|(provider, e)| format!("{}: {}", provider, e))
+            .collect::<Vec<_>>()
+            .join("; ");
+        AirQualityError::UpstreamUnavailable(format!("all providers failed ({})", summary))
+    }
+}
+
+impl fmt::Display for AirQualityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CityNotFound(city) => write!(f, "city '{}' not found", city),
+            Self::RateLimited {
+                retry_after_secs: Some(secs),
+            } => write!(f, "rate limited, retry in {}s", secs),
+            Self::RateLimited {
+                retry_after_secs: None,
+            } => write!(f, "rate limited"),
+            Self::QuotaExhausted => write!(f, "monthly call quota used up"),
+            Self::UpstreamUnavailable(reason) => write!(f, "upstream unavailable: {}", reason),
+            Self::InvalidApiKey => write!(f, "API key rejected"),
+            Self::Parse(reason) => write!(f, "unreadable response: {}", reason),
+            Self::Timeout => write!(f, "request timed out"),
+        }
+    }
+}
+
+impl std::error::Error for AirQualityError {}
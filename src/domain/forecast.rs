@@ -0,0 +1,53 @@
+use crate::domain::models::{AirQualityData, Location};
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+use std::ops::Range;
+
+/// Hourly predicted readings for one location, oldest first.
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub location: Location,
+    pub hours: Vec<(DateTime<Utc>, AirQualityData)>,
+}
+
+/// Consecutive forecast hours that are best for being outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutdoorWindow {
+    pub start: DateTime<Utc>,
+    /// End of the last hour in the window.
+    pub end: DateTime<Utc>,
+    pub average_aqi: i32,
+}
+
+impl Forecast {
+    /// Furthest ahead a forecast reaches.
+    pub const MAX_HOURS: u32 = 72;
+    /// Hours a window spans.
+    pub const WINDOW_HOURS: usize = 2;
+    /// Local hours people are likely to go out in; nobody needs to know that
+    /// 3 AM is the cleanest hour.
+    pub const DAYTIME: Range<u32> = 6..20;
+
+    /// The daytime window with the lowest average US AQI, the earliest on a
+    /// tie. `None` when the forecast has no run of daytime hours long enough.
+    pub fn best_window(&self, timezone: FixedOffset) -> Option<OutdoorWindow> {
+        self.hours
+            .windows(Self::WINDOW_HOURS)
+            .filter(|window| {
+                window
+                    .iter()
+                    .all(|(at, _)| Self::DAYTIME.contains(&at.with_timezone(&timezone).hour()))
+                    && window
+                        .windows(2)
+                        .all(|pair| pair[1].0 - pair[0].0 == Duration::hours(1))
+            })
+            .map(|window| {
+                let total: i32 = window.iter().map(|(_, data)| data.aqi).sum();
+                OutdoorWindow {
+                    start: window[0].0,
+                    end: window[window.len() - 1].0 + Duration::hours(1),
+                    average_aqi: total / window.len() as i32,
+                }
+            })
+            .min_by_key(|window| (window.average_aqi, window.start))
+    }
+}
@@ -0,0 +1,264 @@
+use crate::domain::models::AirQualityData;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryPeriod {
+    Day,
+    Week,
+}
+
+impl HistoryPeriod {
+    /// Accepts "24h"/"1d" and "7d"/"1w"; anything else is `None`.
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "24h" | "1d" | "day" => Some(Self::Day),
+            "7d" | "1w" | "week" => Some(Self::Week),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Day => Duration::hours(24),
+            Self::Week => Duration::days(7),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub average: f64,
+}
+
+impl Stats {
+    fn from_values(values: impl Iterator<Item = f64> + Clone) -> Self {
+        let count = values.clone().count().max(1) as f64;
+        Self {
+            min: values.clone().reduce(f64::min).unwrap_or_default(),
+            max: values.clone().reduce(f64::max).unwrap_or_default(),
+            average: values.sum::<f64>() / count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySummary {
+    pub readings: usize,
+    pub aqi: Stats,
+    pub pm25: Stats,
+    /// Start of the hour with the highest average AQI, and that average.
+    pub worst_hour: (DateTime<Utc>, i32),
+    pub trend: Trend,
+}
+
+impl HistorySummary {
+    /// Average AQI must move by at least this much between the first and second
+    /// half of the period to count as a trend.
+    const TREND_THRESHOLD: f64 = 5.0;
+
+    /// Summarizes readings sorted oldest first; `None` when there are none.
+    pub fn from_readings(readings: &[(DateTime<Utc>, AirQualityData)]) -> Option<Self> {
+        if readings.is_empty() {
+            return None;
+        }
+
+        let aqi = Stats::from_values(readings.iter().map(|(_, d)| f64::from(d.aqi)));
+        let pm25 = Stats::from_values(readings.iter().map(|(_, d)| d.pm25));
+
+        Some(Self {
+            readings: readings.len(),
+            aqi,
+            pm25,
+            worst_hour: Self::worst_hour(readings),
+            trend: Self::trend(readings),
+        })
+    }
+
+    fn worst_hour(readings: &[(DateTime<Utc>, AirQualityData)]) -> (DateTime<Utc>, i32) {
+        let mut hours: Vec<(DateTime<Utc>, i32, i32)> = Vec::new();
+        for (at, data) in readings {
+            let hour = at.duration_trunc(Duration::hours(1)).unwrap_or(*at);
+            match hours.iter_mut().find(|(h, _, _)| *h == hour) {
+                Some((_, sum, count)) => {
+                    *sum += data.aqi;
+                    *count += 1;
+                }
+                None => hours.push((hour, data.aqi, 1)),
+            }
+        }
+
+        hours
+            .into_iter()
+            .map(|(hour, sum, count)| (hour, sum / count))
+            .max_by_key(|&(hour, average)| (average, hour))
+            .unwrap_or((readings[0].0, readings[0].1.aqi))
+    }
+
+    fn trend(readings: &[(DateTime<Utc>, AirQualityData)]) -> Trend {
+        if readings.len() < 2 {
+            return Trend::Steady;
+        }
+
+        let (older, newer) = readings.split_at(readings.len() / 2);
+        let average = |half: &[(DateTime<Utc>, AirQualityData)]| {
+            Stats::from_values(half.iter().map(|(_, d)| f64::from(d.aqi))).average
+        };
+        let change = average(newer) - average(older);
+
+        if change >= Self::TREND_THRESHOLD {
+            Trend::Rising
+        } else if change <= -Self::TREND_THRESHOLD {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Location;
+    use chrono::TimeZone;
+
+    /// Readings at the given minutes past 08:00, as `(minute, aqi, pm25)`.
+    fn readings(points: &[(i64, i32, f64)]) -> Vec<(DateTime<Utc>, AirQualityData)> {
+        let start = Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap();
+        points
+            .iter()
+            .map(|&(minute, aqi, pm25)| {
+                let location = Location::from_city("Ban Suan", "Chon Buri", "Thailand");
+                (
+                    start + Duration::minutes(minute),
+                    AirQualityData::sample(location, aqi, pm25),
+                )
+            })
+            .collect()
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, hour, 0, 0).unwrap()
+    }
+
+    fn summary(points: &[(i64, i32, f64)]) -> HistorySummary {
+        HistorySummary::from_readings(&readings(points)).unwrap()
+    }
+
+    #[test]
+    fn parses_periods() {
+        assert_eq!(HistoryPeriod::parse(" 24H "), Some(HistoryPeriod::Day));
+        assert_eq!(HistoryPeriod::parse("1w"), Some(HistoryPeriod::Week));
+        assert_eq!(HistoryPeriod::parse("30d"), None);
+    }
+
+    #[test]
+    fn no_readings_have_no_summary() {
+        assert!(HistorySummary::from_readings(&[]).is_none());
+    }
+
+    #[test]
+    fn a_single_reading() {
+        let summary = summary(&[(15, 80, 25.0)]);
+
+        assert_eq!(summary.readings, 1);
+        let stats = |value| Stats {
+            min: value,
+            max: value,
+            average: value,
+        };
+        assert_eq!(summary.aqi, stats(80.0));
+        assert_eq!(summary.pm25, stats(25.0));
+        assert_eq!(summary.worst_hour, (at(8), 80));
+        assert_eq!(summary.trend, Trend::Steady);
+    }
+
+    #[test]
+    fn min_max_and_average() {
+        let summary = summary(&[(0, 40, 10.0), (60, 100, 35.0), (120, 70, 21.5)]);
+
+        assert_eq!(
+            summary.aqi,
+            Stats {
+                min: 40.0,
+                max: 100.0,
+                average: 70.0,
+            }
+        );
+        assert_eq!(
+            summary.pm25,
+            Stats {
+                min: 10.0,
+                max: 35.0,
+                average: 22.166666666666668,
+            }
+        );
+    }
+
+    #[test]
+    fn worst_hour_averages_readings_within_each_hour() {
+        // 08:00 peaks at 150 but averages 90; 09:00 averages 100.
+        let summary = summary(&[
+            (0, 30, 8.0),
+            (30, 150, 55.0),
+            (60, 100, 35.0),
+            (90, 100, 35.0),
+        ]);
+
+        assert_eq!(summary.worst_hour, (at(9), 100));
+    }
+
+    #[test]
+    fn worst_hour_tie_goes_to_the_latest() {
+        let summary = summary(&[(0, 90, 30.0), (60, 60, 18.0), (120, 90, 30.0)]);
+
+        assert_eq!(summary.worst_hour, (at(10), 90));
+    }
+
+    #[test]
+    fn trends() {
+        let rising = summary(&[
+            (0, 50, 12.0),
+            (60, 52, 12.0),
+            (120, 60, 16.0),
+            (180, 70, 21.0),
+        ]);
+        assert_eq!(rising.trend, Trend::Rising);
+
+        let falling = summary(&[(0, 120, 43.0), (60, 110, 39.0), (120, 80, 26.0)]);
+        assert_eq!(falling.trend, Trend::Falling);
+
+        // Halves within the threshold of each other, including noisy ones.
+        let flat = summary(&[
+            (0, 60, 16.0),
+            (60, 70, 21.0),
+            (120, 64, 18.0),
+            (180, 62, 17.0),
+        ]);
+        assert_eq!(flat.trend, Trend::Steady);
+    }
+
+    #[test]
+    fn trend_threshold_is_inclusive() {
+        assert_eq!(
+            summary(&[(0, 50, 12.0), (60, 55, 14.0)]).trend,
+            Trend::Rising
+        );
+        assert_eq!(
+            summary(&[(0, 50, 12.0), (60, 54, 13.0)]).trend,
+            Trend::Steady
+        );
+        assert_eq!(
+            summary(&[(0, 55, 14.0), (60, 50, 12.0)]).trend,
+            Trend::Falling
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use super::{Catalogue, CategoryText};
+
+pub static CATALOGUE: Catalogue = Catalogue {
+    language_name: "English",
+    help: "Available commands:\n\n\
+        /help — show all commands\n\
+        /pm25 — air quality for every configured area\n\
+        /check — air quality for a place, e.g. /check Ban Suan\n\
+        /subscribe — get alerts when the air gets bad, e.g. /subscribe Si Racha unhealthy\n\
+        /unsubscribe — stop alerts, e.g. /unsubscribe Si Racha (none given = all)\n\
+        /subscriptions — list this chat's alerts\n\
+        /history — past statistics, e.g. /history Ban Suan 7d (24h or 7d)\n\
+        /chart — past AQI chart, e.g. /chart Ban Suan 7d (24h or 7d)\n\
+        /standard — choose the air quality index, e.g. /standard th (us, th, eu, in)\n\
+        /lang — choose the language, e.g. /lang th (th, en)\n\
+        /template — write your own message layout, e.g. /template {level_emoji} {place} AQI {aqi}\n\
+        /forecast — the next hours and the best time to go out, e.g. /forecast Ban Suan 48h (24h, 48h or 72h)\n\
+        /bestime — the best times today for an activity, e.g. /bestime Ban Suan cycling (running, playground, cycling)",
+
+    levels: [
+        CategoryText {
+            name: "Good",
+            advice: "Enjoy being outside 👍",
+        },
+        CategoryText {
+            name: "Moderate",
+            advice: "Fine to go out, but sensitive people should take care",
+        },
+        CategoryText {
+            name: "Unhealthy for sensitive groups",
+            advice: "Children, older adults and people with health conditions should stay inside",
+        },
+        CategoryText {
+            name: "Unhealthy",
+            advice: "Harmful! Wear an N95 if you have to go out",
+        },
+        CategoryText {
+            name: "Very unhealthy",
+            advice: "Very harmful! Stay inside, close the windows and run an air purifier",
+        },
+        CategoryText {
+            name: "Hazardous",
+            advice: "Emergency! Everyone should stay inside; if you must go out, wear an N95 and come back quickly",
+        },
+    ],
+    thai_pcd_categories: [
+        CategoryText {
+            name: "Very good",
+            advice: "Ideal for outdoor activities and travel",
+        },
+        CategoryText {
+            name: "Good",
+            advice: "Outdoor activities and travel as usual",
+        },
+        CategoryText {
+            name: "Moderate",
+            advice: "Outdoor activities as usual; people who need to look after their health should spend less time outside if they cough, have trouble breathing or eye irritation",
+        },
+        CategoryText {
+            name: "Starting to affect health",
+            advice: "Watch your health; if you cough, have trouble breathing or eye irritation, spend less time outside or wear a protective mask when needed",
+        },
+        CategoryText {
+            name: "Affects health",
+            advice: "Everyone should avoid outdoor activities and polluted areas and wear a protective mask when needed; see a doctor if you have symptoms",
+        },
+    ],
+    caqi_categories: [
+        CategoryText {
+            name: "Very low",
+            advice: "Clean air, outdoor activities as usual",
+        },
+        CategoryText {
+            name: "Low",
+            advice: "Outdoor activities as usual",
+        },
+        CategoryText {
+            name: "Medium",
+            advice: "Sensitive people should cut down on strenuous outdoor activity",
+        },
+        CategoryText {
+            name: "High",
+            advice: "Sensitive people should avoid outdoor activity; everyone else should cut down on strenuous activity",
+        },
+        CategoryText {
+            name: "Very high",
+            advice: "Everyone should cut down on outdoor activity; sensitive people should stay indoors",
+        },
+    ],
+    naqi_categories: [
+        CategoryText {
+            name: "Good",
+            advice: "Minimal impact",
+        },
+        CategoryText {
+            name: "Satisfactory",
+            advice: "Minor breathing discomfort for sensitive people",
+        },
+        CategoryText {
+            name: "Moderately polluted",
+            advice: "Breathing discomfort for people with lung disease, asthma or heart disease, children and older adults",
+        },
+        CategoryText {
+            name: "Poor",
+            advice: "Breathing discomfort for most people on prolonged exposure",
+        },
+        CategoryText {
+            name: "Very poor",
+            advice: "Respiratory illness on prolonged exposure; stay indoors",
+        },
+        CategoryText {
+            name: "Severe",
+            advice: "Affects healthy people and seriously impacts those with existing diseases; avoid outdoor activity",
+        },
+    ],
+    standard_names: [
+        "US AQI (US EPA)",
+        "Thailand AQI (Pollution Control Department)",
+        "European CAQI",
+        "India NAQI",
+    ],
+    index_labels: ["AQI", "Thai AQI", "CAQI", "NAQI"],
+    conditions: [
+        "Clear sky",
+        "Partly cloudy",
+        "Cloudy",
+        "Fog",
+        "Drizzle",
+        "Rain",
+        "Thunderstorm",
+        "Snow",
+    ],
+    periods: ["24 hours", "7 days"],
+    trends: ["📈 worsening", "📉 improving", "➖ steady"],
+    activities: ["running", "the playground", "cycling"],
+
+    estimated: " (estimated)",
+    source: "Source",
+    china_aqi: "China AQI {aqi}",
+    main_pollutant: "Main pollutant {pollutant}",
+    spread: "📊 {sources} sources, range {min}–{max}",
+    spread_inconsistent: " ⚠️ sources disagree",
+    stale_minutes: "{age} min",
+    stale_hours: "{age} h",
+    stale_note: "⏳ Couldn't update, showing the reading from {age} ago",
+    worsened: "⬆️ Air quality is getting worse: US AQI {from} → {to}",
+    improved: "⬇️ Air quality is getting better: US AQI {from} → {to}",
+
+    digest_header: "☀️ Morning air quality · {date}",
+    digest_peak: " · yesterday's peak AQI {aqi}",
+    digest_unavailable: "⚪ {place}: no data right now",
+    digest_advice: "💡 {advice}",
+    digest_plan: "    🌿 Best time to go out today {start}–{end}",
+
+    city_not_found: "City \"{city}\" not found. Check the spelling or give coordinates instead, e.g. 13.46,101.09",
+    rate_limited_for: "Too many requests, please try again in {secs} seconds",
+    rate_limited: "Too many requests, please try again shortly",
+    quota_exhausted: "This month's data quota is nearly used up. The channel will still get its regular updates",
+    upstream_unavailable: "The air quality source is temporarily unavailable, please try again later",
+    invalid_api_key: "The data source is misconfigured, please tell the bot's admin",
+    timeout: "The data source took too long to answer, please try again later",
+
+    check_usage: "Please name a city, e.g. /check Ban Suan or /check 13.46,101.09",
+    invalid_reading: "❌ The reading for {place} looks wrong, please try again",
+    check_failed: "❌ Couldn't get data for {place}: {reason}",
+    subscribe_usage: "Please name an area, e.g. /subscribe Si Racha unhealthy\nLevels: {levels}",
+    subscribed: "🔔 You'll be alerted when {place} reaches {level} or worse",
+    subscribe_failed: "❌ Couldn't save the alert, please try again",
+    unsubscribe_none: "No matching alerts, see the list with /subscriptions",
+    unsubscribed: "🔕 Removed {count} alert(s)",
+    unsubscribe_failed: "❌ Couldn't remove the alerts, please try again",
+    subscriptions_empty: "No alerts yet, start with /subscribe Si Racha",
+    subscriptions_header: "🔔 Alerts for this chat",
+    subscription_item: "• {place} — {level} or worse",
+    subscriptions_failed: "❌ Couldn't load the alerts, please try again",
+    history_usage: "Please name an area, e.g. /history Ban Suan or /history Ban Suan 7d",
+    history_summary: "📊 {place} · last {period}\n\n\
+        AQI min {aqi_min} · max {aqi_max} · average {aqi_average}\n\
+        PM2.5 min {pm25_min} · max {pm25_max} · average {pm25_average} µg/m³\n\
+        Worst {worst_at} (AQI {worst_aqi})\n\
+        Trend {trend}\n\n\
+        From {readings} stored readings",
+    no_history: "No history for {place} yet, try /check {place} or /subscribe {place} first",
+    history_failed: "❌ Couldn't load the history, please try again",
+    chart_usage: "Please name an area, e.g. /chart Ban Suan or /chart Ban Suan 7d",
+    chart_caption: "📈 AQI in {place}, last {period}",
+    chart_failed: "❌ Couldn't draw the chart, please try again",
+    forecast_usage: "Please name an area, e.g. /forecast Ban Suan or /forecast Ban Suan 72h",
+    forecast_header: "🔮 {place} · next {hours} hours",
+    forecast_best: "🌿 Best time to go out {start}–{end}, AQI around {aqi}",
+    forecast_no_window: "🌙 No daytime hours in this forecast",
+    forecast_failed: "❌ Couldn't get the forecast for {place}: {reason}",
+    forecast_unavailable: "Forecasts aren't available on this bot",
+    bestime_usage: "Please name an area, e.g. /bestime Ban Suan or /bestime Ban Suan cycling\n\
+        Activities: {activities}",
+    bestime_header: "{emoji} Best times for {activity} · {place}",
+    bestime_window: "{rank}. {start}–{end}",
+    bestime_from_history: "No forecast right now, so this is based on the past week",
+    bestime_none: "🌙 No daytime hours to suggest in the next day",
+    bestime_failed: "❌ Couldn't find a good time in {place}: {reason}",
+    standard_current: "📏 Current standard: {standard}\nChange it with /standard and a code\n{choices}",
+    standard_unknown: "Unknown standard \"{input}\", choose from\n{choices}",
+    standard_saved: "✅ This chat now uses {standard}",
+    standard_failed: "❌ Couldn't save the standard, please try again",
+    language_current: "🌐 Current language: {language}\nChange it with /lang and a code\n{choices}",
+    language_unknown: "Unknown language \"{input}\", choose from\n{choices}",
+    language_saved: "✅ This chat now uses English",
+    language_failed: "❌ Couldn't save the language, please try again",
+    template_current: "📝 This chat's message template:\n{template}\n\n\
+        Change it with /template and your text, or go back to the default with /template reset\n\
+        Placeholders: {placeholders}",
+    template_builtin: "📝 This chat uses the built-in message layout\n\
+        Write your own with /template and your text, e.g. /template {level_emoji} {place} AQI {aqi}\n\
+        Placeholders: {placeholders}",
+    template_saved: "✅ This chat now uses your template",
+    template_reset: "✅ This chat is back to the default layout",
+    template_invalid: "❌ Can't use that template: {reason}\nPlaceholders: {placeholders}",
+    template_failed: "❌ Couldn't save the template, please try again",
+    template_empty: "there's no text",
+    template_too_long: "it's longer than {max} characters",
+    template_unclosed: "the { at character {position} is never closed",
+    template_unknown: "unknown placeholder {name}",
+
+    reason_clean_air: "clean air (AQI {aqi})",
+    reason_fair_air: "acceptable air (AQI {aqi})",
+    reason_least_polluted: "the least polluted air (AQI {aqi})",
+    reason_comfortable: "comfortable {temperature}°C",
+    reason_hot: "hot at {temperature}°C, bring water",
+    reason_cold: "cool at {temperature}°C, dress warmly",
+    reason_humid: "humid at {humidity}%",
+};
@@ -0,0 +1,673 @@
+mod en;
+mod th;
+
+use crate::domain::errors::AirQualityError;
+use crate::domain::history::{HistoryPeriod, Trend};
+use crate::domain::models::AirQualityLevel;
+use crate::domain::recommendation::{Activity, Reason};
+use crate::domain::standards::{AqiCategory, AqiStandard};
+use crate::domain::template::TemplateError;
+use crate::domain::weather::WeatherCondition;
+use std::fmt::{self, Write};
+
+/// Language of everything the bot says to a chat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Thai,
+    English,
+}
+
+impl Locale {
+    pub const ALL: [Self; 2] = [Self::Thai, Self::English];
+
+    /// Short ASCII name used in bot commands, config and storage.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Thai => "th",
+            Self::English => "en",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.slug().eq_ignore_ascii_case(slug.trim()))
+    }
+
+    pub fn catalogue(&self) -> &'static Catalogue {
+        match self {
+            Self::Thai => &th::CATALOGUE,
+            Self::English => &en::CATALOGUE,
+        }
+    }
+}
+
+/// Name and advice for one category of a standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryText {
+    pub name: &'static str,
+    pub advice: &'static str,
+}
+
+/// Every user-facing text in one language.
+///
+/// Templates name their arguments in braces, e.g. `{place}`, and are filled in
+/// with [`fill`]. Arrays follow the order of the matching enum's variants (and
+/// of each standard's bands, best first).
+#[derive(Debug)]
+pub struct Catalogue {
+    /// The language's own name, e.g. "English".
+    pub language_name: &'static str,
+    pub help: &'static str,
+
+    pub levels: [CategoryText; 6],
+    pub thai_pcd_categories: [CategoryText; 5],
+    pub caqi_categories: [CategoryText; 5],
+    pub naqi_categories: [CategoryText; 6],
+    pub standard_names: [&'static str; 4],
+    pub index_labels: [&'static str; 4],
+    pub conditions: [&'static str; 8],
+    pub periods: [&'static str; 2],
+    pub trends: [&'static str; 3],
+    pub activities: [&'static str; 3],
+
+    // Readings
+    pub estimated: &'static str,
+    pub source: &'static str,
+    /// `{aqi}`
+    pub china_aqi: &'static str,
+    /// `{pollutant}`
+    pub main_pollutant: &'static str,
+    /// `{sources} {min} {max}`
+    pub spread: &'static str,
+    pub spread_inconsistent: &'static str,
+    /// `{age}`
+    pub stale_minutes: &'static str,
+    /// `{age}`
+    pub stale_hours: &'static str,
+    /// `{age}`, one of the two above
+    pub stale_note: &'static str,
+    /// `{from} {to}`, US AQI levels
+    pub worsened: &'static str,
+    /// `{from} {to}`, US AQI levels
+    pub improved: &'static str,
+
+    // Morning digest
+    /// `{date}`
+    pub digest_header: &'static str,
+    /// `{aqi}`, appended to a location's line
+    pub digest_peak: &'static str,
+    /// `{place}`
+    pub digest_unavailable: &'static str,
+    /// `{advice}`, for the worst location
+    pub digest_advice: &'static str,
+    /// `{start} {end}`, under a location's line
+    pub digest_plan: &'static str,
+
+    // Errors fetching a reading
+    /// `{city}`
+    pub city_not_found: &'static str,
+    /// `{secs}`
+    pub rate_limited_for: &'static str,
+    pub rate_limited: &'static str,
+    pub quota_exhausted: &'static str,
+    pub upstream_unavailable: &'static str,
+    pub invalid_api_key: &'static str,
+    pub timeout: &'static str,
+
+    // Commands
+    pub check_usage: &'static str,
+    /// `{place}`
+    pub invalid_reading: &'static str,
+    /// `{place} {reason}`
+    pub check_failed: &'static str,
+    /// `{levels}`
+    pub subscribe_usage: &'static str,
+    /// `{place} {level}`
+    pub subscribed: &'static str,
+    pub subscribe_failed: &'static str,
+    pub unsubscribe_none: &'static str,
+    /// `{count}`
+    pub unsubscribed: &'static str,
+    pub unsubscribe_failed: &'static str,
+    pub subscriptions_empty: &'static str,
+    pub subscriptions_header: &'static str,
+    /// `{place} {level}`
+    pub subscription_item: &'static str,
+    pub subscriptions_failed: &'static str,
+    pub history_usage: &'static str,
+    /// `{place} {period} {aqi_min} {aqi_max} {aqi_average} {pm25_min} {pm25_max}
+    /// {pm25_average} {worst_at} {worst_aqi} {trend} {readings}`
+    pub history_summary: &'static str,
+    /// `{place}`
+    pub no_history: &'static str,
+    pub history_failed: &'static str,
+    pub chart_usage: &'static str,
+    /// `{place} {period}`
+    pub chart_caption: &'static str,
+    pub chart_failed: &'static str,
+    pub forecast_usage: &'static str,
+    /// `{place} {hours}`
+    pub forecast_header: &'static str,
+    /// `{start} {end} {aqi}`
+    pub forecast_best: &'static str,
+    pub forecast_no_window: &'static str,
+    /// `{place} {reason}`
+    pub forecast_failed: &'static str,
+    pub forecast_unavailable: &'static str,
+    /// `{activities}`
+    pub bestime_usage: &'static str,
+    /// `{emoji} {activity} {place}`
+    pub bestime_header: &'static str,
+    /// `{rank} {start} {end}`
+    pub bestime_window: &'static str,
+    pub bestime_from_history: &'static str,
+    pub bestime_none: &'static str,
+    /// `{place} {reason}`
+    pub bestime_failed: &'static str,
+    /// `{standard} {choices}`
+    pub standard_current: &'static str,
+    /// `{input} {choices}`
+    pub standard_unknown: &'static str,
+    /// `{standard}`
+    pub standard_saved: &'static str,
+    pub standard_failed: &'static str,
+    /// `{language} {choices}`
+    pub language_current: &'static str,
+    /// `{input} {choices}`
+    pub language_unknown: &'static str,
+    /// Sent in the newly chosen language.
+    pub language_saved: &'static str,
+    pub language_failed: &'static str,
+    /// `{template} {placeholders}`
+    pub template_current: &'static str,
+    /// `{placeholders}`
+    pub template_builtin: &'static str,
+    pub template_saved: &'static str,
+    pub template_reset: &'static str,
+    /// `{reason} {placeholders}`
+    pub template_invalid: &'static str,
+    pub template_failed: &'static str,
+
+    // Why a template was rejected
+    pub template_empty: &'static str,
+    /// `{max}`
+    pub template_too_long: &'static str,
+    /// `{position}`
+    pub template_unclosed: &'static str,
+    /// `{name}`
+    pub template_unknown: &'static str,
+
+    // Why a window suits an activity
+    /// `{aqi}`
+    pub reason_clean_air: &'static str,
+    /// `{aqi}`
+    pub reason_fair_air: &'static str,
+    /// `{aqi}`
+    pub reason_least_polluted: &'static str,
+    /// `{temperature}`
+    pub reason_comfortable: &'static str,
+    /// `{temperature}`
+    pub reason_hot: &'static str,
+    /// `{temperature}`
+    pub reason_cold: &'static str,
+    /// `{humidity}`
+    pub reason_humid: &'static str,
+}
+
+impl Catalogue {
+    pub fn level(&self, level: AirQualityLevel) -> &CategoryText {
+        &self.levels[level as usize]
+    }
+
+    pub fn category(&self, category: &AqiCategory) -> &CategoryText {
+        let texts: &[CategoryText] = match category.standard {
+            AqiStandard::UsEpa => &self.levels,
+            AqiStandard::ThaiPcd => &self.thai_pcd_categories,
+            AqiStandard::EuropeanCaqi => &self.caqi_categories,
+            AqiStandard::IndiaNaqi => &self.naqi_categories,
+        };
+        &texts[category.rank]
+    }
+
+    pub fn standard_name(&self, standard: AqiStandard) -> &'static str {
+        self.standard_names[standard as usize]
+    }
+
+    /// Label shown in front of the index value.
+    pub fn index_label(&self, standard: AqiStandard) -> &'static str {
+        self.index_labels[standard as usize]
+    }
+
+    pub fn condition(&self, condition: WeatherCondition) -> &'static str {
+        self.conditions[condition as usize]
+    }
+
+    pub fn period(&self, period: HistoryPeriod) -> &'static str {
+        self.periods[period as usize]
+    }
+
+    pub fn trend(&self, trend: Trend) -> &'static str {
+        self.trends[trend as usize]
+    }
+
+    pub fn activity(&self, activity: Activity) -> &'static str {
+        self.activities[activity as usize]
+    }
+
+    /// Reply for bot users, without internal details.
+    pub fn error(&self, error: &AirQualityError) -> String {
+        match error {
+            AirQualityError::CityNotFound(city) => fill(self.city_not_found, &[("city", city)]),
+            AirQualityError::RateLimited {
+                retry_after_secs: Some(secs),
+            } => fill(self.rate_limited_for, &[("secs", secs)]),
+            AirQualityError::RateLimited {
+                retry_after_secs: None,
+            } => self.rate_limited.to_string(),
+            AirQualityError::QuotaExhausted => self.quota_exhausted.to_string(),
+            AirQualityError::UpstreamUnavailable(_) | AirQualityError::Parse(_) => {
+                self.upstream_unavailable.to_string()
+            }
+            AirQualityError::InvalidApiKey => self.invalid_api_key.to_string(),
+            AirQualityError::Timeout => self.timeout.to_string(),
+        }
+    }
+
+    /// Why a template was rejected, for the chat that sent it.
+    pub fn template_error(&self, error: &TemplateError) -> String {
+        match error {
+            TemplateError::Empty => self.template_empty.to_string(),
+            TemplateError::TooLong { max } => fill(self.template_too_long, &[("max", max)]),
+            TemplateError::Unclosed { position } => {
+                fill(self.template_unclosed, &[("position", position)])
+            }
+            TemplateError::UnknownPlaceholder(name) => {
+                fill(self.template_unknown, &[("name", &format!("{{{}}}", name))])
+            }
+        }
+    }
+
+    pub fn reason(&self, reason: &Reason) -> String {
+        match *reason {
+            Reason::CleanAir { aqi } => fill(self.reason_clean_air, &[("aqi", &aqi)]),
+            Reason::FairAir { aqi } => fill(self.reason_fair_air, &[("aqi", &aqi)]),
+            Reason::LeastPollutedAir { aqi } => fill(self.reason_least_polluted, &[("aqi", &aqi)]),
+            Reason::Comfortable { temperature } => {
+                fill(self.reason_comfortable, &[("temperature", &temperature)])
+            }
+            Reason::Hot { temperature } => fill(self.reason_hot, &[("temperature", &temperature)]),
+            Reason::Cold { temperature } => {
+                fill(self.reason_cold, &[("temperature", &temperature)])
+            }
+            Reason::Humid { humidity } => fill(self.reason_humid, &[("humidity", &humidity)]),
+        }
+    }
+
+    /// Note saying how old a stale reading is.
+    pub fn stale_note(&self, age_minutes: i64) -> String {
+        let age = if age_minutes < 60 {
+            fill(self.stale_minutes, &[("age", &age_minutes)])
+        } else {
+            fill(self.stale_hours, &[("age", &(age_minutes / 60))])
+        };
+        fill(self.stale_note, &[("age", &age)])
+    }
+}
+
+/// Replaces each `{name}` in `template` with the matching argument. Braces that
+/// don't name an argument are kept as they are.
+pub fn fill(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    fill_escaped(template, args, str::to_string)
+}
+
+/// Like [`fill`], passing the template's own text through `escape` while the
+/// arguments are inserted as they are.
+pub fn fill_escaped(
+    template: &str,
+    args: &[(&str, &dyn fmt::Display)],
+    escape: impl Fn(&str) -> String,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&escape(&rest[..open]));
+        rest = &rest[open..];
+        let arg = rest.find('}').and_then(|close| {
+            let name = &rest[1..close];
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| (close, value))
+        });
+        match arg {
+            Some((close, value)) => {
+                let _ = write!(out, "{}", value);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                out.push_str(&escape("{"));
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(&escape(rest));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::template::PLACEHOLDERS;
+
+    /// Names in braces that `fill` would try to replace.
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names = Vec::new();
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            rest = &rest[open + 1..];
+            if let Some(close) = rest.find('}') {
+                let name = &rest[..close];
+                if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Each template with the arguments its field's doc comment lists.
+    fn templates(c: &Catalogue) -> Vec<(&'static str, &'static str, &'static [&'static str])> {
+        vec![
+            ("china_aqi", c.china_aqi, &["aqi"]),
+            ("main_pollutant", c.main_pollutant, &["pollutant"]),
+            ("spread", c.spread, &["sources", "min", "max"]),
+            ("stale_minutes", c.stale_minutes, &["age"]),
+            ("stale_hours", c.stale_hours, &["age"]),
+            ("stale_note", c.stale_note, &["age"]),
+            ("worsened", c.worsened, &["from", "to"]),
+            ("improved", c.improved, &["from", "to"]),
+            ("digest_header", c.digest_header, &["date"]),
+            ("digest_peak", c.digest_peak, &["aqi"]),
+            ("digest_unavailable", c.digest_unavailable, &["place"]),
+            ("digest_advice", c.digest_advice, &["advice"]),
+            ("digest_plan", c.digest_plan, &["start", "end"]),
+            ("city_not_found", c.city_not_found, &["city"]),
+            ("rate_limited_for", c.rate_limited_for, &["secs"]),
+            ("invalid_reading", c.invalid_reading, &["place"]),
+            ("check_failed", c.check_failed, &["place", "reason"]),
+            ("subscribe_usage", c.subscribe_usage, &["levels"]),
+            ("subscribed", c.subscribed, &["place", "level"]),
+            ("unsubscribed", c.unsubscribed, &["count"]),
+            (
+                "subscription_item",
+                c.subscription_item,
+                &["place", "level"],
+            ),
+            (
+                "history_summary",
+                c.history_summary,
+                &[
+                    "place",
+                    "period",
+                    "aqi_min",
+                    "aqi_max",
+                    "aqi_average",
+                    "pm25_min",
+                    "pm25_max",
+                    "pm25_average",
+                    "worst_at",
+                    "worst_aqi",
+                    "trend",
+                    "readings",
+                ],
+            ),
+            ("no_history", c.no_history, &["place"]),
+            ("chart_caption", c.chart_caption, &["place", "period"]),
+            ("forecast_header", c.forecast_header, &["place", "hours"]),
+            ("forecast_best", c.forecast_best, &["start", "end", "aqi"]),
+            ("forecast_failed", c.forecast_failed, &["place", "reason"]),
+            ("bestime_usage", c.bestime_usage, &["activities"]),
+            (
+                "bestime_header",
+                c.bestime_header,
+                &["emoji", "activity", "place"],
+            ),
+            (
+                "bestime_window",
+                c.bestime_window,
+                &["rank", "start", "end"],
+            ),
+            ("bestime_failed", c.bestime_failed, &["place", "reason"]),
+            (
+                "standard_current",
+                c.standard_current,
+                &["standard", "choices"],
+            ),
+            (
+                "standard_unknown",
+                c.standard_unknown,
+                &["input", "choices"],
+            ),
+            ("standard_saved", c.standard_saved, &["standard"]),
+            (
+                "language_current",
+                c.language_current,
+                &["language", "choices"],
+            ),
+            (
+                "language_unknown",
+                c.language_unknown,
+                &["input", "choices"],
+            ),
+            (
+                "template_current",
+                c.template_current,
+                &["template", "placeholders"],
+            ),
+            ("template_builtin", c.template_builtin, &["placeholders"]),
+            (
+                "template_invalid",
+                c.template_invalid,
+                &["reason", "placeholders"],
+            ),
+            ("template_too_long", c.template_too_long, &["max"]),
+            ("template_unclosed", c.template_unclosed, &["position"]),
+            ("template_unknown", c.template_unknown, &["name"]),
+            ("reason_clean_air", c.reason_clean_air, &["aqi"]),
+            ("reason_fair_air", c.reason_fair_air, &["aqi"]),
+            ("reason_least_polluted", c.reason_least_polluted, &["aqi"]),
+            ("reason_comfortable", c.reason_comfortable, &["temperature"]),
+            ("reason_hot", c.reason_hot, &["temperature"]),
+            ("reason_cold", c.reason_cold, &["temperature"]),
+            ("reason_humid", c.reason_humid, &["humidity"]),
+        ]
+    }
+
+    /// Texts sent as they are, without arguments.
+    fn plain_texts(c: &Catalogue) -> Vec<(&'static str, &'static str)> {
+        let mut texts = vec![
+            ("language_name", c.language_name),
+            ("estimated", c.estimated),
+            ("source", c.source),
+            ("spread_inconsistent", c.spread_inconsistent),
+            ("rate_limited", c.rate_limited),
+            ("quota_exhausted", c.quota_exhausted),
+            ("upstream_unavailable", c.upstream_unavailable),
+            ("invalid_api_key", c.invalid_api_key),
+            ("timeout", c.timeout),
+            ("check_usage", c.check_usage),
+            ("subscribe_failed", c.subscribe_failed),
+            ("unsubscribe_none", c.unsubscribe_none),
+            ("unsubscribe_failed", c.unsubscribe_failed),
+            ("subscriptions_empty", c.subscriptions_empty),
+            ("subscriptions_header", c.subscriptions_header),
+            ("subscriptions_failed", c.subscriptions_failed),
+            ("history_usage", c.history_usage),
+            ("history_failed", c.history_failed),
+            ("chart_usage", c.chart_usage),
+            ("chart_failed", c.chart_failed),
+            ("forecast_usage", c.forecast_usage),
+            ("forecast_no_window", c.forecast_no_window),
+            ("forecast_unavailable", c.forecast_unavailable),
+            ("bestime_from_history", c.bestime_from_history),
+            ("bestime_none", c.bestime_none),
+            ("standard_failed", c.standard_failed),
+            ("language_saved", c.language_saved),
+            ("language_failed", c.language_failed),
+            ("template_saved", c.template_saved),
+            ("template_reset", c.template_reset),
+            ("template_failed", c.template_failed),
+            ("template_empty", c.template_empty),
+        ];
+        let categories = c
+            .levels
+            .iter()
+            .chain(&c.thai_pcd_categories)
+            .chain(&c.caqi_categories)
+            .chain(&c.naqi_categories);
+        for text in categories {
+            texts.push(("category name", text.name));
+            texts.push(("category advice", text.advice));
+        }
+        let labels = c
+            .standard_names
+            .iter()
+            .chain(&c.index_labels)
+            .chain(&c.conditions)
+            .chain(&c.periods)
+            .chain(&c.trends)
+            .chain(&c.activities);
+        for label in labels {
+            texts.push(("label", *label));
+        }
+        texts
+    }
+
+    #[test]
+    fn templates_use_exactly_their_documented_args() {
+        for locale in Locale::ALL {
+            for (field, text, args) in templates(locale.catalogue()) {
+                let mut used = placeholders(text);
+                // Example message templates shown to users are left unfilled.
+                if field == "template_builtin" {
+                    used.retain(|name| !PLACEHOLDERS.contains(name));
+                }
+                for name in &used {
+                    assert!(
+                        args.contains(name),
+                        "{}.{} uses undocumented {{{}}}",
+                        locale.slug(),
+                        field,
+                        name
+                    );
+                }
+                for arg in args {
+                    assert!(
+                        used.contains(arg),
+                        "{}.{} never uses {{{}}}",
+                        locale.slug(),
+                        field,
+                        arg
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn plain_texts_have_no_placeholders() {
+        for locale in Locale::ALL {
+            let catalogue = locale.catalogue();
+            for (field, text) in plain_texts(catalogue) {
+                assert_eq!(
+                    placeholders(text),
+                    Vec::<&str>::new(),
+                    "{}.{}: {}",
+                    locale.slug(),
+                    field,
+                    text
+                );
+            }
+            // The help only shows an example message template.
+            for name in placeholders(catalogue.help) {
+                assert!(
+                    PLACEHOLDERS.contains(&name),
+                    "{}.help uses {{{}}}",
+                    locale.slug(),
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fill_replaces_every_occurrence() {
+        assert_eq!(
+            fill(
+                "{place} AQI {aqi}, still {place}",
+                &[("place", &"Rayong"), ("aqi", &87)]
+            ),
+            "Rayong AQI 87, still Rayong"
+        );
+        assert_eq!(fill("{a}{b}", &[("a", &1), ("b", &2)]), "12");
+        assert_eq!(fill("no args", &[("place", &"Rayong")]), "no args");
+        assert_eq!(fill("", &[]), "");
+    }
+
+    #[test]
+    fn fill_keeps_unknown_braces() {
+        let args: &[(&str, &dyn fmt::Display)] = &[("aqi", &87)];
+        assert_eq!(fill("{pm25} and {aqi}", args), "{pm25} and 87");
+        assert_eq!(fill("open { then {aqi}", args), "open { then 87");
+        assert_eq!(fill("{aqi} then {", args), "87 then {");
+        assert_eq!(fill("{}", args), "{}");
+        assert_eq!(fill("{{aqi}}", args), "{87}");
+        assert_eq!(fill("closed } alone", args), "closed } alone");
+    }
+
+    #[test]
+    fn fill_does_not_fill_args_inside_args() {
+        assert_eq!(
+            fill("{place}", &[("place", &"{aqi}"), ("aqi", &87)]),
+            "{aqi}"
+        );
+    }
+
+    #[test]
+    fn fill_escaped_escapes_only_the_template_text() {
+        let escape = |text: &str| text.replace('<', "&lt;").replace('>', "&gt;");
+        assert_eq!(
+            fill_escaped(
+                "<b>{place}</b> {missing}",
+                &[("place", &"<i>Rayong</i>")],
+                escape
+            ),
+            "&lt;b&gt;<i>Rayong</i>&lt;/b&gt; {missing}"
+        );
+        assert_eq!(
+            fill_escaped("{a}", &[("a", &"{")], |text| format!("[{}]", text)),
+            "[]{[]"
+        );
+        assert_eq!(
+            fill_escaped("x { y", &[], |text| text.to_uppercase()),
+            "X { Y"
+        );
+    }
+
+    #[test]
+    fn locales_are_found_by_slug() {
+        for locale in Locale::ALL {
+            assert_eq!(Locale::from_slug(locale.slug()), Some(locale));
+        }
+        assert_eq!(Locale::from_slug("th"), Some(Locale::Thai));
+        assert_eq!(Locale::from_slug("EN"), Some(Locale::English));
+        assert_eq!(Locale::from_slug(" en \n"), Some(Locale::English));
+        assert_eq!(Locale::from_slug(""), None);
+        assert_eq!(Locale::from_slug("english"), None);
+        assert_eq!(Locale::from_slug("ไทย"), None);
+        assert_eq!(Locale::default(), Locale::Thai);
+    }
+}
@@ -0,0 +1,231 @@
+use super::{Catalogue, CategoryText};
+
+pub static CATALOGUE: Catalogue = Catalogue {
+    language_name: "ภาษาไทย",
+    help: "คำสั่งที่ใช้ได้:\n\n\
+        /help — แสดงคำสั่งทั้งหมด\n\
+        /pm25 — ดูคุณภาพอากาศทุกพื้นที่\n\
+        /check — ดูคุณภาพอากาศ เช่น /check Ban Suan\n\
+        /subscribe — รับแจ้งเตือนเมื่ออากาศแย่ เช่น /subscribe Si Racha unhealthy\n\
+        /unsubscribe — เลิกรับแจ้งเตือน เช่น /unsubscribe Si Racha (ไม่ระบุ = ทั้งหมด)\n\
+        /subscriptions — ดูรายการแจ้งเตือนที่รับอยู่\n\
+        /history — ดูสถิติย้อนหลัง เช่น /history Ban Suan 7d (24h หรือ 7d)\n\
+        /chart — ดูกราฟ AQI ย้อนหลัง เช่น /chart Ban Suan 7d (24h หรือ 7d)\n\
+        /standard — เลือกมาตรฐานดัชนีคุณภาพอากาศ เช่น /standard th (us, th, eu, in)\n\
+        /lang — เลือกภาษา เช่น /lang en (th, en)\n\
+        /template — กำหนดรูปแบบข้อความเอง เช่น /template {level_emoji} {place} AQI {aqi}\n\
+        /forecast — ดูพยากรณ์และช่วงเวลาที่ดีที่สุดสำหรับออกไปข้างนอก เช่น /forecast Ban Suan 48h (24h, 48h หรือ 72h)\n\
+        /bestime — ช่วงเวลาที่ดีที่สุดสำหรับกิจกรรม เช่น /bestime Ban Suan cycling (running, playground, cycling)",
+
+    levels: [
+        CategoryText {
+            name: "อากาศดี",
+            advice: "ออกไปข้างนอกได้สบายๆ 👍",
+        },
+        CategoryText {
+            name: "พอใช้ได้",
+            advice: "ออกไปได้ แต่คนแพ้ง่ายควรระวัง",
+        },
+        CategoryText {
+            name: "เริ่มแย่",
+            advice: "เด็ก คนแก่ คนป่วย ไม่ควรออกไปข้างนอก",
+        },
+        CategoryText {
+            name: "มีผลกระทบต่อสุขภาพ",
+            advice: "อันตราย! ถ้าต้องออกไป ใส่ N95",
+        },
+        CategoryText {
+            name: "มีผลกระทบต่อสุขภาพมาก",
+            advice: "อันตรายมาก! อยู่ในบ้าน ปิดหน้าต่าง เปิดเครื่องฟอก",
+        },
+        CategoryText {
+            name: "อันตรายต่อสุขภาพ",
+            advice: "ภาวะฉุกเฉิน! ทุกคนงดออกนอกบ้าน ถ้าจำเป็นต้องออกไป ใส่ N95 แล้วรีบกลับ",
+        },
+    ],
+    thai_pcd_categories: [
+        CategoryText {
+            name: "คุณภาพอากาศดีมาก",
+            advice: "เหมาะสำหรับกิจกรรมกลางแจ้งและการท่องเที่ยว",
+        },
+        CategoryText {
+            name: "คุณภาพอากาศดี",
+            advice: "ทำกิจกรรมกลางแจ้งและท่องเที่ยวได้ตามปกติ",
+        },
+        CategoryText {
+            name: "ปานกลาง",
+            advice: "คนทั่วไปทำกิจกรรมกลางแจ้งได้ตามปกติ ผู้ที่ต้องดูแลสุขภาพเป็นพิเศษถ้ามีอาการ เช่น ไอ หายใจลำบาก ระคายเคืองตา ควรลดเวลาทำกิจกรรมกลางแจ้ง",
+        },
+        CategoryText {
+            name: "เริ่มมีผลกระทบต่อสุขภาพ",
+            advice: "ควรเฝ้าระวังสุขภาพ ถ้ามีอาการ เช่น ไอ หายใจลำบาก ระคายเคืองตา ควรลดเวลาทำกิจกรรมกลางแจ้ง หรือใส่หน้ากากป้องกันเมื่อจำเป็น",
+        },
+        CategoryText {
+            name: "มีผลกระทบต่อสุขภาพ",
+            advice: "ทุกคนควรหลีกเลี่ยงกิจกรรมกลางแจ้งและพื้นที่มลพิษสูง ใส่หน้ากากป้องกันเมื่อจำเป็น ถ้ามีอาการควรปรึกษาแพทย์",
+        },
+    ],
+    caqi_categories: [
+        CategoryText {
+            name: "มลพิษต่ำมาก",
+            advice: "อากาศสะอาด ทำกิจกรรมกลางแจ้งได้ตามปกติ",
+        },
+        CategoryText {
+            name: "มลพิษต่ำ",
+            advice: "ทำกิจกรรมกลางแจ้งได้ตามปกติ",
+        },
+        CategoryText {
+            name: "มลพิษปานกลาง",
+            advice: "ผู้ที่ไวต่อมลพิษควรลดกิจกรรมกลางแจ้งที่ใช้แรงมาก",
+        },
+        CategoryText {
+            name: "มลพิษสูง",
+            advice: "ผู้ที่ไวต่อมลพิษควรหลีกเลี่ยงกิจกรรมกลางแจ้ง คนทั่วไปควรลดกิจกรรมที่ใช้แรงมาก",
+        },
+        CategoryText {
+            name: "มลพิษสูงมาก",
+            advice: "ทุกคนควรลดกิจกรรมกลางแจ้ง ผู้ที่ไวต่อมลพิษควรอยู่ในอาคาร",
+        },
+    ],
+    naqi_categories: [
+        CategoryText {
+            name: "ดี",
+            advice: "ผลกระทบต่อสุขภาพน้อยมาก",
+        },
+        CategoryText {
+            name: "พอใช้",
+            advice: "ผู้ที่ไวต่อมลพิษอาจหายใจไม่สะดวกเล็กน้อย",
+        },
+        CategoryText {
+            name: "มลพิษปานกลาง",
+            advice: "ผู้ป่วยโรคปอด หอบหืด โรคหัวใจ เด็กและผู้สูงอายุ อาจหายใจไม่สะดวก",
+        },
+        CategoryText {
+            name: "แย่",
+            advice: "คนส่วนใหญ่อาจหายใจไม่สะดวกถ้าอยู่กลางแจ้งนาน",
+        },
+        CategoryText {
+            name: "แย่มาก",
+            advice: "อยู่กลางแจ้งนานอาจทำให้เกิดโรคทางเดินหายใจ ควรอยู่ในอาคาร",
+        },
+        CategoryText {
+            name: "รุนแรง",
+            advice: "กระทบสุขภาพคนทั่วไป และอันตรายต่อผู้มีโรคประจำตัว งดกิจกรรมกลางแจ้ง",
+        },
+    ],
+    standard_names: [
+        "AQI สหรัฐฯ (US EPA)",
+        "AQI ประเทศไทย (กรมควบคุมมลพิษ)",
+        "CAQI ยุโรป",
+        "NAQI อินเดีย",
+    ],
+    index_labels: ["AQI", "AQI ไทย", "CAQI", "NAQI"],
+    conditions: [
+        "ท้องฟ้าแจ่มใส",
+        "มีเมฆบางส่วน",
+        "เมฆมาก",
+        "หมอก",
+        "ฝนปรอย",
+        "ฝนตก",
+        "พายุฝนฟ้าคะนอง",
+        "หิมะ",
+    ],
+    periods: ["24 ชั่วโมง", "7 วัน"],
+    trends: ["📈 แย่ลง", "📉 ดีขึ้น", "➖ ทรงตัว"],
+    activities: ["วิ่ง", "พาเด็กไปสนามเด็กเล่น", "ปั่นจักรยาน"],
+
+    estimated: " (ประมาณการ)",
+    source: "ที่มา",
+    china_aqi: "AQI จีน {aqi}",
+    main_pollutant: "มลพิษหลัก {pollutant}",
+    spread: "📊 {sources} แหล่งข้อมูล ช่วง {min}–{max}",
+    spread_inconsistent: " ⚠️ ค่าไม่สอดคล้องกัน",
+    stale_minutes: "{age} นาที",
+    stale_hours: "{age} ชั่วโมง",
+    stale_note: "⏳ อัปเดตข้อมูลไม่ได้ แสดงค่าเมื่อ {age}ที่แล้ว",
+    worsened: "⬆️ คุณภาพอากาศแย่ลง: US AQI {from} → {to}",
+    improved: "⬇️ คุณภาพอากาศดีขึ้น: US AQI {from} → {to}",
+
+    digest_header: "☀️ คุณภาพอากาศเช้านี้ · {date}",
+    digest_peak: " · เมื่อวานสูงสุด AQI {aqi}",
+    digest_unavailable: "⚪ {place}: ยังไม่มีข้อมูล",
+    digest_advice: "💡 {advice}",
+    digest_plan: "    🌿 วันนี้ออกไปข้างนอกได้ดีที่สุด {start}–{end}",
+
+    city_not_found: "ไม่พบเมือง \"{city}\" ลองตรวจสอบการสะกด หรือระบุพิกัดแทน เช่น 13.46,101.09",
+    rate_limited_for: "มีการขอข้อมูลถี่เกินไป กรุณาลองใหม่ในอีก {secs} วินาที",
+    rate_limited: "มีการขอข้อมูลถี่เกินไป กรุณาลองใหม่ในอีกสักครู่",
+    quota_exhausted: "โควตาการขอข้อมูลของเดือนนี้ใกล้หมดแล้ว ข้อมูลจะยังส่งตามรอบปกติในช่อง",
+    upstream_unavailable: "แหล่งข้อมูลคุณภาพอากาศขัดข้องชั่วคราว กรุณาลองใหม่ภายหลัง",
+    invalid_api_key: "ระบบตั้งค่าแหล่งข้อมูลไม่ถูกต้อง กรุณาแจ้งผู้ดูแลบอท",
+    timeout: "แหล่งข้อมูลตอบกลับช้าเกินไป กรุณาลองใหม่ภายหลัง",
+
+    check_usage: "กรุณาระบุชื่อเมือง เช่น /check Ban Suan หรือ /check 13.46,101.09",
+    invalid_reading: "❌ ข้อมูลของ {place} ไม่ถูกต้อง ลองใหม่อีกครั้ง",
+    check_failed: "❌ ไม่สามารถดึงข้อมูล {place} ได้: {reason}",
+    subscribe_usage: "กรุณาระบุพื้นที่ เช่น /subscribe Si Racha unhealthy\nระดับที่ใช้ได้: {levels}",
+    subscribed: "🔔 จะแจ้งเตือน {place} เมื่อถึงระดับ {level} ขึ้นไป",
+    subscribe_failed: "❌ บันทึกการแจ้งเตือนไม่สำเร็จ ลองใหม่อีกครั้ง",
+    unsubscribe_none: "ไม่มีการแจ้งเตือนที่ตรงกัน ดูรายการได้ที่ /subscriptions",
+    unsubscribed: "🔕 ยกเลิกการแจ้งเตือนแล้ว {count} รายการ",
+    unsubscribe_failed: "❌ ยกเลิกการแจ้งเตือนไม่สำเร็จ ลองใหม่อีกครั้ง",
+    subscriptions_empty: "ยังไม่ได้รับแจ้งเตือน เริ่มได้ด้วย /subscribe Si Racha",
+    subscriptions_header: "🔔 การแจ้งเตือนของแชทนี้",
+    subscription_item: "• {place} — {level} ขึ้นไป",
+    subscriptions_failed: "❌ โหลดรายการแจ้งเตือนไม่สำเร็จ ลองใหม่อีกครั้ง",
+    history_usage: "กรุณาระบุพื้นที่ เช่น /history Ban Suan หรือ /history Ban Suan 7d",
+    history_summary: "📊 {place} · ย้อนหลัง {period}\n\n\
+        AQI ต่ำสุด {aqi_min} · สูงสุด {aqi_max} · เฉลี่ย {aqi_average}\n\
+        PM2.5 ต่ำสุด {pm25_min} · สูงสุด {pm25_max} · เฉลี่ย {pm25_average} µg/m³\n\
+        แย่ที่สุด {worst_at} (AQI {worst_aqi})\n\
+        แนวโน้ม {trend}\n\n\
+        จาก {readings} ค่าที่บันทึกไว้",
+    no_history: "ยังไม่มีข้อมูลย้อนหลังของ {place} ลองใช้ /check {place} หรือ /subscribe {place} ก่อน",
+    history_failed: "❌ โหลดข้อมูลย้อนหลังไม่สำเร็จ ลองใหม่อีกครั้ง",
+    chart_usage: "กรุณาระบุพื้นที่ เช่น /chart Ban Suan หรือ /chart Ban Suan 7d",
+    chart_caption: "📈 AQI {place} ย้อนหลัง {period}",
+    chart_failed: "❌ สร้างกราฟไม่สำเร็จ ลองใหม่อีกครั้ง",
+    forecast_usage: "กรุณาระบุพื้นที่ เช่น /forecast Ban Suan หรือ /forecast Ban Suan 72h",
+    forecast_header: "🔮 {place} · พยากรณ์ {hours} ชั่วโมงข้างหน้า",
+    forecast_best: "🌿 ช่วงที่เหมาะออกไปข้างนอก {start}–{end} AQI ประมาณ {aqi}",
+    forecast_no_window: "🌙 พยากรณ์นี้ไม่มีช่วงกลางวัน",
+    forecast_failed: "❌ ไม่สามารถดึงพยากรณ์ของ {place} ได้: {reason}",
+    forecast_unavailable: "บอทนี้ยังไม่รองรับการพยากรณ์",
+    bestime_usage: "กรุณาระบุพื้นที่ เช่น /bestime Ban Suan หรือ /bestime Ban Suan cycling\n\
+        กิจกรรม: {activities}",
+    bestime_header: "{emoji} ช่วงเวลาที่ดีที่สุดสำหรับ{activity} · {place}",
+    bestime_window: "{rank}. {start}–{end}",
+    bestime_from_history: "ตอนนี้ไม่มีพยากรณ์ จึงประเมินจากข้อมูลสัปดาห์ที่ผ่านมา",
+    bestime_none: "🌙 ไม่มีช่วงกลางวันให้แนะนำในวันข้างหน้า",
+    bestime_failed: "❌ ไม่สามารถหาช่วงเวลาที่เหมาะใน {place} ได้: {reason}",
+    standard_current: "📏 มาตรฐานที่ใช้อยู่: {standard}\nเปลี่ยนได้ด้วย /standard ตามด้วยรหัส\n{choices}",
+    standard_unknown: "ไม่รู้จักมาตรฐาน \"{input}\" เลือกได้จาก\n{choices}",
+    standard_saved: "✅ แชทนี้จะใช้ {standard} แล้ว",
+    standard_failed: "❌ บันทึกมาตรฐานไม่สำเร็จ ลองใหม่อีกครั้ง",
+    language_current: "🌐 ภาษาที่ใช้อยู่: {language}\nเปลี่ยนได้ด้วย /lang ตามด้วยรหัส\n{choices}",
+    language_unknown: "ไม่รู้จักภาษา \"{input}\" เลือกได้จาก\n{choices}",
+    language_saved: "✅ แชทนี้จะใช้ภาษาไทยแล้ว",
+    language_failed: "❌ บันทึกภาษาไม่สำเร็จ ลองใหม่อีกครั้ง",
+    template_current: "📝 รูปแบบข้อความของแชทนี้:\n{template}\n\n\
+        เปลี่ยนได้ด้วย /template ตามด้วยข้อความ หรือ /template reset เพื่อกลับไปใช้รูปแบบเริ่มต้น\n\
+        ตัวแปรที่ใช้ได้: {placeholders}",
+    template_builtin: "📝 แชทนี้ใช้รูปแบบข้อความปกติ\n\
+        กำหนดเองได้ด้วย /template ตามด้วยข้อความ เช่น /template {level_emoji} {place} AQI {aqi}\n\
+        ตัวแปรที่ใช้ได้: {placeholders}",
+    template_saved: "✅ แชทนี้จะใช้รูปแบบข้อความใหม่แล้ว",
+    template_reset: "✅ แชทนี้กลับไปใช้รูปแบบข้อความเริ่มต้นแล้ว",
+    template_invalid: "❌ ใช้รูปแบบข้อความนี้ไม่ได้: {reason}\nตัวแปรที่ใช้ได้: {placeholders}",
+    template_failed: "❌ บันทึกรูปแบบข้อความไม่สำเร็จ ลองใหม่อีกครั้ง",
+    template_empty: "ไม่มีข้อความ",
+    template_too_long: "ยาวเกิน {max} ตัวอักษร",
+    template_unclosed: "วงเล็บ { ที่ตัวอักษรที่ {position} ไม่มีวงเล็บปิด",
+    template_unknown: "ไม่รู้จักตัวแปร {name}",
+
+    reason_clean_air: "อากาศดี (AQI {aqi})",
+    reason_fair_air: "อากาศพอใช้ (AQI {aqi})",
+    reason_least_polluted: "ฝุ่นน้อยที่สุดแล้ว (AQI {aqi})",
+    reason_comfortable: "อุณหภูมิสบาย {temperature}°C",
+    reason_hot: "ร้อน {temperature}°C พกน้ำไปด้วย",
+    reason_cold: "เย็น {temperature}°C ใส่เสื้อให้อุ่น",
+    reason_humid: "ชื้น {humidity}%",
+};
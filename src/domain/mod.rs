@@ -1,2 +1,14 @@
+pub mod alerts;
+pub mod aqi;
+pub mod digest;
+pub mod errors;
+pub mod forecast;
+pub mod history;
+pub mod i18n;
 pub mod models;
+pub mod recommendation;
+pub mod schedule;
 pub mod services;
+pub mod standards;
+pub mod template;
+pub mod weather;
@@ -1,3 +1,5 @@
+use crate::domain::weather::Weather;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +52,23 @@ impl Location {
         }
     }
 
+    /// Normalized identity of the queried place, stable across display-name changes.
+    pub fn key(&self) -> String {
+        match &self.query {
+            LocationQuery::City {
+                city,
+                state,
+                country,
+            } => format!(
+                "city:{}|{}|{}",
+                city.trim().to_lowercase(),
+                state.trim().to_lowercase(),
+                country.trim().to_lowercase()
+            ),
+            LocationQuery::Coordinates { lat, lon } => format!("coords:{:.2},{:.2}", lat, lon),
+        }
+    }
+
     pub fn city_state_country(&self) -> (String, String, String) {
         match &self.query {
             LocationQuery::City { city, state, country } => {
@@ -62,42 +81,276 @@ impl Location {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AirQualityData {
     pub location: Location,
     pub aqi: i32,
-    pub pm25: i32,
-    pub temperature: i32,
-    pub humidity: i32,
+    /// Measured PM2.5 in µg/m³, or estimated from the AQI when `pm25_estimated` is set.
+    pub pm25: f64,
+    #[serde(default)]
+    pub pm25_estimated: bool,
+    /// Whatever concentrations the provider measured.
+    #[serde(default)]
+    pub pollutants: Pollutants,
+    /// Pollutant that drives the US AQI.
+    #[serde(default)]
+    pub main_pollutant: Option<Pollutant>,
+    /// China AQI, when the provider reports it.
+    #[serde(default)]
+    pub aqi_cn: Option<i32>,
+    /// Thai AQI as the Pollution Control Department reported it, used for the
+    /// Thai standard instead of recomputing it from concentrations.
+    #[serde(default)]
+    pub aqi_th: Option<i32>,
+    /// Flattened so readings stored before wind and pressure were added still load.
+    #[serde(flatten)]
+    pub weather: Weather,
+    /// Provider that served the reading, e.g. "IQAir".
+    #[serde(default)]
+    pub source: String,
+    /// Set when the reading combines several providers.
+    #[serde(default)]
+    pub spread: Option<SourceSpread>,
+    /// Set when the provider failed and an earlier reading from this time is shown.
+    #[serde(skip)]
+    pub stale_since: Option<DateTime<Utc>>,
+}
+
+impl AirQualityData {
+    /// How many minutes old a stale reading is, at least one.
+    pub fn stale_minutes(&self, now: DateTime<Utc>) -> Option<i64> {
+        Some((now - self.stale_since?).num_minutes().max(1))
+    }
+
+    /// A plain measured reading for tests.
+    #[cfg(test)]
+    pub fn sample(location: Location, aqi: i32, pm25: f64) -> Self {
+        Self {
+            location,
+            aqi,
+            pm25,
+            pm25_estimated: false,
+            pollutants: Pollutants {
+                pm25: Some(pm25),
+                ..Pollutants::default()
+            },
+            main_pollutant: Some(Pollutant::Pm25),
+            aqi_cn: None,
+            aqi_th: None,
+            weather: Weather::default(),
+            source: "Test".to_string(),
+            spread: None,
+            stale_since: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Pollutant {
+    Pm25,
+    Pm10,
+    O3,
+    No2,
+    So2,
+    Co,
+}
+
+impl Pollutant {
+    pub const ALL: [Pollutant; 6] = [
+        Pollutant::Pm25,
+        Pollutant::Pm10,
+        Pollutant::O3,
+        Pollutant::No2,
+        Pollutant::So2,
+        Pollutant::Co,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pm25 => "PM2.5",
+            Self::Pm10 => "PM10",
+            Self::O3 => "O₃",
+            Self::No2 => "NO₂",
+            Self::So2 => "SO₂",
+            Self::Co => "CO",
+        }
+    }
+
+    /// Parses IQAir's pollutant codes ("p2", "p1", "o3", "n2", "s2", "co").
+    pub fn from_iqair_code(code: &str) -> Option<Self> {
+        match code {
+            "p2" => Some(Self::Pm25),
+            "p1" => Some(Self::Pm10),
+            "o3" => Some(Self::O3),
+            "n2" => Some(Self::No2),
+            "s2" => Some(Self::So2),
+            "co" => Some(Self::Co),
+            _ => None,
+        }
+    }
+
+    /// Converts a gas mixing ratio in ppb to µg/m³ at 25 °C and 1 atm.
+    /// Particulates have no molar mass and are returned unchanged.
+    pub fn ppb_to_ugm3(&self, ppb: f64) -> f64 {
+        match self.molar_mass() {
+            Some(molar_mass) => ppb * molar_mass / Self::MOLAR_VOLUME,
+            None => ppb,
+        }
+    }
+
+    /// The inverse of `ppb_to_ugm3`.
+    pub fn ugm3_to_ppb(&self, ugm3: f64) -> f64 {
+        match self.molar_mass() {
+            Some(molar_mass) => ugm3 * Self::MOLAR_VOLUME / molar_mass,
+            None => ugm3,
+        }
+    }
+
+    /// Litres per mole of air at 25 °C and 1 atm.
+    const MOLAR_VOLUME: f64 = 24.45;
+
+    fn molar_mass(&self) -> Option<f64> {
+        match self {
+            Self::Pm25 | Self::Pm10 => None,
+            Self::O3 => Some(48.00),
+            Self::No2 => Some(46.01),
+            Self::So2 => Some(64.07),
+            Self::Co => Some(28.01),
+        }
+    }
+}
+
+/// Measured concentrations in µg/m³; each is set only when a provider reports it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pollutants {
+    pub pm25: Option<f64>,
+    pub pm10: Option<f64>,
+    pub o3: Option<f64>,
+    pub no2: Option<f64>,
+    pub so2: Option<f64>,
+    pub co: Option<f64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Pollutants {
+    pub fn get(&self, pollutant: Pollutant) -> Option<f64> {
+        match pollutant {
+            Pollutant::Pm25 => self.pm25,
+            Pollutant::Pm10 => self.pm10,
+            Pollutant::O3 => self.o3,
+            Pollutant::No2 => self.no2,
+            Pollutant::So2 => self.so2,
+            Pollutant::Co => self.co,
+        }
+    }
+
+    pub fn set(&mut self, pollutant: Pollutant, value: Option<f64>) {
+        let slot = match pollutant {
+            Pollutant::Pm25 => &mut self.pm25,
+            Pollutant::Pm10 => &mut self.pm10,
+            Pollutant::O3 => &mut self.o3,
+            Pollutant::No2 => &mut self.no2,
+            Pollutant::So2 => &mut self.so2,
+            Pollutant::Co => &mut self.co,
+        };
+        *slot = value;
+    }
+
+    /// Drops negative and non-finite values, which some sensors send for "no data".
+    pub fn without_invalid(mut self) -> Self {
+        for pollutant in Pollutant::ALL {
+            let value = self.get(pollutant).filter(|v| v.is_finite() && *v >= 0.0);
+            self.set(pollutant, value);
+        }
+        self
+    }
+
+    /// Measured pollutants in display order.
+    pub fn measured(&self) -> impl Iterator<Item = (Pollutant, f64)> + '_ {
+        Pollutant::ALL
+            .into_iter()
+            .filter_map(|p| self.get(p).map(|value| (p, value)))
+    }
+}
+
+/// How far apart the providers behind a combined reading were.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpread {
+    /// Lowest and highest AQI reported, outliers included.
+    pub min: i32,
+    pub max: i32,
+    pub sources: usize,
+    /// False when fewer than two sources agree within the configured margin.
+    pub confident: bool,
+}
+
+/// A chat's request to hear about a location once it reaches `min_level`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub chat_id: i64,
+    pub location: Location,
+    pub min_level: AirQualityLevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AirQualityLevel {
     Good,
     Moderate,
     UnhealthyForSensitive,
     Unhealthy,
     VeryUnhealthy,
+    Hazardous,
 }
 
 impl AirQualityLevel {
-    pub fn from_aqi(aqi: i32) -> Self {
+    /// `None` for a negative AQI, which no level covers.
+    pub fn from_aqi(aqi: i32) -> Option<Self> {
         match aqi {
-            0..=50 => Self::Good,
-            51..=100 => Self::Moderate,
-            101..=150 => Self::UnhealthyForSensitive,
-            151..=200 => Self::Unhealthy,
-            _ => Self::VeryUnhealthy,
+            0..=50 => Some(Self::Good),
+            51..=100 => Some(Self::Moderate),
+            101..=150 => Some(Self::UnhealthyForSensitive),
+            151..=200 => Some(Self::Unhealthy),
+            201..=300 => Some(Self::VeryUnhealthy),
+            301.. => Some(Self::Hazardous),
+            i32::MIN..=-1 => None,
+        }
+    }
+
+    pub const ALL: [Self; 6] = [
+        Self::Good,
+        Self::Moderate,
+        Self::UnhealthyForSensitive,
+        Self::Unhealthy,
+        Self::VeryUnhealthy,
+        Self::Hazardous,
+    ];
+
+    /// Short ASCII name used in bot commands and storage.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Moderate => "moderate",
+            Self::UnhealthyForSensitive => "sensitive",
+            Self::Unhealthy => "unhealthy",
+            Self::VeryUnhealthy => "very-unhealthy",
+            Self::Hazardous => "hazardous",
         }
     }
 
-    pub fn thai_description(&self) -> &'static str {
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.slug().eq_ignore_ascii_case(slug.trim()))
+    }
+
+    /// Inclusive US AQI bounds of this level.
+    pub fn aqi_range(&self) -> (i32, i32) {
         match self {
-            Self::Good => "อากาศดี",
-            Self::Moderate => "พอใช้ได้",
-            Self::UnhealthyForSensitive => "เริ่มแย่",
-            Self::Unhealthy => "มีผลกระทบต่อสุขภาพ",
-            Self::VeryUnhealthy => "มีผลกระทบต่อสุขภาพมาก",
+            Self::Good => (0, 50),
+            Self::Moderate => (51, 100),
+            Self::UnhealthyForSensitive => (101, 150),
+            Self::Unhealthy => (151, 200),
+            Self::VeryUnhealthy => (201, 300),
+            Self::Hazardous => (301, i32::MAX),
         }
     }
 
@@ -108,22 +361,67 @@ impl AirQualityLevel {
             Self::UnhealthyForSensitive => "🟠",
             Self::Unhealthy => "🔴",
             Self::VeryUnhealthy => "🟣",
+            Self::Hazardous => "🟤",
         }
     }
 
-    pub fn health_warning(&self) -> &'static str {
+    /// Chart colour matching `emoji`.
+    pub fn rgb(&self) -> (u8, u8, u8) {
         match self {
-            Self::Good => "ออกไปข้างนอกได้สบายๆ 👍",
-            Self::Moderate => "ออกไปได้ แต่คนแพ้ง่ายควรระวัง",
-            Self::UnhealthyForSensitive => {
-                "เด็ก คนแก่ คนป่วย ไม่ควรออกไปข้างนอก"
-            }
-            Self::Unhealthy => {
-                "อันตราย! ถ้าต้องออกไป ใส่ N95"
-            }
-            Self::VeryUnhealthy => {
-                "อันตรายมาก! อยู่ในบ้าน ปิดหน้าต่าง เปิดเครื่องฟอก"
-            }
+            Self::Good => (76, 175, 80),
+            Self::Moderate => (255, 213, 0),
+            Self::UnhealthyForSensitive => (255, 140, 0),
+            Self::Unhealthy => (229, 57, 53),
+            Self::VeryUnhealthy => (142, 36, 170),
+            Self::Hazardous => (126, 0, 35),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_at_each_boundary() {
+        use AirQualityLevel::*;
+        let cases = [
+            (0, Good),
+            (50, Good),
+            (51, Moderate),
+            (100, Moderate),
+            (101, UnhealthyForSensitive),
+            (150, UnhealthyForSensitive),
+            (151, Unhealthy),
+            (200, Unhealthy),
+            (201, VeryUnhealthy),
+            (300, VeryUnhealthy),
+            (301, Hazardous),
+            (500, Hazardous),
+            // Beyond the scale stays hazardous.
+            (501, Hazardous),
+            (999, Hazardous),
+            (i32::MAX, Hazardous),
+        ];
+        for (aqi, level) in cases {
+            assert_eq!(AirQualityLevel::from_aqi(aqi), Some(level), "AQI {}", aqi);
+        }
+    }
+
+    #[test]
+    fn negative_aqi_has_no_level() {
+        for aqi in [-1, -50, i32::MIN] {
+            assert_eq!(AirQualityLevel::from_aqi(aqi), None, "AQI {}", aqi);
+        }
+    }
+
+    #[test]
+    fn ranges_agree_with_from_aqi() {
+        for level in AirQualityLevel::ALL {
+            let (low, high) = level.aqi_range();
+            assert_eq!(AirQualityLevel::from_aqi(low), Some(level));
+            assert_eq!(AirQualityLevel::from_aqi(high), Some(level));
+            assert_eq!(AirQualityLevel::from_slug(level.slug()), Some(level));
         }
     }
 }
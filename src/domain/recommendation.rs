@@ -0,0 +1,455 @@
+use crate::domain::forecast::Forecast;
+use crate::domain::models::{AirQualityData, Location};
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+use std::ops::RangeInclusive;
+
+/// What a chat wants to go out for; each weighs air, heat and humidity differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Activity {
+    #[default]
+    Running,
+    Playground,
+    Cycling,
+}
+
+/// How an activity scores an hour. Every weight is a penalty, so lower scores
+/// are better.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityProfile {
+    /// Penalty per US AQI point.
+    pub aqi_weight: f64,
+    /// Temperatures in °C that cost nothing.
+    pub comfortable: RangeInclusive<i32>,
+    /// Penalty per °C outside `comfortable`.
+    pub temperature_weight: f64,
+    /// Relative humidity in % above which sweat stops cooling.
+    pub max_humidity: i32,
+    /// Penalty per % above `max_humidity`.
+    pub humidity_weight: f64,
+    /// Consecutive hours the activity takes.
+    pub hours: usize,
+}
+
+impl Activity {
+    pub const ALL: [Self; 3] = [Self::Running, Self::Playground, Self::Cycling];
+
+    /// Short ASCII name used in bot commands.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Playground => "playground",
+            Self::Cycling => "cycling",
+        }
+    }
+
+    /// Accepts the slug or a common short form ("run", "kids", "bike").
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "running" | "run" | "jogging" => Some(Self::Running),
+            "playground" | "kids" | "children" => Some(Self::Playground),
+            "cycling" | "bike" | "cycle" => Some(Self::Cycling),
+            _ => None,
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Running => "🏃",
+            Self::Playground => "🛝",
+            Self::Cycling => "🚴",
+        }
+    }
+
+    /// Runners breathe hardest and overheat first; children are the most
+    /// sensitive to particles but mind the heat less; cyclists cool in the
+    /// wind and are out the longest.
+    pub fn profile(&self) -> ActivityProfile {
+        match self {
+            Self::Running => ActivityProfile {
+                aqi_weight: 1.0,
+                comfortable: 10..=26,
+                temperature_weight: 4.0,
+                max_humidity: 70,
+                humidity_weight: 0.5,
+                hours: 1,
+            },
+            Self::Playground => ActivityProfile {
+                aqi_weight: 1.5,
+                comfortable: 18..=30,
+                temperature_weight: 2.0,
+                max_humidity: 80,
+                humidity_weight: 0.2,
+                hours: 2,
+            },
+            Self::Cycling => ActivityProfile {
+                aqi_weight: 0.8,
+                comfortable: 12..=29,
+                temperature_weight: 2.5,
+                max_humidity: 75,
+                humidity_weight: 0.3,
+                hours: 2,
+            },
+        }
+    }
+}
+
+/// Why a window was picked, or what to watch out for in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// US AQI 50 or less.
+    CleanAir {
+        aqi: i32,
+    },
+    /// US AQI 100 or less.
+    FairAir {
+        aqi: i32,
+    },
+    /// Worse than moderate, but the best there is.
+    LeastPollutedAir {
+        aqi: i32,
+    },
+    Comfortable {
+        temperature: i32,
+    },
+    Hot {
+        temperature: i32,
+    },
+    Cold {
+        temperature: i32,
+    },
+    Humid {
+        humidity: i32,
+    },
+}
+
+/// Consecutive hours recommended for an activity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecommendedWindow {
+    pub start: DateTime<Utc>,
+    /// End of the last hour in the window.
+    pub end: DateTime<Utc>,
+    pub average_aqi: i32,
+    /// Averages over the hours that reported them.
+    pub temperature: Option<i32>,
+    pub humidity: Option<i32>,
+    pub score: f64,
+}
+
+impl RecommendedWindow {
+    pub fn reasons(&self, profile: &ActivityProfile) -> Vec<Reason> {
+        let aqi = self.average_aqi;
+        let mut reasons = vec![match aqi {
+            ..=50 => Reason::CleanAir { aqi },
+            51..=100 => Reason::FairAir { aqi },
+            _ => Reason::LeastPollutedAir { aqi },
+        }];
+        if let Some(temperature) = self.temperature {
+            reasons.push(if temperature > *profile.comfortable.end() {
+                Reason::Hot { temperature }
+            } else if temperature < *profile.comfortable.start() {
+                Reason::Cold { temperature }
+            } else {
+                Reason::Comfortable { temperature }
+            });
+        }
+        if let Some(humidity) = self.humidity.filter(|h| *h > profile.max_humidity) {
+            reasons.push(Reason::Humid { humidity });
+        }
+        reasons
+    }
+}
+
+/// Where upcoming hours came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    Forecast,
+    /// The same hours of the day over the past week, when there was no forecast.
+    History,
+}
+
+/// The best upcoming windows for an activity, best first.
+#[derive(Debug, Clone)]
+pub struct Recommendation {
+    pub location: Location,
+    pub activity: Activity,
+    pub basis: Basis,
+    pub windows: Vec<RecommendedWindow>,
+}
+
+impl Recommendation {
+    /// Hours ahead that are considered.
+    pub const HORIZON_HOURS: u32 = 24;
+    /// Windows returned at most.
+    pub const TOP: usize = 3;
+
+    /// Scores every run of consecutive daytime hours in `hours` and keeps the
+    /// best windows that don't overlap, the earliest on a tie.
+    pub fn from_hours(
+        location: Location,
+        activity: Activity,
+        basis: Basis,
+        hours: &[(DateTime<Utc>, AirQualityData)],
+        timezone: FixedOffset,
+    ) -> Self {
+        let profile = activity.profile();
+        let mut candidates: Vec<RecommendedWindow> = hours
+            .windows(profile.hours)
+            .filter(|window| {
+                window
+                    .iter()
+                    .all(|(at, _)| Forecast::DAYTIME.contains(&at.with_timezone(&timezone).hour()))
+                    && window
+                        .windows(2)
+                        .all(|pair| pair[1].0 - pair[0].0 == Duration::hours(1))
+            })
+            .map(|window| score(window, &profile))
+            .collect();
+        candidates.sort_by(|a, b| a.score.total_cmp(&b.score).then(a.start.cmp(&b.start)));
+
+        let mut windows: Vec<RecommendedWindow> = Vec::new();
+        for candidate in candidates {
+            if windows.len() == Self::TOP {
+                break;
+            }
+            if windows
+                .iter()
+                .all(|kept| candidate.end <= kept.start || candidate.start >= kept.end)
+            {
+                windows.push(candidate);
+            }
+        }
+
+        Self {
+            location,
+            activity,
+            basis,
+            windows,
+        }
+    }
+}
+
+fn score(
+    window: &[(DateTime<Utc>, AirQualityData)],
+    profile: &ActivityProfile,
+) -> RecommendedWindow {
+    let average = |values: Vec<i32>| {
+        (!values.is_empty()).then(|| values.iter().sum::<i32>() / values.len() as i32)
+    };
+    let penalty: f64 = window
+        .iter()
+        .map(|(_, data)| {
+            let temperature = data.weather.temperature.map_or(0, |t| {
+                (profile.comfortable.start() - t)
+                    .max(t - profile.comfortable.end())
+                    .max(0)
+            });
+            let humidity = data
+                .weather
+                .humidity
+                .map_or(0, |h| (h - profile.max_humidity).max(0));
+            profile.aqi_weight * f64::from(data.aqi)
+                + profile.temperature_weight * f64::from(temperature)
+                + profile.humidity_weight * f64::from(humidity)
+        })
+        .sum();
+
+    RecommendedWindow {
+        start: window[0].0,
+        end: window[window.len() - 1].0 + Duration::hours(1),
+        average_aqi: average(window.iter().map(|(_, data)| data.aqi).collect()).unwrap_or_default(),
+        temperature: average(
+            window
+                .iter()
+                .filter_map(|(_, d)| d.weather.temperature)
+                .collect(),
+        ),
+        humidity: average(
+            window
+                .iter()
+                .filter_map(|(_, d)| d.weather.humidity)
+                .collect(),
+        ),
+        score: penalty / window.len() as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bangkok() -> FixedOffset {
+        FixedOffset::east_opt(7 * 3600).unwrap()
+    }
+
+    fn local(hour: u32) -> DateTime<Utc> {
+        bangkok()
+            .with_ymd_and_hms(2025, 1, 10, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn location() -> Location {
+        Location::from_city("Ban Suan", "Chon Buri", "Thailand")
+    }
+
+    /// A cool, humid morning clearing into a hot afternoon, as
+    /// `(local hour, aqi, °C, %)`.
+    const MORNING: [(u32, i32, i32, i32); 8] = [
+        (6, 60, 22, 85),
+        (7, 55, 24, 80),
+        (8, 50, 26, 72),
+        (9, 70, 29, 65),
+        (10, 80, 32, 60),
+        (11, 90, 34, 55),
+        (12, 45, 35, 50),
+        (13, 40, 35, 50),
+    ];
+
+    fn forecast(hours: &[(u32, i32, i32, i32)]) -> Vec<(DateTime<Utc>, AirQualityData)> {
+        hours
+            .iter()
+            .map(|&(hour, aqi, temperature, humidity)| {
+                let mut data = AirQualityData::sample(location(), aqi, 15.0);
+                data.weather.temperature = Some(temperature);
+                data.weather.humidity = Some(humidity);
+                (local(hour), data)
+            })
+            .collect()
+    }
+
+    fn recommend(activity: Activity) -> Recommendation {
+        Recommendation::from_hours(
+            location(),
+            activity,
+            Basis::Forecast,
+            &forecast(&MORNING),
+            bangkok(),
+        )
+    }
+
+    fn starts(recommendation: &Recommendation) -> Vec<DateTime<Utc>> {
+        recommendation.windows.iter().map(|w| w.start).collect()
+    }
+
+    #[test]
+    fn parses_activities() {
+        assert_eq!(Activity::parse(" Run "), Some(Activity::Running));
+        assert_eq!(Activity::parse("kids"), Some(Activity::Playground));
+        assert_eq!(Activity::parse("bike"), Some(Activity::Cycling));
+        assert_eq!(Activity::parse("swimming"), None);
+        for activity in Activity::ALL {
+            assert_eq!(Activity::parse(activity.slug()), Some(activity));
+        }
+    }
+
+    #[test]
+    fn running_avoids_the_heat() {
+        let recommendation = recommend(Activity::Running);
+
+        assert_eq!(starts(&recommendation), vec![local(8), local(7), local(6)]);
+        let best = &recommendation.windows[0];
+        assert_eq!(best.end, local(9));
+        // AQI 50, 2% over the humidity limit at half a point each.
+        assert_eq!(best.score, 51.0);
+        assert_eq!(
+            best.reasons(&Activity::Running.profile()),
+            vec![
+                Reason::CleanAir { aqi: 50 },
+                Reason::Comfortable { temperature: 26 },
+                Reason::Humid { humidity: 72 },
+            ]
+        );
+    }
+
+    #[test]
+    fn playgrounds_favour_clean_air_over_the_heat() {
+        let recommendation = recommend(Activity::Playground);
+
+        assert_eq!(starts(&recommendation), vec![local(12), local(7), local(9)]);
+        let best = &recommendation.windows[0];
+        assert_eq!((best.start, best.end), (local(12), local(14)));
+        assert_eq!(best.score, 73.75);
+        assert_eq!(
+            best.reasons(&Activity::Playground.profile()),
+            vec![
+                Reason::CleanAir { aqi: 42 },
+                Reason::Hot { temperature: 35 },
+            ]
+        );
+    }
+
+    #[test]
+    fn cycling_takes_two_cool_hours() {
+        let recommendation = recommend(Activity::Cycling);
+
+        assert_eq!(starts(&recommendation), vec![local(7), local(12), local(9)]);
+        let best = &recommendation.windows[0];
+        assert_eq!(best.score, 42.75);
+        assert_eq!(
+            best.reasons(&Activity::Cycling.profile()),
+            vec![
+                Reason::FairAir { aqi: 52 },
+                Reason::Comfortable { temperature: 25 },
+                Reason::Humid { humidity: 76 },
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_do_not_overlap() {
+        for activity in Activity::ALL {
+            let windows = recommend(activity).windows;
+            for (i, a) in windows.iter().enumerate() {
+                for b in &windows[i + 1..] {
+                    assert!(a.end <= b.start || a.start >= b.end, "{:?}", activity);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn night_hours_are_never_recommended() {
+        let night = [(20, 10, 24, 60), (21, 10, 24, 60), (22, 10, 24, 60)];
+        let recommendation = Recommendation::from_hours(
+            location(),
+            Activity::Running,
+            Basis::Forecast,
+            &forecast(&night),
+            bangkok(),
+        );
+
+        assert!(recommendation.windows.is_empty());
+    }
+
+    #[test]
+    fn reasons_for_poor_air_and_cold() {
+        let window = RecommendedWindow {
+            start: local(6),
+            end: local(7),
+            average_aqi: 101,
+            temperature: Some(9),
+            humidity: Some(70),
+            score: 0.0,
+        };
+        assert_eq!(
+            window.reasons(&Activity::Running.profile()),
+            vec![
+                Reason::LeastPollutedAir { aqi: 101 },
+                Reason::Cold { temperature: 9 },
+            ]
+        );
+
+        // Without weather only the air is described.
+        let window = RecommendedWindow {
+            average_aqi: 100,
+            temperature: None,
+            humidity: None,
+            ..window
+        };
+        assert_eq!(
+            window.reasons(&Activity::Running.profile()),
+            vec![Reason::FairAir { aqi: 100 }]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+use std::fmt;
+use std::str::FromStr;
+
+/// Six-field cron expression: `sec min hour day-of-month month day-of-week`.
+///
+/// Each field accepts `*`, single values, ranges (`1-5`), steps (`*/4`, `0-30/10`)
+/// and comma-separated lists. Day-of-week uses 0-6 with Sunday as 0 (7 is also Sunday).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    seconds: Field,
+    minutes: Field,
+    hours: Field,
+    days_of_month: Field,
+    months: Field,
+    days_of_week: Field,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronParseError(String);
+
+impl fmt::Display for CronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cron expression: {}", self.0)
+    }
+}
+
+impl std::error::Error for CronParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    bits: u64,
+    wildcard: bool,
+}
+
+impl Field {
+    fn parse(input: &str, name: &str, min: u32, max: u32) -> Result<Self, CronParseError> {
+        let mut bits = 0u64;
+        for part in input.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step.parse().map_err(|_| {
+                        CronParseError(format!("bad step '{}' in {} field", step, name))
+                    })?;
+                    if step == 0 {
+                        return Err(CronParseError(format!("zero step in {} field", name)));
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (
+                    Self::parse_value(start, name, min, max)?,
+                    Self::parse_value(end, name, min, max)?,
+                )
+            } else {
+                let value = Self::parse_value(range, name, min, max)?;
+                // "5/15" means "starting at 5, every 15"
+                (value, if part.contains('/') { max } else { value })
+            };
+
+            if start > end {
+                return Err(CronParseError(format!(
+                    "range {}-{} is reversed in {} field",
+                    start, end, name
+                )));
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            bits,
+            wildcard: input == "*",
+        })
+    }
+
+    fn parse_value(input: &str, name: &str, min: u32, max: u32) -> Result<u32, CronParseError> {
+        let value: u32 = input
+            .parse()
+            .map_err(|_| CronParseError(format!("bad value '{}' in {} field", input, name)))?;
+        if !(min..=max).contains(&value) {
+            return Err(CronParseError(format!(
+                "{} is outside {}-{} in {} field",
+                value, min, max, name
+            )));
+        }
+        Ok(value)
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 6 {
+            return Err(CronParseError(format!(
+                "expected 6 fields (sec min hour day month weekday), got {}",
+                fields.len()
+            )));
+        }
+
+        let mut days_of_week = Field::parse(fields[5], "weekday", 0, 7)?;
+        if days_of_week.contains(7) {
+            days_of_week.bits = (days_of_week.bits | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            seconds: Field::parse(fields[0], "second", 0, 59)?,
+            minutes: Field::parse(fields[1], "minute", 0, 59)?,
+            hours: Field::parse(fields[2], "hour", 0, 23)?,
+            days_of_month: Field::parse(fields[3], "day", 1, 31)?,
+            months: Field::parse(fields[4], "month", 1, 12)?,
+            days_of_week,
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl CronSchedule {
+    /// Returns the first tick strictly after `after`, evaluated in `after`'s offset.
+    pub fn next_after(&self, after: &DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        let start = after.naive_local().with_nanosecond(0)? + Duration::seconds(1);
+        let next = self.next_naive(start)?;
+        next.and_local_timezone(*after.offset()).single()
+    }
+
+    fn next_naive(&self, mut t: NaiveDateTime) -> Option<NaiveDateTime> {
+        // Every combination of fields repeats within a few years, so bail out
+        // instead of looping forever on impossible dates like "31 February".
+        let limit = t.year() + 5;
+
+        while t.year() <= limit {
+            if !self.months.contains(t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours.contains(t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes.contains(t.minute()) {
+                t = t.date().and_hms_opt(t.hour(), t.minute(), 0)? + Duration::minutes(1);
+                continue;
+            }
+            if !self.seconds.contains(t.second()) {
+                t += Duration::seconds(1);
+                continue;
+            }
+            return Some(t);
+        }
+
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month.contains(date.day());
+        let dow = self
+            .days_of_week
+            .contains(date.weekday().num_days_from_sunday());
+
+        // Classic cron semantics: when both day fields are restricted, either may match.
+        match (self.days_of_month.wildcard, self.days_of_week.wildcard) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(input: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(input).unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        let schedule: CronSchedule = expression.parse().unwrap();
+        schedule.next_after(&at(after)).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn steps_from_wildcard_and_start() {
+        assert_eq!(
+            next("0 0 */4 * * *", "2025-01-01T05:00:00+07:00"),
+            "2025-01-01T08:00:00+07:00"
+        );
+        assert_eq!(
+            next("5/15 * * * * *", "2025-01-01T00:00:06+00:00"),
+            "2025-01-01T00:00:20+00:00"
+        );
+        assert_eq!(
+            next("0 0-30/10 * * * *", "2025-01-01T00:25:00+00:00"),
+            "2025-01-01T00:30:00+00:00"
+        );
+        assert_eq!(
+            next("0 0-30/10 * * * *", "2025-01-01T00:30:00+00:00"),
+            "2025-01-01T01:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn ranges_and_lists() {
+        assert_eq!(
+            next("0 0 9-17 * * *", "2025-01-01T17:00:00+00:00"),
+            "2025-01-02T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 15,45 6,18 * * *", "2025-01-01T06:20:00+00:00"),
+            "2025-01-01T06:45:00+00:00"
+        );
+        assert_eq!(
+            next("0 15,45 6,18 * * *", "2025-01-01T06:45:00+00:00"),
+            "2025-01-01T18:15:00+00:00"
+        );
+    }
+
+    #[test]
+    fn zero_and_seven_are_sunday() {
+        // 2025-01-01 is a Wednesday; the next Sunday is the 5th.
+        for weekday in ["0", "7"] {
+            let expression = format!("0 0 7 * * {}", weekday);
+            assert_eq!(
+                next(&expression, "2025-01-01T00:00:00+07:00"),
+                "2025-01-05T07:00:00+07:00"
+            );
+        }
+        assert_eq!(
+            next("0 0 7 * * 6-7", "2025-01-04T08:00:00+07:00"),
+            "2025-01-05T07:00:00+07:00"
+        );
+    }
+
+    #[test]
+    fn either_day_field_matches_when_both_are_set() {
+        // The 15th or any Monday: Monday the 6th comes first.
+        assert_eq!(
+            next("0 0 0 15 * 1", "2025-01-01T00:00:00+00:00"),
+            "2025-01-06T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn rolls_over_months_and_years() {
+        assert_eq!(
+            next("0 0 0 1 * *", "2025-01-31T12:00:00+00:00"),
+            "2025-02-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 0 31 * *", "2025-01-31T12:00:00+00:00"),
+            "2025-03-31T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("59 59 23 31 12 *", "2025-12-31T23:59:59+00:00"),
+            "2026-12-31T23:59:59+00:00"
+        );
+        assert_eq!(
+            next("0 0 0 29 2 *", "2025-01-01T00:00:00+00:00"),
+            "2028-02-29T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn impossible_dates_have_no_next_tick() {
+        let schedule: CronSchedule = "0 0 0 31 2 *".parse().unwrap();
+        assert_eq!(schedule.next_after(&at("2025-01-01T00:00:00+00:00")), None);
+    }
+
+    #[test]
+    fn evaluates_in_the_given_offset() {
+        assert_eq!(
+            next("0 0 7 * * *", "2025-01-01T00:30:00+00:00"),
+            "2025-01-01T07:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 7 * * *", "2025-01-01T07:30:00+07:00"),
+            "2025-01-02T07:00:00+07:00"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expression in [
+            "",
+            "* * * * *",
+            "* * * * * * *",
+            "60 * * * * *",
+            "* * 24 * * *",
+            "* * * 0 * *",
+            "* * * * 13 *",
+            "* * * * * 8",
+            "*/0 * * * * *",
+            "*/x * * * * *",
+            "10-5 * * * * *",
+            "a * * * * *",
+            "1,,2 * * * * *",
+            "-1 * * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "'{}' should be rejected",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_the_expression_for_display() {
+        let schedule: CronSchedule = "0  0 */4 * * *".parse().unwrap();
+        assert_eq!(schedule.to_string(), "0 0 */4 * * *");
+    }
+}
@@ -1,14 +1,160 @@
-pub struct AqiConverter;
-
-impl AqiConverter {
-    pub fn estimate_pm25_from_aqi(aqi: i32) -> i32 {
-        match aqi {
-            0..=50 => (aqi as f64 * 12.0 / 50.0) as i32,
-            51..=100 => (12.0 + ((aqi - 50) as f64 * 23.4 / 50.0)) as i32,
-            101..=150 => (35.4 + ((aqi - 100) as f64 * 19.5 / 50.0)) as i32,
-            151..=200 => (55.4 + ((aqi - 150) as f64 * 94.6 / 50.0)) as i32,
-            201..=300 => (150.4 + ((aqi - 200) as f64 * 99.6 / 100.0)) as i32,
-            _ => (250.4 + ((aqi - 300) as f64 * 249.6 / 200.0)) as i32,
+use crate::domain::models::SourceSpread;
+
+pub struct GeoDistance;
+
+impl GeoDistance {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+
+    /// Great-circle distance between two `(lat, lon)` points in kilometres.
+    pub fn haversine_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+        let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+        let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        2.0 * Self::EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+pub struct Consensus {
+    pub aqi: i32,
+    /// Indices into the input of the readings that were not discarded as outliers.
+    pub kept: Vec<usize>,
+    pub spread: SourceSpread,
+}
+
+impl Consensus {
+    /// Combines AQI readings from several sources into their median.
+    ///
+    /// With three or more readings, any further than `margin` from the median is
+    /// an outlier and is dropped before the final median is taken. Two readings
+    /// can't outvote each other, so both are kept and flagged as unconfident if
+    /// they disagree.
+    pub fn combine(aqis: &[i32], margin: i32) -> Option<Self> {
+        let center = median(aqis.iter().map(|&aqi| aqi as f64))?;
+        let kept: Vec<usize> = if aqis.len() >= 3 {
+            (0..aqis.len())
+                .filter(|&i| (aqis[i] as f64 - center).abs() <= margin as f64)
+                .collect()
+        } else {
+            (0..aqis.len()).collect()
+        };
+        // Readings too scattered for any to sit near the median: keep them all.
+        let kept = if kept.is_empty() {
+            (0..aqis.len()).collect()
+        } else {
+            kept
+        };
+
+        let kept_aqis = || kept.iter().map(|&i| aqis[i]);
+        let kept_min = kept_aqis().min()?;
+        let kept_max = kept_aqis().max()?;
+
+        Some(Self {
+            aqi: median(kept_aqis().map(|aqi| aqi as f64))?.round() as i32,
+            spread: SourceSpread {
+                min: *aqis.iter().min()?,
+                max: *aqis.iter().max()?,
+                sources: aqis.len(),
+                confident: kept.len() >= 2 && kept_max - kept_min <= margin,
+            },
+            kept,
+        })
+    }
+}
+
+/// Median of the values, averaging the two middle ones for an even count.
+pub fn median(values: impl IntoIterator<Item = f64>) -> Option<f64> {
+    let mut values: Vec<f64> = values.into_iter().collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spread(min: i32, max: i32, sources: usize, confident: bool) -> SourceSpread {
+        SourceSpread {
+            min,
+            max,
+            sources,
+            confident,
         }
     }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median([3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median([4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(median([7.0]), Some(7.0));
+        assert_eq!(median([]), None);
+    }
+
+    #[test]
+    fn no_readings_give_no_consensus() {
+        assert!(Consensus::combine(&[], 15).is_none());
+    }
+
+    #[test]
+    fn a_single_reading_is_never_confident() {
+        let consensus = Consensus::combine(&[80], 15).unwrap();
+        assert_eq!(consensus.aqi, 80);
+        assert_eq!(consensus.kept, vec![0]);
+        assert_eq!(consensus.spread, spread(80, 80, 1, false));
+    }
+
+    #[test]
+    fn two_readings_are_confident_up_to_the_margin() {
+        let agree = Consensus::combine(&[80, 95], 15).unwrap();
+        assert_eq!(agree.aqi, 88);
+        assert_eq!(agree.kept, vec![0, 1]);
+        assert_eq!(agree.spread, spread(80, 95, 2, true));
+
+        // Neither can outvote the other, so both stay in but the spread is flagged.
+        let disagree = Consensus::combine(&[80, 96], 15).unwrap();
+        assert_eq!(disagree.aqi, 88);
+        assert_eq!(disagree.kept, vec![0, 1]);
+        assert_eq!(disagree.spread, spread(80, 96, 2, false));
+    }
+
+    #[test]
+    fn odd_count_drops_the_outlier() {
+        let consensus = Consensus::combine(&[50, 150, 55], 15).unwrap();
+        assert_eq!(consensus.aqi, 53);
+        assert_eq!(consensus.kept, vec![0, 2]);
+        // The spread still shows the discarded reading.
+        assert_eq!(consensus.spread, spread(50, 150, 3, true));
+    }
+
+    #[test]
+    fn even_count_drops_the_outlier() {
+        let consensus = Consensus::combine(&[40, 50, 60, 200], 20).unwrap();
+        assert_eq!(consensus.aqi, 50);
+        assert_eq!(consensus.kept, vec![0, 1, 2]);
+        assert_eq!(consensus.spread, spread(40, 200, 4, true));
+    }
+
+    #[test]
+    fn kept_readings_wider_than_the_margin_are_not_confident() {
+        // Both neighbours sit within 15 of the median but 20 apart.
+        let consensus = Consensus::combine(&[90, 100, 110], 15).unwrap();
+        assert_eq!(consensus.kept, vec![0, 1, 2]);
+        assert_eq!(consensus.spread, spread(90, 110, 3, false));
+    }
+
+    #[test]
+    fn scattered_readings_are_all_kept() {
+        let consensus = Consensus::combine(&[0, 100, 200, 300], 10).unwrap();
+        assert_eq!(consensus.aqi, 150);
+        assert_eq!(consensus.kept, vec![0, 1, 2, 3]);
+        assert_eq!(consensus.spread, spread(0, 300, 4, false));
+    }
 }
@@ -0,0 +1,586 @@
+use crate::domain::aqi::{self, bp, Breakpoint};
+use crate::domain::models::{AirQualityData, AirQualityLevel, Pollutant};
+
+/// Scale used to turn a reading into an index and a category.
+///
+/// The US EPA index is the one providers report. The others are computed from
+/// the measured concentrations (PM2.5 falls back to the estimate), applied to
+/// current readings rather than the averaging periods each standard prescribes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AqiStandard {
+    #[default]
+    UsEpa,
+    /// Thailand's Pollution Control Department AQI.
+    ThaiPcd,
+    /// European Common Air Quality Index (hourly grid).
+    EuropeanCaqi,
+    /// India's National Air Quality Index.
+    IndiaNaqi,
+}
+
+/// One band of a standard; its wording comes from the chat's catalogue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AqiCategory {
+    pub standard: AqiStandard,
+    /// Position among the standard's bands, best first.
+    pub rank: usize,
+    pub emoji: &'static str,
+    pub rgb: (u8, u8, u8),
+}
+
+/// Upper index of a band, with its emoji and chart colour.
+type Band = (i32, &'static str, (u8, u8, u8));
+
+/// A reading's index and category under one standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assessment {
+    pub standard: AqiStandard,
+    pub index: i32,
+    pub category: AqiCategory,
+}
+
+impl AqiStandard {
+    pub const ALL: [Self; 4] = [
+        Self::UsEpa,
+        Self::ThaiPcd,
+        Self::EuropeanCaqi,
+        Self::IndiaNaqi,
+    ];
+
+    /// Short ASCII name used in bot commands, config and storage.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::UsEpa => "us",
+            Self::ThaiPcd => "th",
+            Self::EuropeanCaqi => "eu",
+            Self::IndiaNaqi => "in",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|standard| standard.slug().eq_ignore_ascii_case(slug.trim()))
+    }
+
+    /// `None` when the reading has no usable index in this standard. A Thai AQI
+    /// the provider reported is used as is.
+    pub fn assess(&self, data: &AirQualityData) -> Option<Assessment> {
+        let index = match (self, data.aqi_th) {
+            (Self::UsEpa, _) => data.aqi,
+            (Self::ThaiPcd, Some(aqi_th)) => aqi_th,
+            _ => self.index_from_concentrations(data)?,
+        };
+        Some(Assessment {
+            standard: *self,
+            index,
+            category: self.category(index)?,
+        })
+    }
+
+    /// The highest sub-index over every pollutant the standard covers.
+    fn index_from_concentrations(&self, data: &AirQualityData) -> Option<i32> {
+        let pm25 = data
+            .pollutants
+            .pm25
+            .is_none()
+            .then_some((Pollutant::Pm25, data.pm25));
+
+        data.pollutants
+            .measured()
+            .chain(pm25)
+            .filter(|(_, ugm3)| ugm3.is_finite() && *ugm3 >= 0.0)
+            .filter_map(|(pollutant, ugm3)| self.sub_index(pollutant, ugm3))
+            .max()
+    }
+
+    fn sub_index(&self, pollutant: Pollutant, ugm3: f64) -> Option<i32> {
+        let (table, value) = match self {
+            Self::UsEpa => return None,
+            Self::ThaiPcd => (thai_table(pollutant), aqi::reported_value(pollutant, ugm3)),
+            Self::EuropeanCaqi => (caqi_table(pollutant), ugm3.max(0.0)),
+            Self::IndiaNaqi => (naqi_table(pollutant), naqi_value(pollutant, ugm3)),
+        };
+        Some(aqi::interpolate(table, value))
+    }
+
+    /// `None` for a negative index, which no standard defines.
+    pub fn category(&self, index: i32) -> Option<AqiCategory> {
+        let bands: &[Band] = match self {
+            Self::UsEpa => return AirQualityLevel::from_aqi(index).map(Into::into),
+            Self::ThaiPcd => THAI_CATEGORIES,
+            Self::EuropeanCaqi => CAQI_CATEGORIES,
+            Self::IndiaNaqi => NAQI_CATEGORIES,
+        };
+        if index < 0 {
+            return None;
+        }
+        let rank = bands
+            .iter()
+            .position(|(upper, _, _)| index <= *upper)
+            .unwrap_or(bands.len() - 1);
+        let (_, emoji, rgb) = bands[rank];
+        Some(AqiCategory {
+            standard: *self,
+            rank,
+            emoji,
+            rgb,
+        })
+    }
+}
+
+impl From<AirQualityLevel> for AqiCategory {
+    fn from(level: AirQualityLevel) -> Self {
+        Self {
+            standard: AqiStandard::UsEpa,
+            rank: level as usize,
+            emoji: level.emoji(),
+            rgb: level.rgb(),
+        }
+    }
+}
+
+// Thailand PCD (2023 PM2.5 revision): indices 0–25–50–100–200, with 201 and
+// above continuing the last row. Units as in the EPA tables.
+const THAI_PM25: &[Breakpoint] = &[
+    bp(0.0, 15.0, 0, 25),
+    bp(15.1, 25.0, 26, 50),
+    bp(25.1, 37.5, 51, 100),
+    bp(37.6, 75.0, 101, 200),
+];
+const THAI_PM10: &[Breakpoint] = &[
+    bp(0.0, 50.0, 0, 25),
+    bp(51.0, 80.0, 26, 50),
+    bp(81.0, 120.0, 51, 100),
+    bp(121.0, 180.0, 101, 200),
+];
+const THAI_O3: &[Breakpoint] = &[
+    bp(0.0, 35.0, 0, 25),
+    bp(36.0, 50.0, 26, 50),
+    bp(51.0, 70.0, 51, 100),
+    bp(71.0, 120.0, 101, 200),
+];
+const THAI_NO2: &[Breakpoint] = &[
+    bp(0.0, 60.0, 0, 25),
+    bp(61.0, 106.0, 26, 50),
+    bp(107.0, 170.0, 51, 100),
+    bp(171.0, 340.0, 101, 200),
+];
+const THAI_SO2: &[Breakpoint] = &[
+    bp(0.0, 100.0, 0, 25),
+    bp(101.0, 200.0, 26, 50),
+    bp(201.0, 300.0, 51, 100),
+    bp(301.0, 400.0, 101, 200),
+];
+const THAI_CO: &[Breakpoint] = &[
+    bp(0.0, 4.4, 0, 25),
+    bp(4.5, 6.4, 26, 50),
+    bp(6.5, 9.0, 51, 100),
+    bp(9.1, 30.0, 101, 200),
+];
+
+fn thai_table(pollutant: Pollutant) -> &'static [Breakpoint] {
+    match pollutant {
+        Pollutant::Pm25 => THAI_PM25,
+        Pollutant::Pm10 => THAI_PM10,
+        Pollutant::O3 => THAI_O3,
+        Pollutant::No2 => THAI_NO2,
+        Pollutant::So2 => THAI_SO2,
+        Pollutant::Co => THAI_CO,
+    }
+}
+
+const THAI_CATEGORIES: &[Band] = &[
+    (25, "🔵", (59, 204, 255)),
+    (50, "🟢", (146, 208, 80)),
+    (100, "🟡", (255, 255, 0)),
+    (200, "🟠", (255, 162, 0)),
+    (i32::MAX, "🔴", (240, 70, 70)),
+];
+
+// European CAQI, hourly grid in µg/m³: indices 0–25–50–75–100, above 100 is
+// "very high".
+const CAQI_PM25: &[Breakpoint] = &[
+    bp(0.0, 15.0, 0, 25),
+    bp(15.0, 30.0, 25, 50),
+    bp(30.0, 55.0, 50, 75),
+    bp(55.0, 110.0, 75, 100),
+];
+const CAQI_PM10: &[Breakpoint] = &[
+    bp(0.0, 25.0, 0, 25),
+    bp(25.0, 50.0, 25, 50),
+    bp(50.0, 90.0, 50, 75),
+    bp(90.0, 180.0, 75, 100),
+];
+const CAQI_O3: &[Breakpoint] = &[
+    bp(0.0, 60.0, 0, 25),
+    bp(60.0, 120.0, 25, 50),
+    bp(120.0, 180.0, 50, 75),
+    bp(180.0, 240.0, 75, 100),
+];
+const CAQI_NO2: &[Breakpoint] = &[
+    bp(0.0, 50.0, 0, 25),
+    bp(50.0, 100.0, 25, 50),
+    bp(100.0, 200.0, 50, 75),
+    bp(200.0, 400.0, 75, 100),
+];
+const CAQI_SO2: &[Breakpoint] = &[
+    bp(0.0, 50.0, 0, 25),
+    bp(50.0, 100.0, 25, 50),
+    bp(100.0, 350.0, 50, 75),
+    bp(350.0, 500.0, 75, 100),
+];
+const CAQI_CO: &[Breakpoint] = &[
+    bp(0.0, 5000.0, 0, 25),
+    bp(5000.0, 7500.0, 25, 50),
+    bp(7500.0, 10000.0, 50, 75),
+    bp(10000.0, 20000.0, 75, 100),
+];
+
+fn caqi_table(pollutant: Pollutant) -> &'static [Breakpoint] {
+    match pollutant {
+        Pollutant::Pm25 => CAQI_PM25,
+        Pollutant::Pm10 => CAQI_PM10,
+        Pollutant::O3 => CAQI_O3,
+        Pollutant::No2 => CAQI_NO2,
+        Pollutant::So2 => CAQI_SO2,
+        Pollutant::Co => CAQI_CO,
+    }
+}
+
+const CAQI_CATEGORIES: &[Band] = &[
+    (25, "🟢", (121, 188, 106)),
+    (50, "🟡", (187, 207, 76)),
+    (75, "🟠", (238, 194, 11)),
+    (100, "🔴", (242, 147, 5)),
+    (i32::MAX, "🟣", (232, 65, 111)),
+];
+
+// India NAQI in µg/m³ (CO in mg/m³): indices 0–50–100–200–300–400, with 401
+// and above continuing the last row.
+const NAQI_PM25: &[Breakpoint] = &[
+    bp(0.0, 30.0, 0, 50),
+    bp(31.0, 60.0, 51, 100),
+    bp(61.0, 90.0, 101, 200),
+    bp(91.0, 120.0, 201, 300),
+    bp(121.0, 250.0, 301, 400),
+];
+const NAQI_PM10: &[Breakpoint] = &[
+    bp(0.0, 50.0, 0, 50),
+    bp(51.0, 100.0, 51, 100),
+    bp(101.0, 250.0, 101, 200),
+    bp(251.0, 350.0, 201, 300),
+    bp(351.0, 430.0, 301, 400),
+];
+const NAQI_O3: &[Breakpoint] = &[
+    bp(0.0, 50.0, 0, 50),
+    bp(51.0, 100.0, 51, 100),
+    bp(101.0, 168.0, 101, 200),
+    bp(169.0, 208.0, 201, 300),
+    bp(209.0, 748.0, 301, 400),
+];
+const NAQI_NO2: &[Breakpoint] = &[
+    bp(0.0, 40.0, 0, 50),
+    bp(41.0, 80.0, 51, 100),
+    bp(81.0, 180.0, 101, 200),
+    bp(181.0, 280.0, 201, 300),
+    bp(281.0, 400.0, 301, 400),
+];
+const NAQI_SO2: &[Breakpoint] = &[
+    bp(0.0, 40.0, 0, 50),
+    bp(41.0, 80.0, 51, 100),
+    bp(81.0, 380.0, 101, 200),
+    bp(381.0, 800.0, 201, 300),
+    bp(801.0, 1600.0, 301, 400),
+];
+const NAQI_CO: &[Breakpoint] = &[
+    bp(0.0, 1.0, 0, 50),
+    bp(1.1, 2.0, 51, 100),
+    bp(2.1, 10.0, 101, 200),
+    bp(10.1, 17.0, 201, 300),
+    bp(17.1, 34.0, 301, 400),
+];
+
+fn naqi_table(pollutant: Pollutant) -> &'static [Breakpoint] {
+    match pollutant {
+        Pollutant::Pm25 => NAQI_PM25,
+        Pollutant::Pm10 => NAQI_PM10,
+        Pollutant::O3 => NAQI_O3,
+        Pollutant::No2 => NAQI_NO2,
+        Pollutant::So2 => NAQI_SO2,
+        Pollutant::Co => NAQI_CO,
+    }
+}
+
+/// NAQI tables are in whole µg/m³, except CO in mg/m³ to one decimal.
+fn naqi_value(pollutant: Pollutant, ugm3: f64) -> f64 {
+    let ugm3 = ugm3.max(0.0);
+    match pollutant {
+        Pollutant::Co => (ugm3 / 100.0).floor() / 10.0,
+        _ => ugm3.floor(),
+    }
+}
+
+// Chart colours are the official ones, dark to light green for the first two
+// bands; emoji have no second green, so they step down like the US scale.
+const NAQI_CATEGORIES: &[Band] = &[
+    (50, "🟢", (0, 176, 80)),
+    (100, "🟡", (146, 208, 80)),
+    (200, "🟠", (255, 255, 0)),
+    (300, "🔴", (255, 153, 0)),
+    (400, "🟣", (255, 0, 0)),
+    (i32::MAX, "🟤", (192, 0, 0)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Location;
+
+    fn reading(aqi: i32, pm25: f64) -> AirQualityData {
+        AirQualityData::sample(
+            Location::from_city("Ban Suan", "Chon Buri", "Thailand"),
+            aqi,
+            pm25,
+        )
+    }
+
+    #[test]
+    fn thai_standard_prefers_the_reported_thai_aqi() {
+        let mut data = reading(103, 36.4);
+        let computed = AqiStandard::ThaiPcd.assess(&data).unwrap().index;
+
+        data.aqi_th = Some(78);
+        assert_ne!(computed, 78);
+        assert_eq!(AqiStandard::ThaiPcd.assess(&data).unwrap().index, 78);
+        // Other standards still work from the concentrations.
+        assert_eq!(AqiStandard::UsEpa.assess(&data).unwrap().index, 103);
+        assert_eq!(
+            AqiStandard::IndiaNaqi.assess(&data),
+            AqiStandard::IndiaNaqi.assess(&reading(103, 36.4))
+        );
+    }
+
+    /// Concentration tables of each computed standard, by pollutant.
+    fn tables(standard: AqiStandard) -> Vec<(Pollutant, &'static [Breakpoint])> {
+        let table: fn(Pollutant) -> &'static [Breakpoint] = match standard {
+            AqiStandard::UsEpa => unreachable!("the US index comes from the provider"),
+            AqiStandard::ThaiPcd => thai_table,
+            AqiStandard::EuropeanCaqi => caqi_table,
+            AqiStandard::IndiaNaqi => naqi_table,
+        };
+        Pollutant::ALL.into_iter().map(|p| (p, table(p))).collect()
+    }
+
+    const COMPUTED: [AqiStandard; 3] = [
+        AqiStandard::ThaiPcd,
+        AqiStandard::EuropeanCaqi,
+        AqiStandard::IndiaNaqi,
+    ];
+
+    #[test]
+    fn every_table_edge_maps_to_its_index_edge() {
+        for standard in COMPUTED {
+            for (pollutant, table) in tables(standard) {
+                let mut previous_high = None;
+                for row in table {
+                    for (c, index) in row.edges() {
+                        assert_eq!(
+                            aqi::interpolate(table, c),
+                            index,
+                            "{:?} {:?} at {}",
+                            standard,
+                            pollutant,
+                            c
+                        );
+                    }
+                    // Rows start where the previous one ended, or one index on.
+                    let [(_, i_low), (_, i_high)] = row.edges();
+                    if let Some(high) = previous_high {
+                        assert!(
+                            i_low == high || i_low == high + 1,
+                            "{:?} {:?}",
+                            standard,
+                            pollutant
+                        );
+                    }
+                    previous_high = Some(i_high);
+                }
+            }
+        }
+    }
+
+    fn pm25_index(standard: AqiStandard, ugm3: f64) -> i32 {
+        standard.sub_index(Pollutant::Pm25, ugm3).unwrap()
+    }
+
+    #[test]
+    fn thai_pm25_band_edges() {
+        let cases = [
+            (0.0, 0),
+            (15.0, 25),
+            (15.1, 26),
+            (25.0, 50),
+            (25.1, 51),
+            (37.5, 100),
+            (37.6, 101),
+            (75.0, 200),
+            (100.0, 266),
+        ];
+        for (ugm3, index) in cases {
+            assert_eq!(
+                pm25_index(AqiStandard::ThaiPcd, ugm3),
+                index,
+                "{} µg/m³",
+                ugm3
+            );
+        }
+    }
+
+    #[test]
+    fn caqi_pm25_band_edges() {
+        let cases = [
+            (0.0, 0),
+            (15.0, 25),
+            (15.5, 26),
+            (30.0, 50),
+            (55.0, 75),
+            (110.0, 100),
+            (200.0, 141),
+        ];
+        for (ugm3, index) in cases {
+            assert_eq!(
+                pm25_index(AqiStandard::EuropeanCaqi, ugm3),
+                index,
+                "{} µg/m³",
+                ugm3
+            );
+        }
+    }
+
+    #[test]
+    fn naqi_pm25_band_edges() {
+        let cases = [
+            (0.0, 0),
+            (30.0, 50),
+            // Whole µg/m³ only, so 30.9 still reads as 30.
+            (30.9, 50),
+            (31.0, 51),
+            (60.0, 100),
+            (61.0, 101),
+            (90.0, 200),
+            (91.0, 201),
+            (120.0, 300),
+            (121.0, 301),
+            (250.0, 400),
+        ];
+        for (ugm3, index) in cases {
+            assert_eq!(
+                pm25_index(AqiStandard::IndiaNaqi, ugm3),
+                index,
+                "{} µg/m³",
+                ugm3
+            );
+        }
+    }
+
+    fn rank(standard: AqiStandard, index: i32) -> Option<usize> {
+        standard.category(index).map(|category| category.rank)
+    }
+
+    #[test]
+    fn category_band_edges() {
+        let cases: [(AqiStandard, &[(i32, usize)]); 4] = [
+            (
+                AqiStandard::UsEpa,
+                &[
+                    (0, 0),
+                    (50, 0),
+                    (51, 1),
+                    (100, 1),
+                    (101, 2),
+                    (150, 2),
+                    (151, 3),
+                    (200, 3),
+                    (201, 4),
+                    (300, 4),
+                    (301, 5),
+                    (500, 5),
+                ],
+            ),
+            (
+                AqiStandard::ThaiPcd,
+                &[
+                    (0, 0),
+                    (25, 0),
+                    (26, 1),
+                    (50, 1),
+                    (51, 2),
+                    (100, 2),
+                    (101, 3),
+                    (200, 3),
+                    (201, 4),
+                    (500, 4),
+                ],
+            ),
+            (
+                AqiStandard::EuropeanCaqi,
+                &[
+                    (0, 0),
+                    (25, 0),
+                    (26, 1),
+                    (50, 1),
+                    (51, 2),
+                    (75, 2),
+                    (76, 3),
+                    (100, 3),
+                    (101, 4),
+                    (500, 4),
+                ],
+            ),
+            (
+                AqiStandard::IndiaNaqi,
+                &[
+                    (0, 0),
+                    (50, 0),
+                    (51, 1),
+                    (100, 1),
+                    (101, 2),
+                    (200, 2),
+                    (201, 3),
+                    (300, 3),
+                    (301, 4),
+                    (400, 4),
+                    (401, 5),
+                    (500, 5),
+                ],
+            ),
+        ];
+        for (standard, edges) in cases {
+            for &(index, expected) in edges {
+                assert_eq!(
+                    rank(standard, index),
+                    Some(expected),
+                    "{:?} {}",
+                    standard,
+                    index
+                );
+            }
+            assert_eq!(rank(standard, -1), None, "{:?}", standard);
+        }
+    }
+
+    #[test]
+    fn each_band_has_its_own_emoji() {
+        for standard in AqiStandard::ALL {
+            let emoji: Vec<&str> = (0..=500)
+                .filter_map(|index| standard.category(index))
+                .map(|category| category.emoji)
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let bands = standard.category(500).unwrap().rank + 1;
+            assert_eq!(emoji.len(), bands, "{:?}: {:?}", standard, emoji);
+        }
+    }
+}
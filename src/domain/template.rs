@@ -0,0 +1,193 @@
+use std::fmt;
+
+/// Placeholders a message template may use, filled from the reading.
+pub const PLACEHOLDERS: [&str; 13] = [
+    "place",
+    "level_emoji",
+    "level",
+    "advice",
+    "aqi",
+    "index",
+    "index_label",
+    "standard",
+    "pm25",
+    "temperature",
+    "humidity",
+    "wind",
+    "source",
+];
+
+/// Longest template accepted, in characters; leaves room under Telegram's
+/// 4096-character limit for the filled-in values.
+pub const MAX_TEMPLATE_CHARS: usize = 1000;
+
+/// Why a template was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    Empty,
+    TooLong {
+        max: usize,
+    },
+    /// A `{` at this 1-based character position has no closing `}`.
+    Unclosed {
+        position: usize,
+    },
+    UnknownPlaceholder(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "template is empty"),
+            Self::TooLong { max } => write!(f, "template is longer than {} characters", max),
+            Self::Unclosed { position } => {
+                write!(f, "'{{' at character {} is never closed", position)
+            }
+            Self::UnknownPlaceholder(name) => write!(
+                f,
+                "unknown placeholder {{{}}}, use one of {}",
+                name,
+                placeholder_list()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Every placeholder in braces, e.g. "{place}, {level_emoji}, …".
+pub fn placeholder_list() -> String {
+    PLACEHOLDERS
+        .iter()
+        .map(|name| format!("{{{}}}", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Notification text written by a user, e.g. "{level_emoji} {place} AQI {aqi}".
+///
+/// Only built through [`MessageTemplate::parse`], so every `{...}` in it names
+/// a known placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTemplate {
+    source: String,
+}
+
+impl MessageTemplate {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let source = source.trim();
+        if source.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        if source.chars().count() > MAX_TEMPLATE_CHARS {
+            return Err(TemplateError::TooLong {
+                max: MAX_TEMPLATE_CHARS,
+            });
+        }
+
+        let mut rest = source;
+        // Characters before `rest`, for error positions.
+        let mut offset = 0;
+        while let Some(open) = rest.find('{') {
+            let after = &rest[open + 1..];
+            let Some(close) = after.find('}') else {
+                return Err(TemplateError::Unclosed {
+                    position: offset + rest[..open].chars().count() + 1,
+                });
+            };
+            let name = &after[..close];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(TemplateError::UnknownPlaceholder(name.to_string()));
+            }
+            offset += rest[..open + close + 2].chars().count();
+            rest = &after[close + 1..];
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A template naming every placeholder once.
+    fn every_placeholder() -> String {
+        PLACEHOLDERS
+            .iter()
+            .map(|name| format!("{}={{{}}}", name, name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn accepts_every_placeholder() {
+        let source = every_placeholder();
+        let template = MessageTemplate::parse(&format!("  {}\n", source)).unwrap();
+
+        assert_eq!(template.as_str(), source);
+    }
+
+    #[test]
+    fn rejects_an_empty_template() {
+        assert_eq!(MessageTemplate::parse(""), Err(TemplateError::Empty));
+        assert_eq!(MessageTemplate::parse(" \n\t"), Err(TemplateError::Empty));
+    }
+
+    #[test]
+    fn rejects_a_template_over_the_limit() {
+        // Counted in characters, not bytes.
+        let longest = "ฝ".repeat(MAX_TEMPLATE_CHARS);
+        assert!(MessageTemplate::parse(&longest).is_ok());
+
+        assert_eq!(
+            MessageTemplate::parse(&format!("{}!", longest)),
+            Err(TemplateError::TooLong {
+                max: MAX_TEMPLATE_CHARS
+            })
+        );
+    }
+
+    #[test]
+    fn reports_where_an_unclosed_brace_is() {
+        assert_eq!(
+            MessageTemplate::parse("AQI {aqi} in {place"),
+            Err(TemplateError::Unclosed { position: 14 })
+        );
+        assert_eq!(
+            MessageTemplate::parse("ฝุ่น {pm25} {"),
+            Err(TemplateError::Unclosed { position: 13 })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert_eq!(
+            MessageTemplate::parse("{place}: {city}"),
+            Err(TemplateError::UnknownPlaceholder("city".to_string()))
+        );
+        assert_eq!(
+            MessageTemplate::parse("{}"),
+            Err(TemplateError::UnknownPlaceholder(String::new()))
+        );
+        // Names are case-sensitive.
+        assert_eq!(
+            MessageTemplate::parse("{AQI}"),
+            Err(TemplateError::UnknownPlaceholder("AQI".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_message_lists_the_known_ones() {
+        let message = TemplateError::UnknownPlaceholder("city".to_string()).to_string();
+
+        assert!(message.starts_with("unknown placeholder {city}, use one of {place}, "));
+        assert!(message.ends_with("{source}"), "{}", message);
+    }
+}
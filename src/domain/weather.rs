@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+/// Weather at the time of a reading; providers fill in what they report.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    #[serde(default)]
+    pub temperature: Option<i32>,
+    #[serde(default)]
+    pub humidity: Option<i32>,
+    #[serde(default)]
+    pub wind_speed_kmh: Option<f64>,
+    /// Direction the wind blows from, in degrees clockwise from north.
+    #[serde(default)]
+    pub wind_direction_deg: Option<f64>,
+    #[serde(default)]
+    pub pressure_hpa: Option<f64>,
+    #[serde(default)]
+    pub condition: Option<WeatherCondition>,
+}
+
+impl Weather {
+    /// Eight-point compass direction the wind comes from, e.g. "NE".
+    pub fn wind_compass(&self) -> Option<&'static str> {
+        const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        let degrees = self.wind_direction_deg?.rem_euclid(360.0);
+        Some(POINTS[((degrees + 22.5) / 45.0) as usize % 8])
+    }
+
+    /// Wind as "NE 12 km/h", or just the speed when the direction is unknown.
+    pub fn wind_description(&self) -> Option<String> {
+        let speed = self.wind_speed_kmh?;
+        Some(match self.wind_compass() {
+            Some(compass) => format!("{} {:.0} km/h", compass, speed),
+            None => format!("{:.0} km/h", speed),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeatherCondition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Thunderstorm,
+    Snow,
+}
+
+impl WeatherCondition {
+    /// Maps OpenWeatherMap-style icon codes, as used by IQAir ("01d", "10n", ...).
+    pub fn from_icon_code(code: &str) -> Option<Self> {
+        match code.get(..2)? {
+            "01" => Some(Self::Clear),
+            "02" | "03" => Some(Self::PartlyCloudy),
+            "04" => Some(Self::Cloudy),
+            "09" => Some(Self::Drizzle),
+            "10" => Some(Self::Rain),
+            "11" => Some(Self::Thunderstorm),
+            "13" => Some(Self::Snow),
+            "50" => Some(Self::Fog),
+            _ => None,
+        }
+    }
+
+    /// Maps WMO weather interpretation codes, as used by Open-Meteo.
+    pub fn from_wmo_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(Self::Clear),
+            1 | 2 => Some(Self::PartlyCloudy),
+            3 => Some(Self::Cloudy),
+            45 | 48 => Some(Self::Fog),
+            51..=57 => Some(Self::Drizzle),
+            61..=67 | 80..=82 => Some(Self::Rain),
+            71..=77 | 85 | 86 => Some(Self::Snow),
+            95..=99 => Some(Self::Thunderstorm),
+            _ => None,
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Clear => "☀️",
+            Self::PartlyCloudy => "⛅",
+            Self::Cloudy => "☁️",
+            Self::Fog => "🌫️",
+            Self::Drizzle => "🌦️",
+            Self::Rain => "🌧️",
+            Self::Thunderstorm => "⛈️",
+            Self::Snow => "❄️",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wind(direction: Option<f64>, speed: Option<f64>) -> Weather {
+        Weather {
+            wind_direction_deg: direction,
+            wind_speed_kmh: speed,
+            ..Weather::default()
+        }
+    }
+
+    #[test]
+    fn wind_compass_sectors() {
+        let cases = [
+            (0.0, "N"),
+            (22.4, "N"),
+            (22.5, "NE"),
+            (45.0, "NE"),
+            (90.0, "E"),
+            (135.0, "SE"),
+            (180.0, "S"),
+            (225.0, "SW"),
+            (270.0, "W"),
+            (315.0, "NW"),
+            (337.4, "NW"),
+            (337.5, "N"),
+            (359.0, "N"),
+            // Outside 0–360 wraps round.
+            (360.0, "N"),
+            (405.0, "NE"),
+            (-45.0, "NW"),
+            (-1.0, "N"),
+        ];
+        for (degrees, compass) in cases {
+            assert_eq!(
+                wind(Some(degrees), None).wind_compass(),
+                Some(compass),
+                "{}°",
+                degrees
+            );
+        }
+        assert_eq!(wind(None, Some(10.0)).wind_compass(), None);
+    }
+
+    #[test]
+    fn wind_description() {
+        assert_eq!(
+            wind(Some(40.0), Some(14.8)).wind_description().as_deref(),
+            Some("NE 15 km/h")
+        );
+        assert_eq!(
+            wind(None, Some(3.2)).wind_description().as_deref(),
+            Some("3 km/h")
+        );
+        assert_eq!(wind(Some(40.0), None).wind_description(), None);
+    }
+
+    #[test]
+    fn icon_codes() {
+        use WeatherCondition::*;
+        let cases = [
+            ("01d", Some(Clear)),
+            ("01n", Some(Clear)),
+            ("02d", Some(PartlyCloudy)),
+            ("03n", Some(PartlyCloudy)),
+            ("04d", Some(Cloudy)),
+            ("09d", Some(Drizzle)),
+            ("10n", Some(Rain)),
+            ("11d", Some(Thunderstorm)),
+            ("13d", Some(Snow)),
+            ("50n", Some(Fog)),
+            ("05d", None),
+            ("1", None),
+            ("", None),
+        ];
+        for (code, condition) in cases {
+            assert_eq!(
+                WeatherCondition::from_icon_code(code),
+                condition,
+                "{:?}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn wmo_code_groups() {
+        use WeatherCondition::*;
+        let groups: [(&[i32], Option<WeatherCondition>); 9] = [
+            (&[0], Some(Clear)),
+            (&[1, 2], Some(PartlyCloudy)),
+            (&[3], Some(Cloudy)),
+            (&[45, 48], Some(Fog)),
+            (&[51, 53, 55, 56, 57], Some(Drizzle)),
+            (&[61, 63, 65, 66, 67, 80, 81, 82], Some(Rain)),
+            (&[71, 73, 75, 77, 85, 86], Some(Snow)),
+            (&[95, 96, 99], Some(Thunderstorm)),
+            (&[-1, 4, 44, 50, 58, 68, 70, 78, 83, 87, 94, 100], None),
+        ];
+        for (codes, condition) in groups {
+            for &code in codes {
+                assert_eq!(WeatherCondition::from_wmo_code(code), condition, "{}", code);
+            }
+        }
+    }
+}
@@ -1,24 +1,59 @@
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::info;
 
 mod adapters;
 mod domain;
+mod presentation;
 mod use_cases;
 
+use adapters::air4thai::Air4ThaiClient;
 use adapters::bot::BotHandler;
-use adapters::config::Config;
+use adapters::cache::CachingRepository;
+use adapters::clock::SystemClock;
+use adapters::config::{Config, ProviderKind, ProviderStrategy};
+use adapters::consensus::ConsensusRepository;
+use adapters::failover::FailoverRepository;
+use adapters::http::{HttpClient, HttpPolicy};
 use adapters::iqair::IQAirClient;
-use use_cases::CheckAirQuality;
+use adapters::memory::InMemoryStore;
+use adapters::open_meteo::OpenMeteoClient;
+use adapters::quota::CallBudget;
+use adapters::sqlite::SqliteStore;
+use adapters::telegram::TelegramClient;
+use domain::alerts::AlertPolicy;
+use domain::schedule::CronSchedule;
+use use_cases::check_air_quality::AirQualityRepository;
+use use_cases::forecast_air_quality::ForecastRepository;
+use use_cases::storage::Storage;
+use use_cases::{
+    AirQualityHistory, AlertAirQuality, BroadcastAirQuality, ChatSettings, CheckAirQuality,
+    DailyDigest, ForecastAirQuality, ManageSubscriptions, NotifyAirQuality, RecommendOutdoorTime,
+    Scheduler,
+};
 
-struct AirQualityService;
+type ChannelDigest = DailyDigest<Arc<dyn AirQualityRepository>, TelegramClient, Arc<dyn Storage>>;
+
+struct AirQualityService {
+    scheduler: Scheduler<SystemClock>,
+    schedule: CronSchedule,
+    broadcast: BroadcastAirQuality<Arc<dyn AirQualityRepository>, TelegramClient, Arc<dyn Storage>>,
+    digest: Option<(CronSchedule, ChannelDigest)>,
+}
 
 #[shuttle_runtime::async_trait]
 impl shuttle_runtime::Service for AirQualityService {
     async fn bind(self, _addr: std::net::SocketAddr) -> Result<(), shuttle_runtime::Error> {
         info!("Worker started successfully");
 
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-        }
+        let digest = async {
+            if let Some((schedule, digest)) = &self.digest {
+                self.scheduler.run(schedule, digest).await;
+            }
+        };
+        tokio::join!(self.scheduler.run(&self.schedule, &self.broadcast), digest);
+
+        Ok(())
     }
 }
 
@@ -34,17 +69,139 @@ async fn main(
         info!("  - {}", loc.name);
     }
 
-    let iqair_client = IQAirClient::new(config.iqair_token.clone());
-    let check_air_quality = CheckAirQuality::new(iqair_client);
+    let storage: Arc<dyn Storage> = match &config.database_path {
+        Some(path) => {
+            info!("Using database {}", path);
+            Arc::new(SqliteStore::open(path).map_err(shuttle_runtime::Error::Custom)?)
+        }
+        None => {
+            info!("DATABASE_PATH not set, state will be lost on restart");
+            Arc::new(InMemoryStore::new())
+        }
+    };
 
+    let http = HttpClient::new(HttpPolicy {
+        timeout: Duration::from_secs(config.http_timeout_secs),
+        max_retries: config.http_max_retries,
+        ..HttpPolicy::default()
+    });
+    let provider: Arc<dyn AirQualityRepository> = Arc::new(CachingRepository::new(
+        build_provider(&config, &http, &storage),
+        chrono::Duration::seconds(config.cache_ttl_secs),
+        chrono::Duration::seconds(config.cache_max_stale_secs),
+    ));
+    let check_air_quality = CheckAirQuality::new(provider)
+        .with_history(storage.clone())
+        .with_aqi_revision(config.aqi_revision);
+    // Only Open-Meteo publishes forecasts, and it needs no key.
+    let forecasts = ForecastAirQuality::new(
+        Arc::new(OpenMeteoClient::new(http.clone())) as Arc<dyn ForecastRepository>
+    );
+
+    let telegram_client =
+        TelegramClient::new(config.telegram_token.clone(), http, config.message_format);
+    let settings = ChatSettings::new(storage.clone(), config.aqi_standard, config.locale)
+        .with_default_template(config.message_template.clone());
+    let broadcast = BroadcastAirQuality::new(
+        check_air_quality.clone(),
+        AlertAirQuality::new(
+            NotifyAirQuality::new(telegram_client.clone()),
+            AlertPolicy::new(config.alert_hysteresis),
+            storage.clone(),
+        ),
+        ManageSubscriptions::new(storage.clone()),
+        settings.clone(),
+        config.locations.clone(),
+        config.telegram_channel.clone(),
+    );
+    let digest = config.digest_schedule.clone().map(|schedule| {
+        let digest = DailyDigest::new(
+            check_air_quality.clone(),
+            AirQualityHistory::new(storage.clone()),
+            settings,
+            telegram_client,
+            config.locations.clone(),
+            config.telegram_channel.clone(),
+            config.timezone,
+        );
+        let digest = if config.digest_plan {
+            digest.with_forecasts(forecasts.clone())
+        } else {
+            digest
+        };
+        (schedule, digest)
+    });
+
+    let recommender =
+        RecommendOutdoorTime::new(forecasts.clone(), storage.clone(), config.timezone);
     let bot_handler = BotHandler::new(
         config.telegram_token.clone(),
         check_air_quality,
+        storage,
         config.locations.clone(),
-    );
+        config.timezone,
+        config.aqi_standard,
+        config.locale,
+    )
+    .with_default_template(config.message_template.clone())
+    .with_format(config.message_format)
+    .with_forecasts(forecasts)
+    .with_recommender(recommender);
     tokio::spawn(async move {
         bot_handler.run().await;
     });
 
-    Ok(AirQualityService)
+    Ok(AirQualityService {
+        scheduler: Scheduler::new(SystemClock, config.timezone),
+        schedule: config.cron_schedule,
+        broadcast,
+        digest,
+    })
+}
+
+fn build_provider(
+    config: &Config,
+    http: &HttpClient,
+    storage: &Arc<dyn Storage>,
+) -> Arc<dyn AirQualityRepository> {
+    info!(
+        "Using air quality providers {:?} ({:?})",
+        config.providers, config.provider_strategy
+    );
+    let mut providers: Vec<(String, Arc<dyn AirQualityRepository>)> = config
+        .providers
+        .iter()
+        .map(|kind| {
+            let repository: Arc<dyn AirQualityRepository> = match kind {
+                ProviderKind::IQAir => Arc::new(
+                    IQAirClient::new(config.iqair_token.clone().unwrap_or_default(), http.clone())
+                        .with_budget(
+                            CallBudget::new(
+                                config.iqair_calls_per_minute,
+                                config.iqair_monthly_quota,
+                            )
+                            .with_usage("iqair", storage.clone()),
+                        ),
+                ),
+                ProviderKind::OpenMeteo => Arc::new(OpenMeteoClient::new(http.clone())),
+                ProviderKind::Air4Thai => Arc::new(
+                    Air4ThaiClient::new(http.clone()).with_aqi_revision(config.aqi_revision),
+                ),
+            };
+            (format!("{:?}", kind), repository)
+        })
+        .collect();
+
+    if providers.len() == 1 {
+        return providers.remove(0).1;
+    }
+    match config.provider_strategy {
+        ProviderStrategy::Failover => Arc::new(FailoverRepository::new(
+            providers,
+            Duration::from_secs(config.provider_cool_down_secs),
+        )),
+        ProviderStrategy::Consensus => {
+            Arc::new(ConsensusRepository::new(providers, config.consensus_margin))
+        }
+    }
 }
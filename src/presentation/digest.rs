@@ -0,0 +1,137 @@
+use crate::domain::digest::Digest;
+use crate::domain::i18n::{fill, Catalogue};
+use crate::presentation::markup::{fill_template, MessageRenderer};
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// The morning digest: one line per location, worst first, with the best time
+/// to go out when it was planned, then advice for the worst one, all in the
+/// digest's standard.
+pub fn render_digest(
+    renderer: &dyn MessageRenderer,
+    digest: &Digest,
+    text: &Catalogue,
+    timezone: FixedOffset,
+) -> String {
+    let r = renderer;
+    let standard = digest.standard;
+    let date = digest.date.format("%d/%m/%Y");
+    let mut lines = vec![
+        r.bold(&fill(text.digest_header, &[("date", &date)])),
+        String::new(),
+    ];
+
+    for entry in &digest.entries {
+        let data = &entry.data;
+        let Some(assessment) = standard.assess(data) else {
+            lines.push(fill_template(
+                r,
+                text.digest_unavailable,
+                &[("place", &r.escape(&entry.location.name))],
+            ));
+            continue;
+        };
+        let peak = entry
+            .yesterday_peak
+            .map(|aqi| fill_template(r, text.digest_peak, &[("aqi", &aqi)]))
+            .unwrap_or_default();
+        lines.push(format!(
+            "{} {} · {}{}",
+            assessment.category.emoji,
+            r.bold(&entry.location.name),
+            r.escape(&format!(
+                "{} {} · {}",
+                text.index_label(standard),
+                assessment.index,
+                text.category(&assessment.category).name
+            )),
+            peak
+        ));
+        if let Some(window) = entry.best_window {
+            let time = |at: DateTime<Utc>| {
+                r.bold(&at.with_timezone(&timezone).format("%H:%M").to_string())
+            };
+            lines.push(fill_template(
+                r,
+                text.digest_plan,
+                &[("start", &time(window.start)), ("end", &time(window.end))],
+            ));
+        }
+    }
+    for location in &digest.unavailable {
+        lines.push(fill_template(
+            r,
+            text.digest_unavailable,
+            &[("place", &r.escape(&location.name))],
+        ));
+    }
+
+    if let Some(category) = digest
+        .worst()
+        .and_then(|worst| standard.assess(&worst.data))
+        .map(|assessment| assessment.category)
+    {
+        lines.push(String::new());
+        lines.push(fill_template(
+            r,
+            text.digest_advice,
+            &[("advice", &r.escape(text.category(&category).advice))],
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::digest::DigestEntry;
+    use crate::domain::i18n::Locale;
+    use crate::domain::models::{AirQualityData, Location};
+    use crate::domain::standards::AqiStandard;
+    use crate::presentation::MessageFormat;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn names_configured_locations_and_advises_on_the_worst_shown() {
+        let entry = |name: &str, provider_name: &str, aqi: i32, pm25: f64| {
+            let location = Location::from_city(name, "Chon Buri", "Thailand");
+            let reported = Location::from_city(provider_name, "Chon Buri", "Thailand");
+            DigestEntry {
+                location,
+                data: AirQualityData::sample(reported, aqi, pm25),
+                yesterday_peak: None,
+                best_window: None,
+            }
+        };
+        let digest = Digest::new(
+            NaiveDate::from_ymd_opt(2025, 1, 10).unwrap(),
+            AqiStandard::ThaiPcd,
+            vec![
+                entry("Ban Suan", "Chon Buri", 150, 10.0),
+                entry("Si Racha", "Sriracha", 60, 80.0),
+            ],
+            Vec::new(),
+        );
+        let text = Locale::English.catalogue();
+        let message = render_digest(
+            MessageFormat::PlainText.renderer(),
+            &digest,
+            text,
+            FixedOffset::east_opt(7 * 3600).unwrap(),
+        );
+
+        let lines: Vec<&str> = message.lines().collect();
+        assert!(lines[2].contains("Si Racha"), "{}", message);
+        assert!(lines[3].contains("Ban Suan"), "{}", message);
+        assert!(!message.contains("Sriracha"), "{}", message);
+
+        let worst = AqiStandard::ThaiPcd
+            .assess(&digest.entries[0].data)
+            .unwrap()
+            .category;
+        assert_eq!(
+            lines.last().unwrap(),
+            &format!("💡 {}", text.category(&worst).advice)
+        );
+    }
+}
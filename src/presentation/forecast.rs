@@ -0,0 +1,112 @@
+use crate::domain::forecast::Forecast;
+use crate::domain::i18n::{fill, Catalogue};
+use crate::domain::standards::AqiStandard;
+use crate::presentation::markup::{fill_template, MessageRenderer};
+use chrono::FixedOffset;
+
+/// Hours between the rows of a forecast; every hour would be too long to read.
+const ROW_STEP: usize = 3;
+
+/// A forecast as rows every few hours grouped by local day, then the best
+/// window to go outside.
+pub fn render_forecast(
+    renderer: &dyn MessageRenderer,
+    forecast: &Forecast,
+    standard: AqiStandard,
+    text: &Catalogue,
+    timezone: FixedOffset,
+) -> String {
+    let r = renderer;
+    let header = fill(
+        text.forecast_header,
+        &[
+            ("place", &forecast.location.name),
+            ("hours", &forecast.hours.len()),
+        ],
+    );
+    let mut lines = vec![r.bold(&header)];
+
+    let mut day = None;
+    for (at, data) in forecast.hours.iter().step_by(ROW_STEP) {
+        let Some(assessment) = standard.assess(data) else {
+            continue;
+        };
+        let local = at.with_timezone(&timezone);
+        if day != Some(local.date_naive()) {
+            day = Some(local.date_naive());
+            lines.push(String::new());
+            lines.push(r.bold(&local.format("%d/%m").to_string()));
+        }
+        lines.push(format!(
+            "{} {} {}",
+            r.escape(&local.format("%H:%M").to_string()),
+            assessment.category.emoji,
+            r.escape(&format!(
+                "{} {} · PM2.5 {:.0}",
+                text.index_label(standard),
+                assessment.index,
+                data.pm25
+            )),
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(match forecast.best_window(timezone) {
+        Some(window) => {
+            // The forecast spans days, so the start carries its date.
+            let start = window.start.with_timezone(&timezone).format("%d/%m %H:%M");
+            let end = window.end.with_timezone(&timezone).format("%H:%M");
+            fill_template(
+                r,
+                text.forecast_best,
+                &[
+                    ("start", &r.bold(&start.to_string())),
+                    ("end", &r.bold(&end.to_string())),
+                    ("aqi", &window.average_aqi),
+                ],
+            )
+        }
+        None => r.escape(text.forecast_no_window),
+    });
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::i18n::Locale;
+    use crate::domain::models::{AirQualityData, Location};
+    use crate::presentation::MessageFormat;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn best_window_starts_with_its_date() {
+        let location = Location::from_city("Ban Suan", "Chon Buri", "Thailand");
+        // 16:00 local on the 10th, running into the next day.
+        let start = Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap();
+        let hours = (0..24)
+            .map(|offset| {
+                let aqi = if offset == 18 { 20 } else { 90 };
+                (
+                    start + Duration::hours(offset),
+                    AirQualityData::sample(location.clone(), aqi, 10.0),
+                )
+            })
+            .collect();
+        let forecast = Forecast { location, hours };
+
+        let message = render_forecast(
+            MessageFormat::PlainText.renderer(),
+            &forecast,
+            AqiStandard::UsEpa,
+            Locale::English.catalogue(),
+            FixedOffset::east_opt(7 * 3600).unwrap(),
+        );
+        let best = message.lines().last().unwrap();
+        assert_eq!(
+            best,
+            "🌿 Best time to go out 11/01 09:00–11:00, AQI around 55"
+        );
+    }
+}
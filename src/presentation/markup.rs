@@ -0,0 +1,124 @@
+use crate::domain::i18n;
+use std::fmt;
+
+/// Markup a message is sent in, chosen once for the bot and the channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageFormat {
+    #[default]
+    Html,
+    MarkdownV2,
+    PlainText,
+}
+
+impl MessageFormat {
+    pub const ALL: [Self; 3] = [Self::Html, Self::MarkdownV2, Self::PlainText];
+
+    /// Short ASCII name used in config.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::MarkdownV2 => "markdown",
+            Self::PlainText => "plain",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.slug().eq_ignore_ascii_case(slug.trim()))
+    }
+
+    pub fn renderer(&self) -> &'static dyn MessageRenderer {
+        match self {
+            Self::Html => &Html,
+            Self::MarkdownV2 => &MarkdownV2,
+            Self::PlainText => &PlainText,
+        }
+    }
+}
+
+/// Turns plain text into one markup. `bold` and `italic` escape their input,
+/// so callers only ever pass plain text.
+pub trait MessageRenderer: Send + Sync {
+    fn escape(&self, text: &str) -> String;
+    fn bold(&self, text: &str) -> String;
+    fn italic(&self, text: &str) -> String;
+}
+
+/// Fills a catalogue template, escaping its text for `renderer`. Arguments go
+/// in as they are, so they must already be escaped or rendered.
+pub fn fill_template(
+    renderer: &dyn MessageRenderer,
+    template: &str,
+    args: &[(&str, &dyn fmt::Display)],
+) -> String {
+    i18n::fill_escaped(template, args, |text| renderer.escape(text))
+}
+
+/// Telegram's HTML style.
+#[derive(Debug, Clone, Copy)]
+pub struct Html;
+
+impl MessageRenderer for Html {
+    fn escape(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn bold(&self, text: &str) -> String {
+        format!("<b>{}</b>", self.escape(text))
+    }
+
+    fn italic(&self, text: &str) -> String {
+        format!("<i>{}</i>", self.escape(text))
+    }
+}
+
+/// Telegram's MarkdownV2 style.
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownV2;
+
+impl MarkdownV2 {
+    /// Characters MarkdownV2 reserves anywhere in the text.
+    const RESERVED: &'static str = "\\_*[]()~`>#+-=|{}.!";
+}
+
+impl MessageRenderer for MarkdownV2 {
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if Self::RESERVED.contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    fn bold(&self, text: &str) -> String {
+        format!("*{}*", self.escape(text))
+    }
+
+    fn italic(&self, text: &str) -> String {
+        format!("_{}_", self.escape(text))
+    }
+}
+
+/// No markup at all, e.g. for logs or clients without formatting.
+#[derive(Debug, Clone, Copy)]
+pub struct PlainText;
+
+impl MessageRenderer for PlainText {
+    fn escape(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn bold(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn italic(&self, text: &str) -> String {
+        text.to_string()
+    }
+}
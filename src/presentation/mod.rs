@@ -0,0 +1,11 @@
+pub mod digest;
+pub mod forecast;
+pub mod markup;
+pub mod reading;
+pub mod recommendation;
+
+pub use digest::render_digest;
+pub use forecast::render_forecast;
+pub use markup::{fill_template, MessageFormat};
+pub use reading::render_reading;
+pub use recommendation::render_recommendation;
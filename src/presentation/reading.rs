@@ -0,0 +1,343 @@
+use crate::domain::i18n::{fill, Catalogue};
+use crate::domain::models::{AirQualityData, Pollutant};
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use crate::presentation::markup::{fill_template, MessageRenderer};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// A reading as both `/check` and the broadcaster send it, in the chat's
+/// standard and language, laid out by `template` or the built-in layout when
+/// there is none. `None` when the standard has no index for it.
+pub fn render_reading(
+    renderer: &dyn MessageRenderer,
+    template: Option<&MessageTemplate>,
+    data: &AirQualityData,
+    standard: AqiStandard,
+    text: &Catalogue,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let assessment = standard.assess(data)?;
+    let category = assessment.category;
+    let category_text = text.category(&category);
+    let r = renderer;
+
+    let (city, state) = data.location.city_state();
+    let location_str = if state.is_empty() {
+        city
+    } else {
+        format!("{}, {}", city, state)
+    };
+    let stale_line = data
+        .stale_minutes(now)
+        .map(|minutes| format!("\n{}", r.italic(&text.stale_note(minutes))))
+        .unwrap_or_default();
+
+    if let Some(template) = template {
+        // One value for each of `template::PLACEHOLDERS`.
+        let values = [
+            ("place", r.escape(&location_str)),
+            ("level_emoji", category.emoji.to_string()),
+            ("level", r.escape(category_text.name)),
+            ("advice", r.escape(category_text.advice)),
+            ("aqi", data.aqi.to_string()),
+            ("index", assessment.index.to_string()),
+            ("index_label", r.escape(text.index_label(standard))),
+            ("standard", r.escape(text.standard_name(standard))),
+            ("pm25", r.escape(&format!("{:.1}", data.pm25))),
+            ("temperature", optional(data.weather.temperature, r)),
+            ("humidity", optional(data.weather.humidity, r)),
+            ("wind", optional(data.weather.wind_description(), r)),
+            ("source", r.escape(&data.source)),
+        ];
+        let args: Vec<(&str, &dyn fmt::Display)> = values
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn fmt::Display))
+            .collect();
+        return Some(format!(
+            "{}{}",
+            fill_template(r, template.as_str(), &args),
+            stale_line
+        ));
+    }
+
+    let index_text = match standard {
+        AqiStandard::UsEpa => format!("AQI {}", r.bold(&data.aqi.to_string())),
+        _ => format!(
+            "{} {} {}",
+            r.escape(text.index_label(standard)),
+            r.bold(&assessment.index.to_string()),
+            r.escape(&format!("(US AQI {})", data.aqi))
+        ),
+    };
+    let pm25_text = r.escape(&format!(
+        "PM2.5 {:.1} µg/m³{}",
+        data.pm25,
+        if data.pm25_estimated {
+            text.estimated
+        } else {
+            ""
+        }
+    ));
+
+    let index_details: Vec<String> = [
+        data.aqi_cn
+            .map(|aqi| fill(text.china_aqi, &[("aqi", &aqi)])),
+        data.main_pollutant
+            .map(|p| fill(text.main_pollutant, &[("pollutant", &p.label())])),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    // PM2.5 is already on the index line.
+    let others: Vec<String> = data
+        .pollutants
+        .measured()
+        .filter(|(p, _)| *p != Pollutant::Pm25)
+        .map(|(p, value)| format!("{} {:.0}", p.label(), value))
+        .collect();
+    let spread = data.spread.map(|spread| {
+        format!(
+            "{}{}",
+            fill(
+                text.spread,
+                &[
+                    ("sources", &spread.sources),
+                    ("min", &spread.min),
+                    ("max", &spread.max),
+                ],
+            ),
+            if spread.confident {
+                ""
+            } else {
+                text.spread_inconsistent
+            }
+        )
+    });
+    let weather: Vec<String> = [
+        data.weather.temperature.map(|t| format!("🌡️ {}°C", t)),
+        data.weather.humidity.map(|h| format!("💧 {}%", h)),
+        data.weather.wind_description().map(|w| format!("💨 {}", w)),
+        data.weather
+            .pressure_hpa
+            .map(|p| format!("⏲️ {:.0} hPa", p)),
+        data.weather
+            .condition
+            .map(|c| format!("{} {}", c.emoji(), text.condition(c))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let details: Vec<String> = [
+        (!index_details.is_empty()).then(|| index_details.join(" · ")),
+        (!others.is_empty()).then(|| format!("🧪 {} µg/m³", others.join(" · "))),
+        spread,
+        (!weather.is_empty()).then(|| weather.join(" · ")),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|line| format!("{}\n", r.escape(&line)))
+    .collect();
+
+    Some(format!(
+        "{} {}\n\n\
+        {}\n\
+        {} · {}\n\
+        {}\n\
+        {}\n\
+        {}{}",
+        category.emoji,
+        r.bold(category_text.name),
+        r.escape(&format!("📍 {}", location_str)),
+        index_text,
+        pm25_text,
+        details.concat(),
+        r.escape(category_text.advice),
+        r.italic(&format!("{}: {}", text.source, data.source)),
+        stale_line,
+    ))
+}
+
+/// A value the provider may not report; missing ones fill in as nothing.
+fn optional(value: Option<impl ToString>, renderer: &dyn MessageRenderer) -> String {
+    value
+        .map(|value| renderer.escape(&value.to_string()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::i18n::Locale;
+    use crate::domain::models::Location;
+    use crate::domain::weather::Weather;
+    use crate::presentation::MessageFormat;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, 8, 0, 0).unwrap()
+    }
+
+    /// A reading whose place and source carry characters every markup escapes.
+    fn reading() -> AirQualityData {
+        let mut data = AirQualityData::sample(
+            Location::from_city("Si Racha_1", "Chon Buri", "Thailand"),
+            87,
+            28.5,
+        );
+        data.pollutants.pm10 = Some(41.2);
+        data.weather = Weather {
+            temperature: Some(31),
+            humidity: Some(74),
+            wind_speed_kmh: Some(9.5),
+            wind_direction_deg: Some(45.0),
+            ..Weather::default()
+        };
+        data.source = "Open-Meteo (CAMS) & PCD".to_string();
+        data
+    }
+
+    fn render(
+        format: MessageFormat,
+        standard: AqiStandard,
+        template: Option<&MessageTemplate>,
+        data: &AirQualityData,
+    ) -> String {
+        render_reading(
+            format.renderer(),
+            template,
+            data,
+            standard,
+            Locale::English.catalogue(),
+            now(),
+        )
+        .unwrap()
+    }
+
+    fn template() -> MessageTemplate {
+        MessageTemplate::parse("{level_emoji} {place}: AQI {aqi} ({level}) - {wind}.").unwrap()
+    }
+
+    #[test]
+    fn html() {
+        let format = MessageFormat::Html;
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, None, &reading()),
+            "🟡 <b>Moderate</b>\n\
+            \n\
+            📍 Si Racha_1, Chon Buri\n\
+            AQI <b>87</b> · PM2.5 28.5 µg/m³\n\
+            Main pollutant PM2.5\n\
+            🧪 PM10 41 µg/m³\n\
+            🌡\u{fe0f} 31°C · 💧 74% · 💨 NE 10 km/h\n\
+            \n\
+            Fine to go out, but sensitive people should take care\n\
+            <i>Source: Open-Meteo (CAMS) &amp; PCD</i>"
+        );
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, Some(&template()), &reading()),
+            "🟡 Si Racha_1, Chon Buri: AQI 87 (Moderate) - NE 10 km/h."
+        );
+    }
+
+    #[test]
+    fn markdown_v2() {
+        let format = MessageFormat::MarkdownV2;
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, None, &reading()),
+            "🟡 *Moderate*\n\
+            \n\
+            📍 Si Racha\\_1, Chon Buri\n\
+            AQI *87* · PM2\\.5 28\\.5 µg/m³\n\
+            Main pollutant PM2\\.5\n\
+            🧪 PM10 41 µg/m³\n\
+            🌡\u{fe0f} 31°C · 💧 74% · 💨 NE 10 km/h\n\
+            \n\
+            Fine to go out, but sensitive people should take care\n\
+            _Source: Open\\-Meteo \\(CAMS\\) & PCD_"
+        );
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, Some(&template()), &reading()),
+            "🟡 Si Racha\\_1, Chon Buri: AQI 87 \\(Moderate\\) \\- NE 10 km/h\\."
+        );
+    }
+
+    #[test]
+    fn plain_text() {
+        let format = MessageFormat::PlainText;
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, None, &reading()),
+            "🟡 Moderate\n\
+            \n\
+            📍 Si Racha_1, Chon Buri\n\
+            AQI 87 · PM2.5 28.5 µg/m³\n\
+            Main pollutant PM2.5\n\
+            🧪 PM10 41 µg/m³\n\
+            🌡\u{fe0f} 31°C · 💧 74% · 💨 NE 10 km/h\n\
+            \n\
+            Fine to go out, but sensitive people should take care\n\
+            Source: Open-Meteo (CAMS) & PCD"
+        );
+        assert_eq!(
+            render(format, AqiStandard::UsEpa, Some(&template()), &reading()),
+            "🟡 Si Racha_1, Chon Buri: AQI 87 (Moderate) - NE 10 km/h."
+        );
+    }
+
+    #[test]
+    fn other_standards_escape_the_us_aqi_aside() {
+        let message = render(
+            MessageFormat::MarkdownV2,
+            AqiStandard::ThaiPcd,
+            None,
+            &reading(),
+        );
+        assert!(message.contains("\\(US AQI 87\\)"), "{}", message);
+    }
+
+    #[test]
+    fn templates_fill_every_placeholder() {
+        let source = crate::domain::template::PLACEHOLDERS
+            .iter()
+            .map(|name| format!("{{{}}}", name))
+            .collect::<Vec<_>>()
+            .join("|");
+        let template = MessageTemplate::parse(&source).unwrap();
+
+        assert_eq!(
+            render(
+                MessageFormat::PlainText,
+                AqiStandard::ThaiPcd,
+                Some(&template),
+                &reading()
+            ),
+            "Si Racha_1, Chon Buri|🟡|Moderate|Outdoor activities as usual; people who need to \
+            look after their health should spend less time outside if they cough, have trouble \
+            breathing or eye irritation|87|64|Thai AQI|Thailand AQI (Pollution Control \
+            Department)|28.5|31|74|NE 10 km/h|Open-Meteo (CAMS) & PCD"
+        );
+    }
+
+    #[test]
+    fn stale_readings_end_with_a_note_in_every_format() {
+        let mut data = reading();
+        data.stale_since = Some(now() - chrono::Duration::minutes(45));
+        let note = "⏳ Couldn't update, showing the reading from 45 min ago";
+        for (format, expected) in [
+            (MessageFormat::Html, format!("<i>{}</i>", note)),
+            (MessageFormat::MarkdownV2, format!("_{}_", note)),
+            (MessageFormat::PlainText, note.to_string()),
+        ] {
+            for template in [None, Some(template())] {
+                let message = render(format, AqiStandard::UsEpa, template.as_ref(), &data);
+                assert!(
+                    message.ends_with(&format!("\n{}", expected)),
+                    "{:?}: {}",
+                    format,
+                    message
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::domain::i18n::{fill, Catalogue};
+use crate::domain::recommendation::{Basis, Recommendation};
+use crate::presentation::markup::{fill_template, MessageRenderer};
+use chrono::FixedOffset;
+
+/// The recommended windows, best first, each followed by why it was picked.
+pub fn render_recommendation(
+    renderer: &dyn MessageRenderer,
+    recommendation: &Recommendation,
+    text: &Catalogue,
+    timezone: FixedOffset,
+) -> String {
+    let r = renderer;
+    let activity = recommendation.activity;
+    let header = fill(
+        text.bestime_header,
+        &[
+            ("emoji", &activity.emoji()),
+            ("activity", &text.activity(activity)),
+            ("place", &recommendation.location.name),
+        ],
+    );
+    let mut lines = vec![r.bold(&header)];
+    if recommendation.basis == Basis::History {
+        lines.push(r.italic(text.bestime_from_history));
+    }
+
+    if recommendation.windows.is_empty() {
+        lines.push(String::new());
+        lines.push(r.escape(text.bestime_none));
+    }
+    let profile = activity.profile();
+    for (rank, window) in recommendation.windows.iter().enumerate() {
+        let start = window.start.with_timezone(&timezone).format("%d/%m %H:%M");
+        let end = window.end.with_timezone(&timezone).format("%H:%M");
+        lines.push(String::new());
+        lines.push(fill_template(
+            r,
+            text.bestime_window,
+            &[
+                ("rank", &(rank + 1)),
+                ("start", &r.bold(&start.to_string())),
+                ("end", &r.bold(&end.to_string())),
+            ],
+        ));
+        let reasons: Vec<String> = window
+            .reasons(&profile)
+            .iter()
+            .map(|reason| text.reason(reason))
+            .collect();
+        lines.push(r.escape(&reasons.join(" · ")));
+    }
+
+    lines.join("\n")
+}
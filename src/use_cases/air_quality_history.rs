@@ -0,0 +1,56 @@
+use crate::domain::history::{HistoryPeriod, HistorySummary};
+use crate::domain::models::Location;
+use crate::use_cases::check_air_quality::ReadingRepository;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+pub struct AirQualityHistory<H: ReadingRepository> {
+    readings: H,
+}
+
+impl<H: ReadingRepository> Clone for AirQualityHistory<H>
+where
+    H: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            readings: self.readings.clone(),
+        }
+    }
+}
+
+impl<H: ReadingRepository> AirQualityHistory<H> {
+    pub fn new(readings: H) -> Self {
+        Self { readings }
+    }
+
+    /// Summary of `location` over the `period` ending at `now`, or `None` without readings.
+    pub async fn summarize(
+        &self,
+        location: &Location,
+        period: HistoryPeriod,
+        now: DateTime<Utc>,
+    ) -> Result<Option<HistorySummary>> {
+        let readings = self
+            .readings
+            .readings_since(location, now - period.duration())
+            .await?;
+        Ok(HistorySummary::from_readings(&readings))
+    }
+
+    /// Highest AQI recorded for `location` from `start` until `end`, or `None`
+    /// without readings.
+    pub async fn peak(
+        &self,
+        location: &Location,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<i32>> {
+        let readings = self.readings.readings_since(location, start).await?;
+        Ok(readings
+            .iter()
+            .filter(|(at, _)| *at < end)
+            .map(|(_, data)| data.aqi)
+            .max())
+    }
+}
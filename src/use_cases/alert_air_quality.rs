@@ -0,0 +1,273 @@
+use crate::domain::alerts::{AlertPolicy, LevelTransition};
+use crate::domain::i18n::fill;
+use crate::domain::models::{AirQualityData, AirQualityLevel, Location};
+use crate::use_cases::chat_settings::ChatPreferences;
+use crate::use_cases::notify_air_quality::{NotificationGateway, NotifyAirQuality};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Remembers the last level each chat was told about for each location.
+#[async_trait]
+pub trait AlertStateRepository: Send + Sync {
+    async fn last_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+    ) -> Result<Option<AirQualityLevel>>;
+    async fn save_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+        level: AirQualityLevel,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: AlertStateRepository + ?Sized> AlertStateRepository for Arc<T> {
+    async fn last_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+    ) -> Result<Option<AirQualityLevel>> {
+        (**self).last_alert_level(chat_id, location).await
+    }
+
+    async fn save_alert_level(
+        &self,
+        chat_id: &str,
+        location: &Location,
+        level: AirQualityLevel,
+    ) -> Result<()> {
+        (**self).save_alert_level(chat_id, location, level).await
+    }
+}
+
+/// Pushes a notification only when a location's level crosses a boundary.
+pub struct AlertAirQuality<N: NotificationGateway, A: AlertStateRepository> {
+    notifier: NotifyAirQuality<N>,
+    policy: AlertPolicy,
+    state: A,
+}
+
+impl<N: NotificationGateway, A: AlertStateRepository> AlertAirQuality<N, A> {
+    pub fn new(notifier: NotifyAirQuality<N>, policy: AlertPolicy, state: A) -> Self {
+        Self {
+            notifier,
+            policy,
+            state,
+        }
+    }
+
+    /// Returns the transition that was observed; a message is only sent when
+    /// it concerns `min_level`. Levels follow the US AQI whatever standard
+    /// and language `preferences` write the message in, and the headline names
+    /// the US levels so it can't be mistaken for the chat's own categories.
+    pub async fn execute(
+        &self,
+        chat_id: &str,
+        location: &Location,
+        data: &AirQualityData,
+        min_level: AirQualityLevel,
+        preferences: &ChatPreferences,
+    ) -> Result<LevelTransition> {
+        let previous = self.state.last_alert_level(chat_id, location).await?;
+        let transition = self
+            .policy
+            .evaluate(previous, data.aqi)
+            .with_context(|| format!("AQI {} has no level", data.aqi))?;
+
+        if transition.concerns(min_level) {
+            let text = preferences.locale.catalogue();
+            let headline = match transition {
+                LevelTransition::Worsened { from, to } => Some((text.worsened, from, to)),
+                LevelTransition::Improved { from, to } => Some((text.improved, from, to)),
+                _ => None,
+            };
+            match headline {
+                Some((headline, from, to)) => {
+                    let headline = fill(
+                        headline,
+                        &[
+                            ("from", &text.level(from).name),
+                            ("to", &text.level(to).name),
+                        ],
+                    );
+                    self.notifier
+                        .execute_with_headline(chat_id, &headline, data, preferences)
+                        .await?
+                }
+                None => self.notifier.execute(chat_id, data, preferences).await?,
+            }
+        }
+
+        // Only remember the new level once the message went out, so a failed
+        // send is retried on the next tick.
+        if previous != Some(transition.level()) {
+            self.state
+                .save_alert_level(chat_id, location, transition.level())
+                .await?;
+        }
+
+        Ok(transition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::memory::InMemoryStore;
+    use crate::domain::i18n::Locale;
+    use crate::domain::standards::AqiStandard;
+    use crate::presentation::MessageFormat;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use AirQualityLevel::*;
+
+    /// Keeps every message instead of sending it; fails while `failing` is set.
+    #[derive(Default)]
+    struct RecordingGateway {
+        sent: Mutex<Vec<String>>,
+        failing: AtomicBool,
+    }
+
+    #[async_trait]
+    impl NotificationGateway for Arc<RecordingGateway> {
+        async fn send(&self, _channel_id: &str, message: &str) -> Result<()> {
+            if self.failing.load(Ordering::SeqCst) {
+                anyhow::bail!("Telegram is down");
+            }
+            self.sent.lock().unwrap().push(message.to_string());
+            Ok(())
+        }
+
+        fn format(&self) -> MessageFormat {
+            MessageFormat::PlainText
+        }
+    }
+
+    struct Harness {
+        gateway: Arc<RecordingGateway>,
+        alerts: AlertAirQuality<Arc<RecordingGateway>, Arc<InMemoryStore>>,
+        location: Location,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let gateway = Arc::new(RecordingGateway::default());
+            let alerts = AlertAirQuality::new(
+                NotifyAirQuality::new(gateway.clone()),
+                AlertPolicy::new(5),
+                Arc::new(InMemoryStore::new()),
+            );
+            Self {
+                gateway,
+                alerts,
+                location: Location::from_city("Ban Suan", "Chon Buri", "Thailand"),
+            }
+        }
+
+        async fn reading(
+            &self,
+            aqi: i32,
+            pm25: f64,
+            standard: AqiStandard,
+        ) -> Result<LevelTransition> {
+            let preferences = ChatPreferences {
+                standard,
+                locale: Locale::English,
+                template: None,
+            };
+            let data = AirQualityData::sample(self.location.clone(), aqi, pm25);
+            self.alerts
+                .execute(
+                    "@channel",
+                    &self.location,
+                    &data,
+                    UnhealthyForSensitive,
+                    &preferences,
+                )
+                .await
+        }
+
+        fn sent(&self) -> Vec<String> {
+            self.gateway.sent.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn alerts_only_on_transitions_that_concern_the_chat() {
+        let harness = Harness::new();
+        let us = AqiStandard::UsEpa;
+
+        // Below the chat's minimum level: remembered, not sent.
+        assert_eq!(
+            harness.reading(80, 25.0, us).await.unwrap(),
+            LevelTransition::Initial(Moderate)
+        );
+        assert!(harness.sent().is_empty());
+
+        assert_eq!(
+            harness.reading(130, 48.0, us).await.unwrap(),
+            LevelTransition::Worsened {
+                from: Moderate,
+                to: UnhealthyForSensitive
+            }
+        );
+        assert_eq!(
+            harness.reading(125, 45.0, us).await.unwrap(),
+            LevelTransition::Unchanged(UnhealthyForSensitive)
+        );
+        assert!(matches!(
+            harness.reading(60, 15.0, us).await.unwrap(),
+            LevelTransition::Improved { .. }
+        ));
+
+        let sent = harness.sent();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[0].starts_with(
+            "⬆️ Air quality is getting worse: US AQI Moderate → Unhealthy for sensitive groups\n\n"
+        ));
+        assert!(sent[1].starts_with(
+            "⬇️ Air quality is getting better: US AQI Unhealthy for sensitive groups → Moderate\n\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn headline_names_us_levels_for_chats_on_other_standards() {
+        let harness = Harness::new();
+        let thai = AqiStandard::ThaiPcd;
+        harness.reading(80, 25.0, thai).await.unwrap();
+        harness.reading(160, 73.0, thai).await.unwrap();
+
+        let sent = harness.sent();
+        assert_eq!(sent.len(), 1);
+        assert!(
+            sent[0].starts_with("⬆️ Air quality is getting worse: US AQI Moderate → Unhealthy\n\n"),
+            "{}",
+            sent[0]
+        );
+        // The reading underneath is still in the chat's own standard.
+        let data = AirQualityData::sample(harness.location.clone(), 160, 73.0);
+        let category = thai.assess(&data).unwrap().category;
+        let name = Locale::English.catalogue().category(&category).name;
+        assert!(sent[0].contains(name), "{}", sent[0]);
+    }
+
+    #[tokio::test]
+    async fn failed_sends_are_retried_on_the_next_reading() {
+        let harness = Harness::new();
+        let us = AqiStandard::UsEpa;
+        harness.reading(80, 25.0, us).await.unwrap();
+
+        harness.gateway.failing.store(true, Ordering::SeqCst);
+        assert!(harness.reading(130, 48.0, us).await.is_err());
+
+        harness.gateway.failing.store(false, Ordering::SeqCst);
+        assert!(matches!(
+            harness.reading(131, 48.0, us).await.unwrap(),
+            LevelTransition::Worsened { .. }
+        ));
+        assert_eq!(harness.sent().len(), 1);
+    }
+}
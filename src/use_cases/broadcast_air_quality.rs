@@ -0,0 +1,167 @@
+use crate::domain::models::{AirQualityLevel, Location};
+use crate::use_cases::alert_air_quality::AlertStateRepository;
+use crate::use_cases::chat_settings::{ChatPreferences, ChatSettingsRepository};
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority};
+use crate::use_cases::manage_subscriptions::SubscriptionRepository;
+use crate::use_cases::notify_air_quality::NotificationGateway;
+use crate::use_cases::scheduler::ScheduledJob;
+use crate::use_cases::{AlertAirQuality, ChatSettings, CheckAirQuality, ManageSubscriptions};
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, info};
+
+pub struct BroadcastAirQuality<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: SubscriptionRepository + AlertStateRepository + ChatSettingsRepository,
+{
+    checker: CheckAirQuality<R>,
+    alerts: AlertAirQuality<N, S>,
+    subscriptions: ManageSubscriptions<S>,
+    settings: ChatSettings<S>,
+    locations: Vec<Location>,
+    channel_id: String,
+}
+
+/// Everyone who wants to hear about one location.
+struct Audience {
+    location: Location,
+    recipients: Vec<Recipient>,
+}
+
+struct Recipient {
+    chat_id: String,
+    min_level: AirQualityLevel,
+    preferences: ChatPreferences,
+}
+
+impl<R, N, S> BroadcastAirQuality<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: SubscriptionRepository + AlertStateRepository + ChatSettingsRepository,
+{
+    pub fn new(
+        checker: CheckAirQuality<R>,
+        alerts: AlertAirQuality<N, S>,
+        subscriptions: ManageSubscriptions<S>,
+        settings: ChatSettings<S>,
+        locations: Vec<Location>,
+        channel_id: String,
+    ) -> Self {
+        Self {
+            checker,
+            alerts,
+            subscriptions,
+            settings,
+            locations,
+            channel_id,
+        }
+    }
+
+    /// The channel follows every configured location at every level with the
+    /// deployment's preferences; subscribed chats follow their own locations
+    /// from their chosen level, with their own preferences.
+    async fn audiences(&self) -> Vec<Audience> {
+        let mut audiences: Vec<Audience> = Vec::new();
+        let mut add = |location: &Location, recipient: Recipient| {
+            let key = location.key();
+            match audiences.iter_mut().find(|a| a.location.key() == key) {
+                Some(audience) => audience.recipients.push(recipient),
+                None => audiences.push(Audience {
+                    location: location.clone(),
+                    recipients: vec![recipient],
+                }),
+            }
+        };
+
+        for location in &self.locations {
+            add(
+                location,
+                Recipient {
+                    chat_id: self.channel_id.clone(),
+                    min_level: AirQualityLevel::Good,
+                    preferences: self.settings.default_preferences(),
+                },
+            );
+        }
+
+        match self.subscriptions.all().await {
+            Ok(subscriptions) => {
+                for subscription in subscriptions {
+                    let preferences = self.settings.preferences(subscription.chat_id).await;
+                    add(
+                        &subscription.location,
+                        Recipient {
+                            chat_id: subscription.chat_id.to_string(),
+                            min_level: subscription.min_level,
+                            preferences,
+                        },
+                    );
+                }
+            }
+            Err(e) => error!("Failed to load subscriptions: {:#}", e),
+        }
+
+        audiences
+    }
+}
+
+#[async_trait]
+impl<R, N, S> ScheduledJob for BroadcastAirQuality<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: SubscriptionRepository + AlertStateRepository + ChatSettingsRepository,
+{
+    fn name(&self) -> &str {
+        "air quality broadcast"
+    }
+
+    async fn run(&self) -> Result<()> {
+        for audience in self.audiences().await {
+            let location = &audience.location;
+            let data = match self
+                .checker
+                .execute(location.clone(), FetchPriority::Scheduled)
+                .await
+            {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("Failed to check {}: {:#}", location.name, e);
+                    continue;
+                }
+            };
+
+            for recipient in &audience.recipients {
+                let chat_id = &recipient.chat_id;
+                match self
+                    .alerts
+                    .execute(
+                        chat_id,
+                        location,
+                        &data,
+                        recipient.min_level,
+                        &recipient.preferences,
+                    )
+                    .await
+                {
+                    Ok(transition) if transition.concerns(recipient.min_level) => {
+                        info!(
+                            "Notified {} about {} (AQI {})",
+                            chat_id, location.name, data.aqi
+                        )
+                    }
+                    Ok(_) => debug!("{} unchanged for {}", location.name, chat_id),
+                    Err(e) => error!(
+                        "Failed to notify {} about {}: {:#}",
+                        chat_id, location.name, e
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::domain::history::HistoryPeriod;
+use crate::domain::models::{AirQualityData, Location};
+use crate::use_cases::check_air_quality::ReadingRepository;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+pub trait ChartRenderer: Send + Sync {
+    /// Renders AQI readings between `from` and `to` as an encoded image.
+    fn render(
+        &self,
+        readings: &[(DateTime<Utc>, AirQualityData)],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<u8>>;
+}
+
+pub struct ChartAirQuality<H: ReadingRepository, C: ChartRenderer> {
+    readings: H,
+    renderer: C,
+}
+
+impl<H: ReadingRepository, C: ChartRenderer> ChartAirQuality<H, C> {
+    pub fn new(readings: H, renderer: C) -> Self {
+        Self { readings, renderer }
+    }
+
+    /// Chart of `location` over the `period` ending at `now`, or `None` without readings.
+    pub async fn execute(
+        &self,
+        location: &Location,
+        period: HistoryPeriod,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Vec<u8>>> {
+        let from = now - period.duration();
+        let readings = self.readings.readings_since(location, from).await?;
+        if readings.is_empty() {
+            return Ok(None);
+        }
+
+        self.renderer.render(&readings, from, now).map(Some)
+    }
+}
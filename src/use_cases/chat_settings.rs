@@ -0,0 +1,163 @@
+use crate::domain::i18n::Locale;
+use crate::domain::standards::AqiStandard;
+use crate::domain::template::MessageTemplate;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Per-chat preferences chosen with bot commands.
+#[async_trait]
+pub trait ChatSettingsRepository: Send + Sync {
+    async fn aqi_standard(&self, chat_id: i64) -> Result<Option<AqiStandard>>;
+    async fn save_aqi_standard(&self, chat_id: i64, standard: AqiStandard) -> Result<()>;
+    async fn locale(&self, chat_id: i64) -> Result<Option<Locale>>;
+    async fn save_locale(&self, chat_id: i64, locale: Locale) -> Result<()>;
+    async fn template(&self, chat_id: i64) -> Result<Option<MessageTemplate>>;
+    /// `None` goes back to the default.
+    async fn save_template(&self, chat_id: i64, template: Option<&MessageTemplate>) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: ChatSettingsRepository + ?Sized> ChatSettingsRepository for Arc<T> {
+    async fn aqi_standard(&self, chat_id: i64) -> Result<Option<AqiStandard>> {
+        (**self).aqi_standard(chat_id).await
+    }
+
+    async fn save_aqi_standard(&self, chat_id: i64, standard: AqiStandard) -> Result<()> {
+        (**self).save_aqi_standard(chat_id, standard).await
+    }
+
+    async fn locale(&self, chat_id: i64) -> Result<Option<Locale>> {
+        (**self).locale(chat_id).await
+    }
+
+    async fn save_locale(&self, chat_id: i64, locale: Locale) -> Result<()> {
+        (**self).save_locale(chat_id, locale).await
+    }
+
+    async fn template(&self, chat_id: i64) -> Result<Option<MessageTemplate>> {
+        (**self).template(chat_id).await
+    }
+
+    async fn save_template(&self, chat_id: i64, template: Option<&MessageTemplate>) -> Result<()> {
+        (**self).save_template(chat_id, template).await
+    }
+}
+
+/// How one chat wants its readings written.
+#[derive(Debug, Clone)]
+pub struct ChatPreferences {
+    pub standard: AqiStandard,
+    pub locale: Locale,
+    /// `None` uses the built-in layout.
+    pub template: Option<MessageTemplate>,
+}
+
+/// Chat preferences, falling back to the deployment's defaults.
+pub struct ChatSettings<S: ChatSettingsRepository> {
+    store: S,
+    default_standard: AqiStandard,
+    default_locale: Locale,
+    default_template: Option<MessageTemplate>,
+}
+
+impl<S: ChatSettingsRepository> Clone for ChatSettings<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            default_standard: self.default_standard,
+            default_locale: self.default_locale,
+            default_template: self.default_template.clone(),
+        }
+    }
+}
+
+impl<S: ChatSettingsRepository> ChatSettings<S> {
+    pub fn new(store: S, default_standard: AqiStandard, default_locale: Locale) -> Self {
+        Self {
+            store,
+            default_standard,
+            default_locale,
+            default_template: None,
+        }
+    }
+
+    /// Template for chats that haven't written their own, including the
+    /// channel; without one they get the built-in layout.
+    pub fn with_default_template(mut self, template: Option<MessageTemplate>) -> Self {
+        self.default_template = template;
+        self
+    }
+
+    /// Preferences for chats that haven't picked any, including the channel.
+    pub fn default_preferences(&self) -> ChatPreferences {
+        ChatPreferences {
+            standard: self.default_standard,
+            locale: self.default_locale,
+            template: self.default_template.clone(),
+        }
+    }
+
+    pub async fn preferences(&self, chat_id: i64) -> ChatPreferences {
+        ChatPreferences {
+            standard: self.aqi_standard(chat_id).await,
+            locale: self.locale(chat_id).await,
+            template: self.template(chat_id).await,
+        }
+    }
+
+    /// The chat's standard; a storage failure falls back to the default rather
+    /// than holding up the message.
+    pub async fn aqi_standard(&self, chat_id: i64) -> AqiStandard {
+        match self.store.aqi_standard(chat_id).await {
+            Ok(standard) => standard.unwrap_or(self.default_standard),
+            Err(e) => {
+                warn!("Failed to load AQI standard for chat {}: {:#}", chat_id, e);
+                self.default_standard
+            }
+        }
+    }
+
+    pub async fn set_aqi_standard(&self, chat_id: i64, standard: AqiStandard) -> Result<()> {
+        self.store.save_aqi_standard(chat_id, standard).await
+    }
+
+    /// The chat's language, falling back to the default like `aqi_standard`.
+    pub async fn locale(&self, chat_id: i64) -> Locale {
+        match self.store.locale(chat_id).await {
+            Ok(locale) => locale.unwrap_or(self.default_locale),
+            Err(e) => {
+                warn!("Failed to load language for chat {}: {:#}", chat_id, e);
+                self.default_locale
+            }
+        }
+    }
+
+    pub async fn set_locale(&self, chat_id: i64, locale: Locale) -> Result<()> {
+        self.store.save_locale(chat_id, locale).await
+    }
+
+    /// The chat's own template, or the default one, falling back like `aqi_standard`.
+    pub async fn template(&self, chat_id: i64) -> Option<MessageTemplate> {
+        match self.store.template(chat_id).await {
+            Ok(Some(template)) => Some(template),
+            Ok(None) => self.default_template.clone(),
+            Err(e) => {
+                warn!("Failed to load template for chat {}: {:#}", chat_id, e);
+                self.default_template.clone()
+            }
+        }
+    }
+
+    pub async fn set_template(
+        &self,
+        chat_id: i64,
+        template: Option<&MessageTemplate>,
+    ) -> Result<()> {
+        self.store.save_template(chat_id, template).await
+    }
+}
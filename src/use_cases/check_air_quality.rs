@@ -1,23 +1,109 @@
-use crate::domain::models::{AirQualityData, Location};
-use crate::domain::services::AqiConverter;
+use crate::domain::aqi::EpaRevision;
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{AirQualityData, Location, Pollutant, Pollutants, SourceSpread};
+use crate::domain::weather::Weather;
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Who a fetch is for, so providers with a call budget can favour scheduled work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchPriority {
+    /// Scheduled broadcasts; these wait for the rate limit rather than fail.
+    Scheduled,
+    /// Bot commands; refused first when the budget runs low.
+    Interactive,
+}
 
 #[async_trait]
 pub trait AirQualityRepository: Send + Sync {
-    async fn get_air_quality(&self, location: &Location) -> Result<RawAirQualityData>;
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError>;
+}
+
+#[async_trait]
+impl<T: AirQualityRepository + ?Sized> AirQualityRepository for Arc<T> {
+    async fn get_air_quality(
+        &self,
+        location: &Location,
+        priority: FetchPriority,
+    ) -> Result<RawAirQualityData, AirQualityError> {
+        (**self).get_air_quality(location, priority).await
+    }
+}
+
+/// Days of readings a store keeps; nothing looks back further than `/history 7d`.
+pub const READING_RETENTION_DAYS: i64 = 7;
+
+/// Keeps every reading that was fetched, keyed by the location that was asked for.
+#[async_trait]
+pub trait ReadingRepository: Send + Sync {
+    /// Also drops readings older than `READING_RETENTION_DAYS` before `recorded_at`.
+    async fn save_reading(
+        &self,
+        location: &Location,
+        recorded_at: DateTime<Utc>,
+        data: &AirQualityData,
+    ) -> Result<()>;
+    /// Readings for `location` recorded at or after `since`, oldest first.
+    async fn readings_since(
+        &self,
+        location: &Location,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>>;
 }
 
+#[async_trait]
+impl<T: ReadingRepository + ?Sized> ReadingRepository for Arc<T> {
+    async fn save_reading(
+        &self,
+        location: &Location,
+        recorded_at: DateTime<Utc>,
+        data: &AirQualityData,
+    ) -> Result<()> {
+        (**self).save_reading(location, recorded_at, data).await
+    }
+
+    async fn readings_since(
+        &self,
+        location: &Location,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>> {
+        (**self).readings_since(location, since).await
+    }
+}
+
+#[derive(Clone)]
 pub struct RawAirQualityData {
     pub city: String,
     pub state: String,
     pub aqi: i32,
-    pub temperature: i32,
-    pub humidity: i32,
+    /// Measured concentrations the provider reports.
+    pub pollutants: Pollutants,
+    pub main_pollutant: Option<Pollutant>,
+    pub aqi_cn: Option<i32>,
+    /// Thai AQI, when the provider reports it.
+    pub aqi_th: Option<i32>,
+    pub weather: Weather,
+    /// Name of the provider that served this reading.
+    pub source: String,
+    /// Disagreement between providers, when the reading combines several.
+    pub spread: Option<SourceSpread>,
+    /// Set when the provider failed and this is an earlier reading fetched at that time.
+    pub stale_since: Option<DateTime<Utc>>,
+    /// Served from a cache rather than fetched for this request.
+    pub cached: bool,
 }
 
 pub struct CheckAirQuality<R: AirQualityRepository> {
     repository: R,
+    history: Option<Arc<dyn ReadingRepository>>,
+    aqi_revision: EpaRevision,
 }
 
 impl<R: AirQualityRepository> Clone for CheckAirQuality<R>
@@ -27,25 +113,140 @@ where
     fn clone(&self) -> Self {
         Self {
             repository: self.repository.clone(),
+            history: self.history.clone(),
+            aqi_revision: self.aqi_revision,
         }
     }
 }
 
 impl<R: AirQualityRepository> CheckAirQuality<R> {
     pub fn new(repository: R) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            history: None,
+            aqi_revision: EpaRevision::default(),
+        }
+    }
+
+    /// Breakpoint tables used to estimate PM2.5 when a provider only reports the AQI.
+    pub fn with_aqi_revision(mut self, aqi_revision: EpaRevision) -> Self {
+        self.aqi_revision = aqi_revision;
+        self
+    }
+
+    /// Records every reading fetched upstream in `history`; cached ones are
+    /// already there.
+    pub fn with_history(mut self, history: Arc<dyn ReadingRepository>) -> Self {
+        self.history = Some(history);
+        self
     }
 
-    pub async fn execute(&self, location: Location) -> Result<AirQualityData> {
-        let raw_data = self.repository.get_air_quality(&location).await?;
-        let pm25 = AqiConverter::estimate_pm25_from_aqi(raw_data.aqi);
+    pub async fn execute(
+        &self,
+        location: Location,
+        priority: FetchPriority,
+    ) -> Result<AirQualityData, AirQualityError> {
+        let raw_data = self.repository.get_air_quality(&location, priority).await?;
+        if raw_data.aqi < 0 {
+            return Err(AirQualityError::Parse(format!(
+                "{} reported a negative AQI ({})",
+                raw_data.source, raw_data.aqi
+            )));
+        }
+        let pollutants = raw_data.pollutants.without_invalid();
+        let cached = raw_data.cached;
+
+        // Only fall back to estimating PM2.5 from the AQI when it wasn't measured.
+        let (pm25, pm25_estimated) = match pollutants.pm25 {
+            Some(pm25) => (pm25, false),
+            None => (
+                self.aqi_revision
+                    .concentration_from_aqi(Pollutant::Pm25, raw_data.aqi),
+                true,
+            ),
+        };
 
-        Ok(AirQualityData {
+        let data = AirQualityData {
             location: Location::from_city(raw_data.city, raw_data.state, "Thailand"),
             aqi: raw_data.aqi,
             pm25,
-            temperature: raw_data.temperature,
-            humidity: raw_data.humidity,
-        })
+            pm25_estimated,
+            pollutants,
+            main_pollutant: raw_data.main_pollutant,
+            aqi_cn: raw_data.aqi_cn,
+            aqi_th: raw_data.aqi_th,
+            weather: raw_data.weather,
+            source: raw_data.source,
+            spread: raw_data.spread,
+            stale_since: raw_data.stale_since,
+        };
+
+        // A cached or stale reading is already in the history from when it was
+        // fetched; saving it again would count it once per request.
+        if let Some(history) = self.history.as_ref().filter(|_| !cached) {
+            if let Err(e) = history.save_reading(&location, Utc::now(), &data).await {
+                warn!("Failed to record reading for {}: {:#}", location.name, e);
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::cache::CachingRepository;
+    use crate::adapters::memory::InMemoryStore;
+
+    /// Always reports the same reading.
+    struct FixedRepository;
+
+    #[async_trait]
+    impl AirQualityRepository for FixedRepository {
+        async fn get_air_quality(
+            &self,
+            location: &Location,
+            _priority: FetchPriority,
+        ) -> Result<RawAirQualityData, AirQualityError> {
+            Ok(RawAirQualityData {
+                city: location.name.clone(),
+                state: "Chon Buri".to_string(),
+                aqi: 87,
+                pollutants: Pollutants::default(),
+                main_pollutant: None,
+                aqi_cn: None,
+                aqi_th: None,
+                weather: Weather::default(),
+                source: "Test".to_string(),
+                spread: None,
+                stale_since: None,
+                cached: false,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn records_history_only_for_fetched_readings() {
+        let store = Arc::new(InMemoryStore::new());
+        let cache = CachingRepository::new(
+            FixedRepository,
+            chrono::Duration::minutes(10),
+            chrono::Duration::hours(1),
+        );
+        let checker = CheckAirQuality::new(cache).with_history(store.clone());
+        let location = Location::from_city("Ban Suan", "Chon Buri", "Thailand");
+
+        let start = Utc::now();
+        for _ in 0..3 {
+            let data = checker
+                .execute(location.clone(), FetchPriority::Interactive)
+                .await
+                .unwrap();
+            assert_eq!(data.aqi, 87);
+        }
+
+        let history = store.readings_since(&location, start).await.unwrap();
+        assert_eq!(history.len(), 1);
     }
 }
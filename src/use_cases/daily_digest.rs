@@ -0,0 +1,163 @@
+use crate::domain::digest::{Digest, DigestEntry};
+use crate::domain::models::Location;
+use crate::domain::standards::AqiStandard;
+use crate::presentation::render_digest;
+use crate::use_cases::chat_settings::ChatSettingsRepository;
+use crate::use_cases::check_air_quality::{AirQualityRepository, FetchPriority, ReadingRepository};
+use crate::use_cases::forecast_air_quality::ForecastRepository;
+use crate::use_cases::notify_air_quality::NotificationGateway;
+use crate::use_cases::scheduler::ScheduledJob;
+use crate::use_cases::{AirQualityHistory, ChatSettings, CheckAirQuality, ForecastAirQuality};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// Posts every configured location to the channel in one morning message.
+pub struct DailyDigest<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: ReadingRepository + ChatSettingsRepository,
+{
+    checker: CheckAirQuality<R>,
+    history: AirQualityHistory<S>,
+    settings: ChatSettings<S>,
+    gateway: N,
+    forecasts: Option<ForecastAirQuality<Arc<dyn ForecastRepository>>>,
+    locations: Vec<Location>,
+    channel_id: String,
+    timezone: FixedOffset,
+}
+
+impl<R, N, S> DailyDigest<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: ReadingRepository + ChatSettingsRepository,
+{
+    pub fn new(
+        checker: CheckAirQuality<R>,
+        history: AirQualityHistory<S>,
+        settings: ChatSettings<S>,
+        gateway: N,
+        locations: Vec<Location>,
+        channel_id: String,
+        timezone: FixedOffset,
+    ) -> Self {
+        Self {
+            checker,
+            history,
+            settings,
+            gateway,
+            forecasts: None,
+            locations,
+            channel_id,
+            timezone,
+        }
+    }
+
+    /// Adds the best time to go out later today under each location.
+    pub fn with_forecasts(
+        mut self,
+        forecasts: ForecastAirQuality<Arc<dyn ForecastRepository>>,
+    ) -> Self {
+        self.forecasts = Some(forecasts);
+        self
+    }
+
+    /// Current readings with yesterday's peaks, and today's best window when
+    /// forecasts are on, as of `now`, ranked under `standard`. A location that
+    /// can't be checked is listed as unavailable rather than dropping the digest.
+    pub async fn compile(&self, now: DateTime<Utc>, standard: AqiStandard) -> Digest {
+        let (start, end) = Digest::yesterday(now, self.timezone);
+        let mut entries = Vec::new();
+        let mut unavailable = Vec::new();
+
+        for location in &self.locations {
+            let data = match self
+                .checker
+                .execute(location.clone(), FetchPriority::Scheduled)
+                .await
+            {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("Failed to check {} for the digest: {:#}", location.name, e);
+                    unavailable.push(location.clone());
+                    continue;
+                }
+            };
+            let yesterday_peak = match self.history.peak(location, start, end).await {
+                Ok(peak) => peak,
+                Err(e) => {
+                    warn!(
+                        "Failed to load yesterday's peak for {}: {:#}",
+                        location.name, e
+                    );
+                    None
+                }
+            };
+            let best_window = match &self.forecasts {
+                Some(forecasts) => {
+                    // Only the rest of today; tomorrow gets its own digest.
+                    let hours_left = 24 - now.with_timezone(&self.timezone).hour();
+                    match forecasts.execute(location.clone(), hours_left).await {
+                        Ok(forecast) => forecast.best_window(self.timezone),
+                        Err(e) => {
+                            warn!("Failed to forecast {} for the digest: {}", location.name, e);
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+            entries.push(DigestEntry {
+                location: location.clone(),
+                data,
+                yesterday_peak,
+                best_window,
+            });
+        }
+
+        Digest::new(
+            now.with_timezone(&self.timezone).date_naive(),
+            standard,
+            entries,
+            unavailable,
+        )
+    }
+}
+
+#[async_trait]
+impl<R, N, S> ScheduledJob for DailyDigest<R, N, S>
+where
+    R: AirQualityRepository,
+    N: NotificationGateway,
+    S: ReadingRepository + ChatSettingsRepository,
+{
+    fn name(&self) -> &str {
+        "daily digest"
+    }
+
+    async fn run(&self) -> Result<()> {
+        let preferences = self.settings.default_preferences();
+        let digest = self.compile(Utc::now(), preferences.standard).await;
+        if digest.entries.is_empty() {
+            anyhow::bail!("No location could be checked");
+        }
+
+        let message = render_digest(
+            self.gateway.format().renderer(),
+            &digest,
+            preferences.locale.catalogue(),
+            self.timezone,
+        );
+        self.gateway.send(&self.channel_id, &message).await?;
+        info!(
+            "Posted the digest for {} locations",
+            digest.entries.len() + digest.unavailable.len()
+        );
+        Ok(())
+    }
+}
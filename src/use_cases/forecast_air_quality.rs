@@ -0,0 +1,206 @@
+use crate::domain::errors::AirQualityError;
+use crate::domain::forecast::Forecast;
+use crate::domain::models::{AirQualityData, Location};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+#[async_trait]
+pub trait ForecastRepository: Send + Sync {
+    /// Predicted readings for the next `hours` hours, one per hour, oldest first.
+    async fn get_forecast(
+        &self,
+        location: &Location,
+        hours: u32,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>, AirQualityError>;
+}
+
+#[async_trait]
+impl<T: ForecastRepository + ?Sized> ForecastRepository for Arc<T> {
+    async fn get_forecast(
+        &self,
+        location: &Location,
+        hours: u32,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>, AirQualityError> {
+        (**self).get_forecast(location, hours).await
+    }
+}
+
+/// Serves the same forecast for every location, or fails when there is none.
+#[cfg(test)]
+pub struct FixedForecast {
+    pub hours: Option<Vec<(DateTime<Utc>, AirQualityData)>>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl ForecastRepository for FixedForecast {
+    async fn get_forecast(
+        &self,
+        location: &Location,
+        _hours: u32,
+    ) -> Result<Vec<(DateTime<Utc>, AirQualityData)>, AirQualityError> {
+        self.hours.clone().ok_or_else(|| {
+            AirQualityError::UpstreamUnavailable(format!("no model for {}", location.name))
+        })
+    }
+}
+
+pub struct ForecastAirQuality<F: ForecastRepository> {
+    repository: F,
+}
+
+impl<F: ForecastRepository> Clone for ForecastAirQuality<F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            repository: self.repository.clone(),
+        }
+    }
+}
+
+impl<F: ForecastRepository> ForecastAirQuality<F> {
+    pub fn new(repository: F) -> Self {
+        Self { repository }
+    }
+
+    /// The next `hours` hours for `location`, up to `Forecast::MAX_HOURS`.
+    pub async fn execute(
+        &self,
+        location: Location,
+        hours: u32,
+    ) -> Result<Forecast, AirQualityError> {
+        let hours = hours.clamp(1, Forecast::MAX_HOURS);
+        let mut predicted = self.repository.get_forecast(&location, hours).await?;
+        predicted.retain(|(_, data)| data.aqi >= 0);
+        predicted.truncate(hours as usize);
+
+        if predicted.is_empty() {
+            return Err(AirQualityError::UpstreamUnavailable(format!(
+                "no forecast for {}",
+                location.name
+            )));
+        }
+
+        Ok(Forecast {
+            location,
+            hours: predicted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::forecast::OutdoorWindow;
+    use chrono::{Duration, FixedOffset, TimeZone};
+
+    fn bangkok() -> FixedOffset {
+        FixedOffset::east_opt(7 * 3600).unwrap()
+    }
+
+    fn location() -> Location {
+        Location::from_city("Ban Suan", "Chon Buri", "Thailand")
+    }
+
+    /// Consecutive hours from `local_hour` on 10 January, Bangkok time.
+    fn hours(local_hour: u32, aqis: &[i32]) -> Vec<(DateTime<Utc>, AirQualityData)> {
+        let start = bangkok()
+            .with_ymd_and_hms(2025, 1, 10, local_hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        aqis.iter()
+            .enumerate()
+            .map(|(offset, &aqi)| {
+                (
+                    start + Duration::hours(offset as i64),
+                    AirQualityData::sample(location(), aqi, 20.0),
+                )
+            })
+            .collect()
+    }
+
+    async fn forecast(
+        predicted: Option<Vec<(DateTime<Utc>, AirQualityData)>>,
+        hours: u32,
+    ) -> Result<Forecast, AirQualityError> {
+        ForecastAirQuality::new(FixedForecast { hours: predicted })
+            .execute(location(), hours)
+            .await
+    }
+
+    fn local(hour: u32) -> DateTime<Utc> {
+        bangkok()
+            .with_ymd_and_hms(2025, 1, 10, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn finds_the_cleanest_daytime_window() {
+        // 04:00 and 05:00 are cleanest but before daytime; 10:00–12:00 and
+        // 14:00–16:00 tie, so the earlier one wins.
+        let aqis = [10, 10, 80, 90, 70, 60, 40, 50, 70, 80, 50, 40, 90, 95];
+        let forecast = forecast(Some(hours(4, &aqis)), 24).await.unwrap();
+
+        assert_eq!(forecast.hours.len(), aqis.len());
+        assert_eq!(
+            forecast.best_window(bangkok()),
+            Some(OutdoorWindow {
+                start: local(10),
+                end: local(12),
+                average_aqi: 45,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn searches_only_the_requested_hours() {
+        let aqis = [80, 90, 70, 60, 20, 20];
+        let forecast = forecast(Some(hours(8, &aqis)), 4).await.unwrap();
+
+        assert_eq!(forecast.hours.len(), 4);
+        assert_eq!(forecast.best_window(bangkok()).unwrap().start, local(10));
+    }
+
+    #[tokio::test]
+    async fn no_window_without_two_daytime_hours_in_a_row() {
+        // Evening into the night.
+        let night = forecast(Some(hours(19, &[30, 20, 10, 10])), 24)
+            .await
+            .unwrap();
+        assert_eq!(night.best_window(bangkok()), None);
+
+        // Daytime hours with a gap the provider left out.
+        let mut gappy = hours(9, &[30, 40, 50]);
+        gappy.remove(1);
+        let gappy = forecast(Some(gappy), 24).await.unwrap();
+        assert_eq!(gappy.best_window(bangkok()), None);
+    }
+
+    #[tokio::test]
+    async fn hours_without_an_aqi_are_dropped() {
+        let forecast = forecast(Some(hours(9, &[-1, 60, 40])), 24).await.unwrap();
+
+        assert_eq!(forecast.hours.len(), 2);
+        assert_eq!(forecast.best_window(bangkok()).unwrap().average_aqi, 50);
+    }
+
+    #[tokio::test]
+    async fn fails_without_any_forecast() {
+        assert!(matches!(
+            forecast(Some(Vec::new()), 24).await,
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "no forecast for Ban Suan"
+        ));
+        assert!(matches!(
+            forecast(Some(hours(9, &[-1, -1])), 24).await,
+            Err(AirQualityError::UpstreamUnavailable(_))
+        ));
+        assert!(matches!(
+            forecast(None, 24).await,
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "no model for Ban Suan"
+        ));
+    }
+}
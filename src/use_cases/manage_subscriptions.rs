@@ -0,0 +1,198 @@
+use crate::domain::models::{AirQualityLevel, Location, Subscription};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait SubscriptionRepository: Send + Sync {
+    /// Adds the subscription, replacing any existing one for the same chat and location.
+    async fn save_subscription(&self, subscription: Subscription) -> Result<()>;
+    /// Removes the chat's subscription to `location`, or all of them when `None`.
+    /// Returns how many were removed.
+    async fn delete_subscriptions(
+        &self,
+        chat_id: i64,
+        location: Option<&Location>,
+    ) -> Result<usize>;
+    async fn subscriptions_for_chat(&self, chat_id: i64) -> Result<Vec<Subscription>>;
+    async fn all_subscriptions(&self) -> Result<Vec<Subscription>>;
+}
+
+#[async_trait]
+impl<T: SubscriptionRepository + ?Sized> SubscriptionRepository for Arc<T> {
+    async fn save_subscription(&self, subscription: Subscription) -> Result<()> {
+        (**self).save_subscription(subscription).await
+    }
+
+    async fn delete_subscriptions(
+        &self,
+        chat_id: i64,
+        location: Option<&Location>,
+    ) -> Result<usize> {
+        (**self).delete_subscriptions(chat_id, location).await
+    }
+
+    async fn subscriptions_for_chat(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        (**self).subscriptions_for_chat(chat_id).await
+    }
+
+    async fn all_subscriptions(&self) -> Result<Vec<Subscription>> {
+        (**self).all_subscriptions().await
+    }
+}
+
+pub struct ManageSubscriptions<S: SubscriptionRepository> {
+    store: S,
+}
+
+impl<S: SubscriptionRepository> Clone for ManageSubscriptions<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<S: SubscriptionRepository> ManageSubscriptions<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub async fn subscribe(
+        &self,
+        chat_id: i64,
+        location: Location,
+        min_level: AirQualityLevel,
+    ) -> Result<Subscription> {
+        let subscription = Subscription {
+            chat_id,
+            location,
+            min_level,
+        };
+        self.store.save_subscription(subscription.clone()).await?;
+        Ok(subscription)
+    }
+
+    pub async fn unsubscribe(&self, chat_id: i64, location: Option<&Location>) -> Result<usize> {
+        self.store.delete_subscriptions(chat_id, location).await
+    }
+
+    pub async fn list(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        self.store.subscriptions_for_chat(chat_id).await
+    }
+
+    pub async fn all(&self) -> Result<Vec<Subscription>> {
+        self.store.all_subscriptions().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::memory::InMemoryStore;
+
+    fn city(name: &str) -> Location {
+        Location::from_city(name, "Chon Buri", "Thailand")
+    }
+
+    fn subscriptions() -> ManageSubscriptions<Arc<InMemoryStore>> {
+        ManageSubscriptions::new(Arc::new(InMemoryStore::new()))
+    }
+
+    fn levels(subscriptions: &[Subscription]) -> Vec<(String, AirQualityLevel)> {
+        let mut levels: Vec<_> = subscriptions
+            .iter()
+            .map(|s| (s.location.name.clone(), s.min_level))
+            .collect();
+        levels.sort_by(|a, b| a.0.cmp(&b.0));
+        levels
+    }
+
+    #[tokio::test]
+    async fn subscribing_again_replaces_the_level() {
+        let manage = subscriptions();
+
+        let subscription = manage
+            .subscribe(1, city("Ban Suan"), AirQualityLevel::Moderate)
+            .await
+            .unwrap();
+        assert_eq!(subscription.chat_id, 1);
+        assert_eq!(subscription.min_level, AirQualityLevel::Moderate);
+
+        manage
+            .subscribe(1, city("ban suan"), AirQualityLevel::Unhealthy)
+            .await
+            .unwrap();
+        assert_eq!(
+            levels(&manage.list(1).await.unwrap()),
+            vec![("ban suan".to_string(), AirQualityLevel::Unhealthy)]
+        );
+    }
+
+    #[tokio::test]
+    async fn chats_keep_their_own_subscriptions() {
+        let manage = subscriptions();
+        manage
+            .subscribe(1, city("Ban Suan"), AirQualityLevel::Moderate)
+            .await
+            .unwrap();
+        manage
+            .subscribe(2, city("Ban Suan"), AirQualityLevel::Hazardous)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            levels(&manage.list(2).await.unwrap()),
+            vec![("Ban Suan".to_string(), AirQualityLevel::Hazardous)]
+        );
+        assert_eq!(manage.all().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_from_one_place() {
+        let manage = subscriptions();
+        for place in ["Ban Suan", "Si Racha"] {
+            manage
+                .subscribe(1, city(place), AirQualityLevel::UnhealthyForSensitive)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            manage
+                .unsubscribe(1, Some(&city("Si Racha")))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            manage.unsubscribe(1, Some(&city("Pattaya"))).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            levels(&manage.list(1).await.unwrap()),
+            vec![(
+                "Ban Suan".to_string(),
+                AirQualityLevel::UnhealthyForSensitive
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_from_everything_leaves_other_chats() {
+        let manage = subscriptions();
+        for (chat_id, place) in [(1, "Ban Suan"), (1, "Si Racha"), (2, "Pattaya")] {
+            manage
+                .subscribe(chat_id, city(place), AirQualityLevel::Moderate)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(manage.unsubscribe(1, None).await.unwrap(), 2);
+        assert!(manage.list(1).await.unwrap().is_empty());
+        assert_eq!(manage.all().await.unwrap().len(), 1);
+    }
+}
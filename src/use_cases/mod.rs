@@ -1,5 +1,26 @@
+pub mod air_quality_history;
+pub mod alert_air_quality;
+pub mod broadcast_air_quality;
+pub mod chart_air_quality;
+pub mod chat_settings;
 pub mod check_air_quality;
+pub mod daily_digest;
+pub mod forecast_air_quality;
+pub mod manage_subscriptions;
 pub mod notify_air_quality;
+pub mod recommend_outdoor_time;
+pub mod scheduler;
+pub mod storage;
 
+pub use air_quality_history::AirQualityHistory;
+pub use alert_air_quality::AlertAirQuality;
+pub use broadcast_air_quality::BroadcastAirQuality;
+pub use chart_air_quality::ChartAirQuality;
+pub use chat_settings::ChatSettings;
 pub use check_air_quality::CheckAirQuality;
+pub use daily_digest::DailyDigest;
+pub use forecast_air_quality::ForecastAirQuality;
+pub use manage_subscriptions::ManageSubscriptions;
 pub use notify_air_quality::NotifyAirQuality;
+pub use recommend_outdoor_time::RecommendOutdoorTime;
+pub use scheduler::Scheduler;
@@ -1,10 +1,15 @@
-use crate::domain::models::{AirQualityData, AirQualityLevel};
-use anyhow::Result;
+use crate::domain::models::AirQualityData;
+use crate::presentation::{render_reading, MessageFormat};
+use crate::use_cases::chat_settings::ChatPreferences;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::Utc;
 
 #[async_trait]
 pub trait NotificationGateway: Send + Sync {
     async fn send(&self, channel_id: &str, message: &str) -> Result<()>;
+    /// Markup `send` expects messages in.
+    fn format(&self) -> MessageFormat;
 }
 
 pub struct NotifyAirQuality<N: NotificationGateway> {
@@ -27,34 +32,45 @@ impl<N: NotificationGateway> NotifyAirQuality<N> {
         Self { gateway }
     }
 
-    pub async fn execute(&self, channel_id: &str, data: &AirQualityData) -> Result<()> {
-        let message = self.format_message(data);
+    pub async fn execute(
+        &self,
+        channel_id: &str,
+        data: &AirQualityData,
+        preferences: &ChatPreferences,
+    ) -> Result<()> {
+        let message = self.format_message(data, preferences)?;
         self.gateway.send(channel_id, &message).await
     }
 
-    fn format_message(&self, data: &AirQualityData) -> String {
-        let level = AirQualityLevel::from_aqi(data.aqi);
-        let (city, state) = data.location.city_state();
-        let location_str = if state.is_empty() {
-            city
-        } else {
-            format!("{}, {}", city, state)
-        };
+    pub async fn execute_with_headline(
+        &self,
+        channel_id: &str,
+        headline: &str,
+        data: &AirQualityData,
+        preferences: &ChatPreferences,
+    ) -> Result<()> {
+        let message = format!(
+            "{}\n\n{}",
+            self.gateway.format().renderer().escape(headline),
+            self.format_message(data, preferences)?
+        );
+        self.gateway.send(channel_id, &message).await
+    }
 
-        format!(
-            "{} *{}*\n\n\
-            📍 {}\n\
-            ค่า AQI *{}* · ฝุ่น PM2.5 {} µg/m³\n\
-            อุณหภูมิ {}°C · ความชื้น {}%\n\n\
-            {}",
-            level.emoji(),
-            level.thai_description(),
-            location_str,
-            data.aqi,
-            data.pm25,
-            data.temperature,
-            data.humidity,
-            level.health_warning(),
+    fn format_message(
+        &self,
+        data: &AirQualityData,
+        preferences: &ChatPreferences,
+    ) -> Result<String> {
+        let standard = preferences.standard;
+        render_reading(
+            self.gateway.format().renderer(),
+            preferences.template.as_ref(),
+            data,
+            standard,
+            preferences.locale.catalogue(),
+            Utc::now(),
         )
+        .with_context(|| format!("No {} index for AQI {}", standard.slug(), data.aqi))
     }
 }
@@ -0,0 +1,308 @@
+use crate::domain::errors::AirQualityError;
+use crate::domain::models::{AirQualityData, Location};
+use crate::domain::recommendation::{Activity, Basis, Recommendation};
+use crate::use_cases::check_air_quality::ReadingRepository;
+use crate::use_cases::forecast_air_quality::ForecastRepository;
+use crate::use_cases::ForecastAirQuality;
+use chrono::{DateTime, Duration, DurationRound, FixedOffset, Timelike, Utc};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Picks the best upcoming hours to be outside for an activity.
+pub struct RecommendOutdoorTime<F: ForecastRepository, H: ReadingRepository> {
+    forecasts: ForecastAirQuality<F>,
+    readings: H,
+    timezone: FixedOffset,
+}
+
+impl<F: ForecastRepository, H: ReadingRepository> Clone for RecommendOutdoorTime<F, H>
+where
+    F: Clone,
+    H: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            forecasts: self.forecasts.clone(),
+            readings: self.readings.clone(),
+            timezone: self.timezone,
+        }
+    }
+}
+
+impl<F: ForecastRepository, H: ReadingRepository> RecommendOutdoorTime<F, H> {
+    /// How far back stored readings go when there is no forecast.
+    const HISTORY_DAYS: i64 = 7;
+
+    pub fn new(forecasts: ForecastAirQuality<F>, readings: H, timezone: FixedOffset) -> Self {
+        Self {
+            forecasts,
+            readings,
+            timezone,
+        }
+    }
+
+    /// Scores the next `Recommendation::HORIZON_HOURS` hours from the forecast.
+    /// Without one, each upcoming hour is assumed to look like the same hour of
+    /// the day over the past week; the forecast's error is returned only when
+    /// there is no history either.
+    pub async fn execute(
+        &self,
+        location: Location,
+        activity: Activity,
+        now: DateTime<Utc>,
+    ) -> Result<Recommendation, AirQualityError> {
+        let error = match self
+            .forecasts
+            .execute(location.clone(), Recommendation::HORIZON_HOURS)
+            .await
+        {
+            Ok(forecast) => {
+                return Ok(Recommendation::from_hours(
+                    location,
+                    activity,
+                    Basis::Forecast,
+                    &forecast.hours,
+                    self.timezone,
+                ));
+            }
+            Err(e) => e,
+        };
+        warn!(
+            "No forecast for {}, falling back to history: {}",
+            location.name, error
+        );
+
+        let readings = match self
+            .readings
+            .readings_since(&location, now - Duration::days(Self::HISTORY_DAYS))
+            .await
+        {
+            Ok(readings) => readings,
+            Err(e) => {
+                warn!("Failed to load history for {}: {:#}", location.name, e);
+                return Err(error);
+            }
+        };
+        let hours = self.project(&readings, now);
+        if hours.is_empty() {
+            return Err(error);
+        }
+
+        Ok(Recommendation::from_hours(
+            location,
+            activity,
+            Basis::History,
+            &hours,
+            self.timezone,
+        ))
+    }
+
+    /// Upcoming hours from the current one, each the average of the readings
+    /// taken at that local hour of the day. Hours never seen are left out.
+    fn project(
+        &self,
+        readings: &[(DateTime<Utc>, AirQualityData)],
+        now: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, AirQualityData)> {
+        let mut by_hour: BTreeMap<u32, Vec<&AirQualityData>> = BTreeMap::new();
+        for (at, data) in readings {
+            by_hour
+                .entry(at.with_timezone(&self.timezone).hour())
+                .or_default()
+                .push(data);
+        }
+
+        let start = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+        (0..i64::from(Recommendation::HORIZON_HOURS))
+            .map(|offset| start + Duration::hours(offset))
+            .filter_map(|at| {
+                let samples = by_hour.get(&at.with_timezone(&self.timezone).hour())?;
+                Some((at, typical(samples)?))
+            })
+            .collect()
+    }
+}
+
+/// The latest sample with AQI, PM2.5, temperature and humidity averaged.
+fn typical(samples: &[&AirQualityData]) -> Option<AirQualityData> {
+    let average = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    let mut data = (*samples.last()?).clone();
+    data.aqi = average(samples.iter().map(|d| f64::from(d.aqi)).collect())?.round() as i32;
+    data.pm25 = average(samples.iter().map(|d| d.pm25).collect())?;
+    data.weather.temperature = average(
+        samples
+            .iter()
+            .filter_map(|d| d.weather.temperature.map(f64::from))
+            .collect(),
+    )
+    .map(|t| t.round() as i32);
+    data.weather.humidity = average(
+        samples
+            .iter()
+            .filter_map(|d| d.weather.humidity.map(f64::from))
+            .collect(),
+    )
+    .map(|h| h.round() as i32);
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::memory::InMemoryStore;
+    use crate::use_cases::forecast_air_quality::FixedForecast;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn bangkok() -> FixedOffset {
+        FixedOffset::east_opt(7 * 3600).unwrap()
+    }
+
+    /// `hour` o'clock Bangkok time on `day` January 2025.
+    fn local(day: u32, hour: u32) -> DateTime<Utc> {
+        bangkok()
+            .with_ymd_and_hms(2025, 1, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn location() -> Location {
+        Location::from_city("Ban Suan", "Chon Buri", "Thailand")
+    }
+
+    fn reading(aqi: i32) -> AirQualityData {
+        AirQualityData::sample(location(), aqi, 20.0)
+    }
+
+    struct FailingHistory;
+
+    #[async_trait]
+    impl ReadingRepository for FailingHistory {
+        async fn save_reading(
+            &self,
+            _location: &Location,
+            _recorded_at: DateTime<Utc>,
+            _data: &AirQualityData,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!("database is locked")
+        }
+
+        async fn readings_since(
+            &self,
+            _location: &Location,
+            _since: DateTime<Utc>,
+        ) -> anyhow::Result<Vec<(DateTime<Utc>, AirQualityData)>> {
+            anyhow::bail!("database is locked")
+        }
+    }
+
+    fn use_case<H: ReadingRepository>(
+        forecast: Option<Vec<(DateTime<Utc>, AirQualityData)>>,
+        history: H,
+    ) -> RecommendOutdoorTime<FixedForecast, H> {
+        RecommendOutdoorTime::new(
+            ForecastAirQuality::new(FixedForecast { hours: forecast }),
+            history,
+            bangkok(),
+        )
+    }
+
+    /// Two mornings of readings: hazy at 06:00 and 08:00, clearer at 07:00
+    /// and cleanest at 09:00.
+    async fn two_mornings() -> Arc<InMemoryStore> {
+        let store = Arc::new(InMemoryStore::new());
+        for (day, aqis) in [(8, [190, 30, 100, 20]), (9, [210, 50, 120, 40])] {
+            for (hour, aqi) in (6..).zip(aqis) {
+                store
+                    .save_reading(&location(), local(day, hour), &reading(aqi))
+                    .await
+                    .unwrap();
+            }
+        }
+        store
+    }
+
+    fn now() -> DateTime<Utc> {
+        local(10, 6) + Duration::minutes(30)
+    }
+
+    #[tokio::test]
+    async fn uses_the_forecast_when_there_is_one() {
+        let forecast = (7..10)
+            .map(|hour| (local(10, hour), reading(hour as i32 * 10)))
+            .collect();
+        let recommendation = use_case(Some(forecast), two_mornings().await)
+            .execute(location(), Activity::Running, now())
+            .await
+            .unwrap();
+
+        assert_eq!(recommendation.basis, Basis::Forecast);
+        assert_eq!(recommendation.windows[0].start, local(10, 7));
+        assert_eq!(recommendation.windows[0].average_aqi, 70);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_same_hours_in_the_past_week() {
+        let recommendation = use_case(None, two_mornings().await)
+            .execute(location(), Activity::Running, now())
+            .await
+            .unwrap();
+
+        assert_eq!(recommendation.basis, Basis::History);
+        let windows: Vec<(DateTime<Utc>, i32)> = recommendation
+            .windows
+            .iter()
+            .map(|w| (w.start, w.average_aqi))
+            .collect();
+        assert_eq!(
+            windows,
+            vec![(local(10, 9), 30), (local(10, 7), 40), (local(10, 8), 110)]
+        );
+    }
+
+    #[tokio::test]
+    async fn history_is_averaged_per_hour_of_the_day() {
+        let store = two_mornings().await;
+        let use_case = use_case(None, store.clone());
+        let readings = store
+            .readings_since(&location(), now() - Duration::days(7))
+            .await
+            .unwrap();
+
+        let hours = use_case.project(&readings, now());
+
+        let projected: Vec<(DateTime<Utc>, i32)> =
+            hours.iter().map(|(at, data)| (*at, data.aqi)).collect();
+        assert_eq!(
+            projected,
+            vec![
+                (local(10, 6), 200),
+                (local(10, 7), 40),
+                (local(10, 8), 110),
+                (local(10, 9), 30),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn without_forecast_or_history_the_forecast_error_is_returned() {
+        let empty = use_case(None, Arc::new(InMemoryStore::new()))
+            .execute(location(), Activity::Cycling, now())
+            .await;
+        assert!(matches!(
+            empty,
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "no model for Ban Suan"
+        ));
+
+        let failing = use_case(None, FailingHistory)
+            .execute(location(), Activity::Cycling, now())
+            .await;
+        assert!(matches!(
+            failing,
+            Err(AirQualityError::UpstreamUnavailable(message)) if message == "no model for Ban Suan"
+        ));
+    }
+}
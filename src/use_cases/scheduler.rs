@@ -0,0 +1,137 @@
+use crate::domain::schedule::CronSchedule;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use tracing::{error, info, warn};
+
+/// Source of time for the scheduler, so ticks can be driven by a fake clock.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+    async fn sleep_until(&self, deadline: DateTime<Utc>);
+}
+
+#[async_trait]
+pub trait ScheduledJob: Send + Sync {
+    fn name(&self) -> &str;
+    async fn run(&self) -> Result<()>;
+}
+
+pub struct Scheduler<C: Clock> {
+    clock: C,
+    timezone: FixedOffset,
+}
+
+impl<C: Clock> Scheduler<C> {
+    pub fn new(clock: C, timezone: FixedOffset) -> Self {
+        Self { clock, timezone }
+    }
+
+    /// Runs `job` at every tick of `schedule` until the schedule has no further ticks.
+    pub async fn run<J: ScheduledJob>(&self, schedule: &CronSchedule, job: &J) {
+        info!("Scheduling {} with '{}'", job.name(), schedule);
+
+        loop {
+            let now = self.clock.now().with_timezone(&self.timezone);
+            let Some(next) = schedule.next_after(&now) else {
+                warn!(
+                    "Schedule '{}' has no upcoming runs, stopping {}",
+                    schedule,
+                    job.name()
+                );
+                return;
+            };
+
+            info!("Next {} run at {}", job.name(), next);
+            self.clock.sleep_until(next.with_timezone(&Utc)).await;
+
+            if let Err(e) = job.run().await {
+                error!("{} failed: {:#}", job.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::clock::FakeClock;
+    use std::sync::Mutex;
+
+    struct RecordingJob {
+        clock: FakeClock,
+        runs: Mutex<Vec<DateTime<Utc>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ScheduledJob for RecordingJob {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn run(&self) -> Result<()> {
+            self.runs.lock().unwrap().push(self.clock.now());
+            if self.fail {
+                anyhow::bail!("failed on purpose");
+            }
+            Ok(())
+        }
+    }
+
+    async fn run_ticks(expression: &str, start: &str, ticks: usize, fail: bool) -> Vec<String> {
+        let clock = FakeClock::new(DateTime::parse_from_rfc3339(start).unwrap().to_utc());
+        let timezone = FixedOffset::east_opt(7 * 3600).unwrap();
+        let scheduler = Scheduler::new(clock.clone(), timezone);
+        let schedule: CronSchedule = expression.parse().unwrap();
+        let job = RecordingJob {
+            clock,
+            runs: Mutex::new(Vec::new()),
+            fail,
+        };
+
+        // Checked first, so the scheduler can't slip in an extra tick.
+        tokio::select! {
+            biased;
+            _ = async {
+                while job.runs.lock().unwrap().len() < ticks {
+                    tokio::task::yield_now().await;
+                }
+            } => {}
+            _ = scheduler.run(&schedule, &job) => {}
+        }
+
+        let runs = job.runs.lock().unwrap();
+        runs.iter()
+            .map(|at| at.with_timezone(&timezone).to_rfc3339())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn runs_the_job_at_each_tick_in_the_timezone() {
+        let runs = run_ticks("0 0 */4 * * *", "2025-01-01T00:30:00Z", 3, false).await;
+        assert_eq!(
+            runs,
+            [
+                "2025-01-01T08:00:00+07:00",
+                "2025-01-01T12:00:00+07:00",
+                "2025-01-01T16:00:00+07:00",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_going_after_a_failed_run() {
+        let runs = run_ticks("0 0 7 * * *", "2025-01-01T00:30:00Z", 2, true).await;
+        assert_eq!(
+            runs,
+            ["2025-01-02T07:00:00+07:00", "2025-01-03T07:00:00+07:00"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_when_the_schedule_has_no_ticks() {
+        let runs = run_ticks("0 0 0 30 2 *", "2025-01-01T00:00:00Z", 1, false).await;
+        assert!(runs.is_empty());
+    }
+}
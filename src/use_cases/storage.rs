@@ -0,0 +1,46 @@
+use crate::use_cases::alert_air_quality::AlertStateRepository;
+use crate::use_cases::chat_settings::ChatSettingsRepository;
+use crate::use_cases::check_air_quality::ReadingRepository;
+use crate::use_cases::manage_subscriptions::SubscriptionRepository;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Calls spent against a metered API's monthly quota, so the count survives
+/// redeploys. Only the latest month is kept for each API.
+#[async_trait]
+pub trait QuotaUsageRepository: Send + Sync {
+    /// Calls recorded for `api` in `month` as `(year, month)`; zero when none were.
+    async fn quota_used(&self, api: &str, month: (i32, u32)) -> Result<u32>;
+    async fn save_quota_used(&self, api: &str, month: (i32, u32), used: u32) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: QuotaUsageRepository + ?Sized> QuotaUsageRepository for Arc<T> {
+    async fn quota_used(&self, api: &str, month: (i32, u32)) -> Result<u32> {
+        (**self).quota_used(api, month).await
+    }
+
+    async fn save_quota_used(&self, api: &str, month: (i32, u32), used: u32) -> Result<()> {
+        (**self).save_quota_used(api, month, used).await
+    }
+}
+
+/// Everything the service persists between restarts, implemented by each storage backend.
+pub trait Storage:
+    SubscriptionRepository
+    + ReadingRepository
+    + AlertStateRepository
+    + ChatSettingsRepository
+    + QuotaUsageRepository
+{
+}
+
+impl<T> Storage for T where
+    T: SubscriptionRepository
+        + ReadingRepository
+        + AlertStateRepository
+        + ChatSettingsRepository
+        + QuotaUsageRepository
+{
+}